@@ -1,11 +1,19 @@
+mod my_actions;
 mod my_api;
+mod my_clipboard_watch;
 mod my_command;
 mod my_config;
+mod my_dictionary;
 mod my_events;
+mod my_ocr;
+mod my_profiles;
 mod my_rdev;
 mod my_shortcut;
+mod my_stt;
 mod my_test;
+mod my_tools;
 mod my_tray;
+mod my_tts;
 mod my_types;
 mod my_windows;
 mod states;
@@ -33,24 +41,76 @@ pub fn run() {
         }))
         .manage(std::sync::Mutex::new(setting_states::AppState::default()))
         .manage(my_api::manager::GlobalAPIManager(api_manager))
+        .manage(my_actions::ActionRegistry::with_builtin_actions())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .on_page_load(|webview, payload| {
+            if matches!(payload.event(), tauri::webview::PageLoadEvent::Started) {
+                my_stt::on_window_unloaded(webview.label());
+            }
+        })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                my_stt::on_window_unloaded(window.label());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
-            my_command::toggle_auto_close_translate,
-            my_command::get_auto_close_translate_state,
+            my_command::toggle_translate_pinned,
+            my_command::get_translate_pinned_state,
             my_command::toggle_auto_speak,
             my_command::get_auto_speak_state,
+            my_command::get_settings,
+            my_command::set_settings,
+            my_command::toggle_clipboard_watch,
+            my_command::get_clipboard_watch_state,
             my_command::command_window_translate_show,
             my_command::close_main_window,
             my_command::chat_stream,
             my_command::detect_language,
             my_command::get_histories,
+            my_command::get_gesture_config,
+            my_command::set_gesture_config,
+            my_command::write_back,
             my_shortcut::register_hotkey_okey_ai,
+            my_shortcut::register_hotkey_write_back,
+            my_shortcut::register_hotkey,
+            my_shortcut::unregister_hotkey,
+            my_command::list_actions,
+            my_command::list_quick_actions,
+            my_command::list_custom_actions,
+            my_command::save_custom_action,
+            my_command::delete_custom_action,
+            my_command::run_quick_action,
+            my_command::resize_translate_bubble,
+            my_command::list_profiles,
+            my_command::create_profile,
+            my_command::delete_profile,
+            my_command::switch_profile,
+            my_command::export_profile,
+            my_command::import_profile,
             my_api::commands::switch_model,
             my_api::commands::get_current_model,
             my_api::commands::list_models,
+            my_api::commands::list_providers_missing_key,
+            my_tts::commands::tts_speak,
+            my_tts::commands::tts_stop,
+            my_tts::commands::tts_pause,
+            my_tts::commands::tts_resume,
+            my_tts::commands::tts_state,
+            my_tts::commands::tts_clear_cache,
+            my_tts::commands::get_tts_config,
+            my_tts::commands::set_tts_config,
+            my_stt::commands::transcribe_audio,
+            my_stt::commands::recording_window_ready,
+            my_stt::commands::get_stt_config,
+            my_stt::commands::set_stt_config,
+            my_tools::commands::get_tools_config,
+            my_tools::commands::set_tools_config,
+            my_dictionary::commands::get_dictionary_config,
+            my_dictionary::commands::set_dictionary_config,
+            my_dictionary::commands::list_offline_dictionaries,
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -64,14 +124,21 @@ pub fn run() {
 
             // 初始化 API 管理器
             my_api::setup_api_manager(&app.handle())?;
+            setting_states::load_settings(&app.handle())?;
+            my_tts::setup_tts(&app.handle())?;
             // my_modifier_keys::init_global_input_listener(&app.handle())?;
+            my_actions::load_custom_actions(&app.handle())?;
             my_shortcut::init_shortcuts(&app.handle())?;
 
             my_tray::create_tray(&app.handle())?;
             crate::my_test::test();
             crate::my_rdev::init_global_input_listener(&app.handle())?;
+            utils::calculate_text_width::init_bubble_font(&app.handle());
             // ✅ 初始化翻译管理器
             setup_translation_manager(app)?;
+            my_tools::setup_tools(&app.handle())?;
+            my_dictionary::setup_dictionary(&app.handle())?;
+            my_clipboard_watch::init_clipboard_watch(&app.handle())?;
             // 在 macOS 上隐藏 Dock 栏图标
             #[cfg(target_os = "macos")]
             {
@@ -0,0 +1,322 @@
+use crate::my_api::manager::GlobalAPIManager;
+use crate::my_config::{self, ActionOutput, CustomAction};
+use crate::my_events::event_names;
+use crate::utils::quick_actions::QuickAction;
+use crate::utils::{custom_actions, quick_actions, text_translation, write_back};
+use crate::{my_clipboard_watch, my_ocr, my_shortcut, my_windows};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub mod action_ids {
+    pub const TRANSLATE_WINDOW: &str = "translate_window";
+    pub const TRANSLATE_BUBBLE: &str = "translate_bubble";
+    pub const WRITE_BACK: &str = "write_back";
+    pub const TOGGLE_CLIPBOARD_WATCH: &str = "toggle_clipboard_watch";
+    pub const OPEN_HISTORY: &str = "open_history";
+    pub const SHOW_ABOUT: &str = "show_about";
+    pub const OCR_TRANSLATE: &str = "ocr_translate";
+}
+
+type ActionHandler = Arc<dyn Fn(&AppHandle) + Send + Sync>;
+
+/// 可被快捷键、按键手势和托盘菜单绑定的动作
+#[derive(Clone)]
+pub struct Action {
+    pub id: String,
+    pub label: String,
+    /// 快捷键松开时才触发（需要模拟按键的动作，避免与按住的修饰键叠加）
+    pub on_release: bool,
+    /// 来自配置的自定义动作
+    pub user_defined: bool,
+    handler: ActionHandler,
+}
+
+impl Action {
+    pub fn new<F>(id: &str, label: &str, handler: F) -> Self
+    where
+        F: Fn(&AppHandle) + Send + Sync + 'static,
+    {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            on_release: false,
+            user_defined: false,
+            handler: Arc::new(handler),
+        }
+    }
+
+    pub fn on_release(mut self) -> Self {
+        self.on_release = true;
+        self
+    }
+
+    pub fn user_defined(mut self) -> Self {
+        self.user_defined = true;
+        self
+    }
+
+    pub fn run(&self, app: &AppHandle) {
+        (self.handler)(app);
+    }
+}
+
+/// 返回给前端的动作信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionInfo {
+    pub id: String,
+    pub label: String,
+    pub hot_key: Option<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct ActionRegistry(Arc<RwLock<BTreeMap<String, Action>>>);
+
+impl ActionRegistry {
+    pub fn with_builtin_actions() -> Self {
+        let registry = Self::default();
+        registry.register(Action::new(
+            action_ids::TRANSLATE_WINDOW,
+            "Translate to window",
+            text_translation::translate_selected_text,
+        ));
+        registry.register(Action::new(
+            action_ids::TRANSLATE_BUBBLE,
+            "Translate to bubble",
+            translate_to_bubble,
+        ));
+        registry.register(
+            Action::new(
+                action_ids::WRITE_BACK,
+                "Replace selection with translation",
+                write_back::write_back_last_translation,
+            )
+            .on_release(),
+        );
+        registry.register(Action::new(
+            action_ids::TOGGLE_CLIPBOARD_WATCH,
+            "Toggle clipboard watch",
+            |app| {
+                if let Err(e) = my_clipboard_watch::toggle_clipboard_watch(app) {
+                    eprintln!("切换剪贴板监听失败: {}", e);
+                }
+            },
+        ));
+        registry.register(Action::new(
+            action_ids::OCR_TRANSLATE,
+            "Translate screen region",
+            my_ocr::ocr_translate,
+        ));
+        registry.register(Action::new(
+            action_ids::OPEN_HISTORY,
+            "Open history",
+            open_history,
+        ));
+        registry.register(Action::new(
+            action_ids::SHOW_ABOUT,
+            "About",
+            my_windows::window_about_show,
+        ));
+        for quick_action in QuickAction::ALL {
+            registry.register(Action::new(
+                quick_action.id(),
+                quick_action.label(),
+                move |app| {
+                    let app_clone = app.clone();
+                    with_bubble(app, move || {
+                        quick_actions::run_on_selection_bubble(&app_clone, quick_action)
+                    });
+                },
+            ));
+        }
+        registry
+    }
+
+    /// 注册动作，同 id 的动作会被替换
+    pub fn register(&self, action: Action) {
+        self.0.write().unwrap().insert(action.id.clone(), action);
+    }
+
+    pub fn unregister(&self, id: &str) -> Option<Action> {
+        self.0.write().unwrap().remove(id)
+    }
+
+    /// id 是否被内置动作占用
+    pub fn is_builtin(&self, id: &str) -> bool {
+        self.get(id).is_some_and(|action| !action.user_defined)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Action> {
+        self.0.read().unwrap().get(resolve_action_id(id)).cloned()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.0.read().unwrap().contains_key(resolve_action_id(id))
+    }
+
+    pub fn list(&self) -> Vec<Action> {
+        self.0.read().unwrap().values().cloned().collect()
+    }
+}
+
+/// 旧版配置中的快捷键名称映射到动作 id
+pub fn resolve_action_id(name: &str) -> &str {
+    match name {
+        "okey_ai" => action_ids::TRANSLATE_WINDOW,
+        other => other,
+    }
+}
+
+/// 把自定义动作注册为可绑定的动作
+fn register_custom_action(registry: &ActionRegistry, custom_action: CustomAction) {
+    let id = custom_action.id.clone();
+    let name = custom_action.name.clone();
+    let output = custom_action.output;
+    let action = Action::new(&id, &name, move |app| {
+        let custom_action = custom_action.clone();
+        if custom_action.output == ActionOutput::Bubble {
+            let app_clone = app.clone();
+            with_bubble(app, move || {
+                custom_actions::run_on_selection(&app_clone, custom_action)
+            });
+        } else {
+            custom_actions::run_on_selection(app, custom_action);
+        }
+    });
+    // 回写需要等快捷键松开后再模拟粘贴
+    let action = if output == ActionOutput::WriteBack {
+        action.on_release()
+    } else {
+        action
+    };
+    registry.register(action.user_defined());
+}
+
+/// 注册配置中的自定义动作，无效的动作跳过；已加载的自定义动作会先被移除
+pub fn load_custom_actions(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let registry = app.state::<ActionRegistry>();
+    for action in registry.list() {
+        if action.user_defined {
+            registry.unregister(&action.id);
+        }
+    }
+    for custom_action in my_config::get_global_config(app)?.custom_actions {
+        // API 管理器异步初始化，启动时不校验服务商
+        if let Err(e) = custom_actions::validate_custom_action(
+            &custom_action,
+            |id| registry.is_builtin(id),
+            None,
+        ) {
+            eprintln!("跳过无效的自定义动作 {}: {}", custom_action.id, e);
+            continue;
+        }
+        register_custom_action(&registry, custom_action);
+    }
+    Ok(())
+}
+
+/// 新增或更新自定义动作，`hot_key` 为空时解除快捷键绑定
+pub async fn save_custom_action(
+    app: &AppHandle,
+    custom_action: CustomAction,
+    hot_key: Option<String>,
+) -> Result<(), String> {
+    let providers = {
+        let api_manager = app.state::<GlobalAPIManager>();
+        let manager = api_manager.0.read().await;
+        manager.list_available_models().await
+    };
+    let registry = app.state::<ActionRegistry>();
+    custom_actions::validate_custom_action(
+        &custom_action,
+        |id| registry.is_builtin(id),
+        Some(providers.as_slice()),
+    )?;
+
+    let mut global_config =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    match global_config
+        .custom_actions
+        .iter_mut()
+        .find(|a| a.id == custom_action.id)
+    {
+        Some(existing) => *existing = custom_action.clone(),
+        None => global_config.custom_actions.push(custom_action.clone()),
+    }
+    my_config::set_global_config(app, &global_config)
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    let id = custom_action.id.clone();
+    register_custom_action(&registry, custom_action);
+
+    let current_hot_key = global_config
+        .shortcuts
+        .iter()
+        .find(|s| s.name == id)
+        .map(|s| s.hot_key.clone());
+    match hot_key {
+        Some(hot_key) if current_hot_key.as_deref() != Some(hot_key.as_str()) => {
+            my_shortcut::register_hotkey(app.clone(), id, hot_key)
+        }
+        Some(_) => Ok(()),
+        None => my_shortcut::unregister_hotkey(app.clone(), id),
+    }
+}
+
+/// 删除自定义动作及其快捷键
+pub fn delete_custom_action(app: &AppHandle, id: &str) -> Result<(), String> {
+    let mut global_config =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    let len = global_config.custom_actions.len();
+    global_config.custom_actions.retain(|a| a.id != id);
+    if global_config.custom_actions.len() == len {
+        return Err(format!("未找到自定义动作: {}", id));
+    }
+    my_config::set_global_config(app, &global_config)
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    my_shortcut::unregister_hotkey(app.clone(), id.to_string())?;
+    app.state::<ActionRegistry>().unregister(id);
+    Ok(())
+}
+
+/// 按 id 执行动作
+pub fn run_action(app: &AppHandle, id: &str) -> Result<(), String> {
+    let action = app
+        .state::<ActionRegistry>()
+        .get(id)
+        .ok_or_else(|| format!("未知动作: {}", id))?;
+    action.run(app);
+    Ok(())
+}
+
+fn translate_to_bubble(app: &AppHandle) {
+    let app_clone = app.clone();
+    with_bubble(app, move || {
+        text_translation::translate_selected_text_bubble(&app_clone);
+    });
+}
+
+/// 确保气泡窗口已创建后再执行，翻译窗口正在显示时直接执行，结果显示在翻译窗口中
+fn with_bubble<F>(app: &AppHandle, f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    if my_windows::is_translate_window_visible(app) {
+        f();
+    } else {
+        my_windows::window_translate_bubble_show(app, Some(f));
+    }
+}
+
+fn open_history(app: &AppHandle) {
+    let app_clone = app.clone();
+    my_windows::window_translate_show(
+        app,
+        Some(move || {
+            let _ = app_clone.emit(event_names::OPEN_HISTORY, {});
+        }),
+    );
+}
@@ -1,13 +1,21 @@
-use crate::my_api::manager::GlobalAPIManager;
-use tauri::State;
+use crate::my_api::{self, manager::GlobalAPIManager};
+use crate::my_config;
+use tauri::{AppHandle, State};
 
+/// 切换当前服务商，并保存到当前配置方案
 #[tauri::command]
 pub async fn switch_model(
+    app: AppHandle,
     model_name: String,
     state: State<'_, GlobalAPIManager>,
 ) -> Result<(), String> {
     let manager = state.0.read().await;
-    manager.set_current_model(model_name).await
+    manager.set_current_model(model_name.clone()).await?;
+
+    let mut global_config =
+        my_config::get_global_config(&app).map_err(|e| format!("获取配置失败: {}", e))?;
+    global_config.active_provider = Some(model_name);
+    my_config::set_global_config(&app, &global_config).map_err(|e| format!("保存配置失败: {}", e))
 }
 
 #[tauri::command]
@@ -21,3 +29,11 @@ pub async fn list_models(state: State<'_, GlobalAPIManager>) -> Result<Vec<Strin
     let manager = state.0.read().await;
     Ok(manager.list_available_models().await)
 }
+
+/// 缺少密钥的服务商，如导入的方案修改了服务地址、没有沿用已有密钥时，页面提示用户填写
+#[tauri::command]
+pub fn list_providers_missing_key(app: AppHandle) -> Result<Vec<String>, String> {
+    my_config::get_global_config(&app)
+        .map(|config| my_api::providers_missing_key(&config.providers))
+        .map_err(|e| format!("获取配置失败: {}", e))
+}
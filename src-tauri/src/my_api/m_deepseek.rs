@@ -1,6 +1,6 @@
 use crate::my_api::traits::{
     APIConfig, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse,
-    ChatMessageDelta, ChoiceDelta, LLMClient,
+    ChatMessageDelta, ChoiceDelta, ContentFormat, LLMClient, ToolCallAssembler, ToolCallDelta,
 };
 use futures::stream::{BoxStream, StreamExt};
 use serde::{Deserialize, Serialize};
@@ -22,6 +22,10 @@ impl DeepSeekClient {
 }
 
 impl LLMClient for DeepSeekClient {
+    fn default_model(&self) -> &str {
+        &self.config.model
+    }
+
     fn chat_completion<'a>(
         &'a self,
         request: &'a ChatCompletionRequest,
@@ -33,6 +37,7 @@ impl LLMClient for DeepSeekClient {
 
             let mut request = request.clone();
             request.stream = Some(false); // Ensure stream is false for non-streaming requests
+            request.adapt_content(ContentFormat::TextOnly)?;
 
             let json_body = serde_json::to_string(&request)
                 .map_err(|e| format!("Failed to serialize request: {}", e))?;
@@ -83,6 +88,7 @@ impl LLMClient for DeepSeekClient {
 
             let mut request = request.clone();
             request.stream = Some(true); // Enable streaming
+            request.adapt_content(ContentFormat::TextOnly)?;
 
             let json_body = serde_json::to_string(&request)
                 .map_err(|e| format!("Failed to serialize request: {}", e))?;
@@ -116,6 +122,7 @@ impl LLMClient for DeepSeekClient {
             // Spawn a task to process the stream and send chunks to the receiver
             let _handle = tauri::async_runtime::spawn(async move {
                 let mut buffer = String::new();
+                let mut assembler = ToolCallAssembler::default();
                 let mut stream = byte_stream;
 
                 while let Some(chunk_result) = stream.next().await {
@@ -164,10 +171,12 @@ impl LLMClient for DeepSeekClient {
                                                             .into_iter()
                                                             .map(|choice| ChoiceDelta {
                                                                 index: choice.index,
-                                                                delta: ChatMessageDelta {
-                                                                    role: choice.delta.role,
-                                                                    content: choice.delta.content,
-                                                                },
+                                                                tool_calls: assembler.push(
+                                                                    choice.index,
+                                                                    &choice.delta.tool_calls,
+                                                                    &choice.finish_reason,
+                                                                ),
+                                                                delta: choice.delta.into(),
                                                                 finish_reason: choice.finish_reason,
                                                             })
                                                             .collect(),
@@ -246,4 +255,36 @@ struct DeepSeekStreamChoice {
 struct DeepSeekStreamDelta {
     role: Option<String>,
     content: Option<String>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+impl From<DeepSeekStreamDelta> for ChatMessageDelta {
+    fn from(delta: DeepSeekStreamDelta) -> Self {
+        ChatMessageDelta {
+            role: delta.role,
+            content: delta.content,
+            reasoning_content: delta.reasoning_content,
+            tool_calls: delta.tool_calls,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reasoning_delta() {
+        let chunk: DeepSeekStreamResponse = serde_json::from_str(
+            r#"{"id":"1","object":"chat.completion.chunk","created":0,"model":"deepseek-reasoner",
+                "choices":[{"index":0,"delta":{"content":null,"reasoning_content":"先分析"},"finish_reason":null}]}"#,
+        )
+        .unwrap();
+        let delta: ChatMessageDelta = chunk.choices.into_iter().next().unwrap().delta.into();
+        assert_eq!(delta.content, None);
+        assert_eq!(delta.reasoning_content.as_deref(), Some("先分析"));
+    }
 }
@@ -1,6 +1,6 @@
 use crate::my_api::traits::{
     APIConfig, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse,
-    ChatMessageDelta, ChoiceDelta, LLMClient,
+    ChatMessageDelta, ChoiceDelta, ContentFormat, LLMClient, ToolCallAssembler, ToolCallDelta,
 };
 use futures::stream::{BoxStream, StreamExt};
 use serde::{Deserialize, Serialize};
@@ -22,6 +22,10 @@ impl OpenAIClient {
 }
 
 impl LLMClient for OpenAIClient {
+    fn default_model(&self) -> &str {
+        &self.config.model
+    }
+
     fn chat_completion<'a>(
         &'a self,
         request: &'a ChatCompletionRequest,
@@ -33,6 +37,7 @@ impl LLMClient for OpenAIClient {
 
             let mut request = request.clone();
             request.stream = Some(false); // Ensure stream is false for non-streaming requests
+            request.adapt_content(ContentFormat::OpenAI)?;
 
             let json_body = serde_json::to_string(&request)
                 .map_err(|e| format!("Failed to serialize request: {}", e))?;
@@ -83,6 +88,7 @@ impl LLMClient for OpenAIClient {
 
             let mut request = request.clone();
             request.stream = Some(true); // Enable streaming
+            request.adapt_content(ContentFormat::OpenAI)?;
 
             let json_body = serde_json::to_string(&request)
                 .map_err(|e| format!("Failed to serialize request: {}", e))?;
@@ -116,6 +122,7 @@ impl LLMClient for OpenAIClient {
             // Spawn a task to process the stream and send chunks to the receiver
             let _handle = tauri::async_runtime::spawn(async move {
                 let mut buffer = String::new();
+                let mut assembler = ToolCallAssembler::default();
                 let mut stream = byte_stream;
 
                 while let Some(chunk_result) = stream.next().await {
@@ -164,10 +171,12 @@ impl LLMClient for OpenAIClient {
                                                             .into_iter()
                                                             .map(|choice| ChoiceDelta {
                                                                 index: choice.index,
-                                                                delta: ChatMessageDelta {
-                                                                    role: choice.delta.role,
-                                                                    content: choice.delta.content,
-                                                                },
+                                                                tool_calls: assembler.push(
+                                                                    choice.index,
+                                                                    &choice.delta.tool_calls,
+                                                                    &choice.finish_reason,
+                                                                ),
+                                                                delta: choice.delta.into(),
                                                                 finish_reason: choice.finish_reason,
                                                             })
                                                             .collect(),
@@ -246,4 +255,19 @@ struct OpenAIStreamChoice {
 struct OpenAIStreamDelta {
     role: Option<String>,
     content: Option<String>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+impl From<OpenAIStreamDelta> for ChatMessageDelta {
+    fn from(delta: OpenAIStreamDelta) -> Self {
+        ChatMessageDelta {
+            role: delta.role,
+            content: delta.content,
+            reasoning_content: delta.reasoning_content,
+            tool_calls: delta.tool_calls,
+        }
+    }
 }
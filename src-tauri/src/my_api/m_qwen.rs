@@ -1,6 +1,6 @@
 use crate::my_api::traits::{
     APIConfig, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse,
-    ChatMessageDelta, ChoiceDelta, LLMClient,
+    ChatMessageDelta, ChoiceDelta, ContentFormat, LLMClient, ToolCallAssembler, ToolCallDelta,
 };
 use crate::utils::chat_message::ChatMessage;
 use futures::stream::{BoxStream, StreamExt};
@@ -23,6 +23,10 @@ impl QwenClient {
 }
 
 impl LLMClient for QwenClient {
+    fn default_model(&self) -> &str {
+        &self.config.model
+    }
+
     fn chat_completion<'a>(
         &'a self,
         request: &'a ChatCompletionRequest,
@@ -37,6 +41,7 @@ impl LLMClient for QwenClient {
 
             let mut request = request.clone();
             request.stream = Some(false); // Ensure stream is false for non-streaming requests
+            request.adapt_content(ContentFormat::NoImageDetail)?;
 
             let json_body = serde_json::to_string(&request)
                 .map_err(|e| format!("Failed to serialize request: {}", e))?;
@@ -110,6 +115,7 @@ impl LLMClient for QwenClient {
 
             let mut request = request.clone();
             request.stream = Some(true); // Enable streaming
+            request.adapt_content(ContentFormat::NoImageDetail)?;
 
             let json_body = serde_json::to_string(&request)
                 .map_err(|e| format!("Failed to serialize request: {}", e))?;
@@ -143,6 +149,7 @@ impl LLMClient for QwenClient {
             // Spawn a task to process the stream and send chunks to the receiver
             let _handle = tauri::async_runtime::spawn(async move {
                 let mut buffer = String::new();
+                let mut assembler = ToolCallAssembler::default();
                 let mut stream = byte_stream;
 
                 while let Some(chunk_result) = stream.next().await {
@@ -191,10 +198,12 @@ impl LLMClient for QwenClient {
                                                             .into_iter()
                                                             .map(|choice| ChoiceDelta {
                                                                 index: choice.index,
-                                                                delta: ChatMessageDelta {
-                                                                    role: choice.delta.role,
-                                                                    content: choice.delta.content,
-                                                                },
+                                                                tool_calls: assembler.push(
+                                                                    choice.index,
+                                                                    &choice.delta.tool_calls,
+                                                                    &choice.finish_reason,
+                                                                ),
+                                                                delta: choice.delta.into(),
                                                                 finish_reason: choice.finish_reason,
                                                             })
                                                             .collect(),
@@ -283,6 +292,21 @@ struct QwenStreamChoice {
 struct QwenStreamDelta {
     role: Option<String>,
     content: Option<String>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+impl From<QwenStreamDelta> for ChatMessageDelta {
+    fn from(delta: QwenStreamDelta) -> Self {
+        ChatMessageDelta {
+            role: delta.role,
+            content: delta.content,
+            reasoning_content: delta.reasoning_content,
+            tool_calls: delta.tool_calls,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
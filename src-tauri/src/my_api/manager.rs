@@ -48,13 +48,23 @@ impl APIManager {
     pub async fn chat_completion(
         &self,
         request: &ChatCompletionRequest<'_>,
+    ) -> Result<ChatCompletionResponse, String> {
+        self.chat_completion_with(None, request).await
+    }
+
+    /// 使用指定的服务商发送请求，未指定时使用当前模型
+    pub async fn chat_completion_with(
+        &self,
+        provider: Option<&str>,
+        request: &ChatCompletionRequest<'_>,
     ) -> Result<ChatCompletionResponse, String> {
         let current_model = self.current_model.read().await;
+        let provider = provider.unwrap_or(current_model.as_str());
         let clients = self.clients.read().await;
 
         let client = clients
-            .get(&*current_model)
-            .ok_or_else(|| format!("No client configured for model: {}", current_model))?;
+            .get(provider)
+            .ok_or_else(|| format!("No client configured for model: {}", provider))?;
 
         // Call the client's chat_completion method which returns a future
         client.chat_completion(request).await
@@ -87,11 +97,35 @@ impl APIManager {
         Ok(())
     }
 
+    /// 服务商配置中的默认模型名称
+    pub async fn default_model(&self, provider: Option<&str>) -> Option<String> {
+        let current_model = self.current_model.read().await;
+        let provider = provider.unwrap_or(current_model.as_str());
+        let clients = self.clients.read().await;
+        clients
+            .get(provider)
+            .map(|client| client.default_model().to_string())
+    }
+
     pub async fn list_available_models(&self) -> Vec<String> {
         let clients = self.clients.read().await;
         clients.keys().cloned().collect()
     }
 
+    /// 用新的服务商配置替换所有客户端，`current_model` 为空或不存在时保持原来的选择
+    pub async fn reload_clients(
+        &self,
+        configs: HashMap<String, APIConfig>,
+        current_model: Option<String>,
+    ) -> Result<(), String> {
+        self.clients.write().await.clear();
+        self.initialize_default_clients(configs).await;
+        match current_model {
+            Some(model_name) => self.set_current_model(model_name).await,
+            None => Ok(()),
+        }
+    }
+
     pub async fn initialize_default_clients(&self, configs: HashMap<String, APIConfig>) {
         for (name, config) in configs {
             match name.as_str() {
@@ -5,30 +5,77 @@ pub mod m_qwen;
 pub mod manager;
 pub mod traits;
 
-use std::collections::HashMap;
+use crate::my_config::{self, GlobalConfig};
+use std::collections::{BTreeMap, HashMap};
 use tauri::{AppHandle, Manager};
 use traits::APIConfig;
 
 pub fn setup_api_manager(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let global_config = my_config::get_global_config(app)?;
     tauri::async_runtime::spawn({
         let app_handle = app.clone();
         async move {
             let api_manager_state = app_handle.state::<manager::GlobalAPIManager>();
 
-            let config_map = get_default_configs();
+            let config_map = provider_configs(&global_config.providers);
 
-            let _ = api_manager_state
-                .0
-                .write()
-                .await
-                .initialize_default_clients(config_map)
-                .await;
+            let api_manager = api_manager_state.0.write().await;
+            api_manager.initialize_default_clients(config_map).await;
+            if let Some(provider) = global_config.active_provider {
+                if let Err(e) = api_manager.set_current_model(provider).await {
+                    eprintln!("{}", e);
+                }
+            }
             println!("API manager initialized successfully");
         }
     });
     Ok(())
 }
 
+/// 按配置重新创建所有客户端，切换配置方案时使用
+pub async fn reload_api_manager(
+    app: &AppHandle,
+    global_config: &GlobalConfig,
+) -> Result<(), String> {
+    let api_manager_state = app.state::<manager::GlobalAPIManager>();
+    let api_manager = api_manager_state.0.read().await;
+    api_manager
+        .reload_clients(
+            provider_configs(&global_config.providers),
+            global_config.active_provider.clone(),
+        )
+        .await
+}
+
+/// 内置服务商配置叠加用户配置。用户配置中 api_key 为空且 base_url 与内置的完全相同时沿用内置的密钥，
+/// 地址被修改时保持为空，避免把内置密钥发送到其他地址
+fn provider_configs(providers: &BTreeMap<String, APIConfig>) -> HashMap<String, APIConfig> {
+    let mut configs = get_default_configs();
+    for (name, provider) in providers {
+        let mut provider = provider.clone();
+        if provider.api_key.is_empty() {
+            if let Some(default) = configs.get(name) {
+                if default.base_url == provider.base_url {
+                    provider.api_key = default.api_key.clone();
+                }
+            }
+        }
+        configs.insert(name.clone(), provider);
+    }
+    configs
+}
+
+/// 缺少密钥的服务商，按名称排序
+pub fn providers_missing_key(providers: &BTreeMap<String, APIConfig>) -> Vec<String> {
+    let mut names: Vec<String> = provider_configs(providers)
+        .into_iter()
+        .filter(|(_, config)| config.api_key.is_empty())
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+    names
+}
+
 // Get default configurations for initialization
 fn get_default_configs() -> HashMap<String, APIConfig> {
     let mut configs = HashMap::new();
@@ -63,3 +110,32 @@ fn get_default_configs() -> HashMap<String, APIConfig> {
 
     configs
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(base_url: &str) -> APIConfig {
+        APIConfig {
+            api_key: String::new(),
+            base_url: base_url.to_string(),
+            model: "qwen-plus".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_key_only_for_builtin_base_url() {
+        let builtin = get_default_configs()["qwen"].clone();
+        let providers = BTreeMap::from([("qwen".to_string(), provider(&builtin.base_url))]);
+        assert_eq!(
+            provider_configs(&providers)["qwen"].api_key,
+            builtin.api_key
+        );
+        assert!(providers_missing_key(&providers).is_empty());
+
+        let providers =
+            BTreeMap::from([("qwen".to_string(), provider("https://attacker.example.com"))]);
+        assert!(provider_configs(&providers)["qwen"].api_key.is_empty());
+        assert_eq!(providers_missing_key(&providers), ["qwen"]);
+    }
+}
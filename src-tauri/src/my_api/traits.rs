@@ -1,7 +1,9 @@
 use crate::utils::chat_message::ChatMessage;
-use crate::utils::chat_message::LLMChatMessage;
+use crate::utils::chat_message::{LLMChatMessage, LLMContent, LLMContentPart, ToolCall};
 use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::future::Future;
 
 #[derive(Debug, Serialize, Clone)]
@@ -16,6 +18,100 @@ pub struct ChatCompletionRequest<'a> {
     pub top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
+    /// 模型可以调用的工具
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ToolDefinition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+}
+
+/// 回复格式，JSON 模式下提示词中必须出现 "JSON"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseFormat {
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl ResponseFormat {
+    pub fn json_object() -> Self {
+        ResponseFormat {
+            kind: "json_object".to_string(),
+        }
+    }
+}
+
+/// 工具定义，参数为 JSON Schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: FunctionDefinition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+impl ToolDefinition {
+    pub fn function(name: &str, description: &str, parameters: serde_json::Value) -> Self {
+        ToolDefinition {
+            kind: "function".to_string(),
+            function: FunctionDefinition {
+                name: name.to_string(),
+                description: description.to_string(),
+                parameters,
+            },
+        }
+    }
+}
+
+/// 服务商接受的消息内容格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentFormat {
+    /// 文本和图片分段，图片可指定精度
+    OpenAI,
+    /// 支持图片，但不接受 detail 字段
+    NoImageDetail,
+    /// 只接受字符串内容
+    TextOnly,
+}
+
+impl ChatCompletionRequest<'_> {
+    /// 按服务商支持的格式调整消息内容，不支持图片时返回错误
+    pub fn adapt_content(&mut self, format: ContentFormat) -> Result<(), String> {
+        for message in &mut self.messages {
+            let LLMContent::Parts(parts) = &mut message.content else {
+                continue;
+            };
+            match format {
+                ContentFormat::OpenAI => {}
+                ContentFormat::NoImageDetail => {
+                    for part in parts.iter_mut() {
+                        if let LLMContentPart::ImageUrl { image_url } = part {
+                            image_url.detail = None;
+                        }
+                    }
+                }
+                ContentFormat::TextOnly => {
+                    let text = parts
+                        .iter()
+                        .map(|part| match part {
+                            LLMContentPart::Text { text } => Ok(*text),
+                            LLMContentPart::ImageUrl { .. } => {
+                                Err(format!("模型 {} 不支持图片输入", self.model))
+                            }
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                        .join("\n");
+                    message.content = LLMContent::Text(Cow::Owned(text));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -57,15 +153,100 @@ pub struct ChoiceDelta {
     pub index: u32,
     pub delta: ChatMessageDelta,
     pub finish_reason: Option<String>,
+    /// 该选项结束时附带拼接完整的工具调用
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChatMessageDelta {
     pub role: Option<String>,
     pub content: Option<String>,
+    /// 推理模型（如 DeepSeek-R1、Qwen 的思考模式）在正文之前返回的思考过程
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+/// 流式返回的工具调用片段，同一调用的参数分散在多个片段中，按 index 拼接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallDelta {
+    pub index: u32,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<FunctionCallDelta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCallDelta {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+/// 在解析流式响应时拼接工具调用
+#[derive(Debug, Default)]
+pub struct ToolCallAssembler {
+    /// (选项序号, 调用序号) → 已拼接的调用
+    calls: BTreeMap<(u32, u32), ToolCall>,
+}
+
+impl ToolCallAssembler {
+    /// 记录一个选项的片段；选项结束时返回该选项拼接完整的调用
+    pub fn push(
+        &mut self,
+        choice_index: u32,
+        deltas: &Option<Vec<ToolCallDelta>>,
+        finish_reason: &Option<String>,
+    ) -> Option<Vec<ToolCall>> {
+        for delta in deltas.iter().flatten() {
+            let call = self
+                .calls
+                .entry((choice_index, delta.index))
+                .or_insert_with(|| ToolCall {
+                    id: String::new(),
+                    kind: "function".to_string(),
+                    function: Default::default(),
+                });
+            if let Some(id) = delta.id.as_deref().filter(|id| !id.is_empty()) {
+                call.id = id.to_string();
+            }
+            if let Some(function) = &delta.function {
+                if let Some(name) = &function.name {
+                    call.function.name.push_str(name);
+                }
+                if let Some(arguments) = &function.arguments {
+                    call.function.arguments.push_str(arguments);
+                }
+            }
+        }
+
+        if finish_reason.is_none() {
+            return None;
+        }
+        let keys: Vec<_> = self
+            .calls
+            .range((choice_index, 0)..=(choice_index, u32::MAX))
+            .map(|(key, _)| *key)
+            .collect();
+        if keys.is_empty() {
+            return None;
+        }
+        Some(
+            keys.iter()
+                .filter_map(|key| self.calls.remove(key))
+                .collect(),
+        )
+    }
 }
 
 pub trait LLMClient {
+    /// 请求未指定模型时使用的模型名称
+    fn default_model(&self) -> &str;
+
     fn chat_completion<'a>(
         &'a self,
         request: &'a ChatCompletionRequest,
@@ -91,3 +272,113 @@ pub struct APIConfig {
     pub base_url: String,
     pub model: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::chat_message::{ChatMessage, ContentPart, ImageDetail, ImageSource, Role};
+
+    fn image_message() -> ChatMessage {
+        ChatMessage::new(
+            Role::User,
+            vec![
+                ContentPart::Text {
+                    text: "这是什么".to_string(),
+                },
+                ContentPart::Image {
+                    source: ImageSource::Url("https://example.com/a.png".to_string()),
+                    detail: Some(ImageDetail::Low),
+                },
+            ],
+        )
+    }
+
+    fn request(messages: &[ChatMessage]) -> ChatCompletionRequest<'_> {
+        ChatCompletionRequest {
+            model: "test".to_string(),
+            messages: messages.iter().map(|m| m.as_llm().unwrap()).collect(),
+            temperature: None,
+            max_tokens: None,
+            top_p: None,
+            stream: None,
+            tools: None,
+            response_format: None,
+        }
+    }
+
+    #[test]
+    fn test_adapt_content() {
+        let messages = [image_message()];
+
+        let mut openai = request(&messages);
+        openai.adapt_content(ContentFormat::OpenAI).unwrap();
+        assert_eq!(
+            serde_json::to_value(&openai.messages[0].content).unwrap()[1],
+            serde_json::json!({
+                "type": "image_url",
+                "image_url": { "url": "https://example.com/a.png", "detail": "low" }
+            })
+        );
+
+        let mut qwen = request(&messages);
+        qwen.adapt_content(ContentFormat::NoImageDetail).unwrap();
+        assert_eq!(
+            serde_json::to_value(&qwen.messages[0].content).unwrap()[1],
+            serde_json::json!({
+                "type": "image_url",
+                "image_url": { "url": "https://example.com/a.png" }
+            })
+        );
+
+        let mut deepseek = request(&messages);
+        assert!(deepseek.adapt_content(ContentFormat::TextOnly).is_err());
+    }
+
+    #[test]
+    fn test_adapt_content_flattens_text_parts() {
+        let messages = [ChatMessage::new(
+            Role::User,
+            vec![
+                ContentPart::Text {
+                    text: "第一段".to_string(),
+                },
+                ContentPart::Text {
+                    text: "第二段".to_string(),
+                },
+            ],
+        )];
+        let mut deepseek = request(&messages);
+        deepseek.adapt_content(ContentFormat::TextOnly).unwrap();
+        assert_eq!(
+            serde_json::to_value(&deepseek.messages[0]).unwrap(),
+            serde_json::json!({ "role": "user", "content": "第一段\n第二段" })
+        );
+    }
+
+    #[test]
+    fn test_tool_call_assembler() {
+        let deltas: Vec<Vec<ToolCallDelta>> = serde_json::from_value(serde_json::json!([
+            [{ "index": 0, "id": "call_1", "type": "function",
+               "function": { "name": "glossary", "arguments": "" } }],
+            [{ "index": 0, "function": { "arguments": "{\"term\":" } }],
+            [{ "index": 0, "function": { "arguments": "\"Rust\"}" } },
+             { "index": 1, "id": "call_2", "function": { "name": "clipboard", "arguments": "{}" } }]
+        ]))
+        .unwrap();
+
+        let finished = Some("tool_calls".to_string());
+        let mut assembler = ToolCallAssembler::default();
+        for delta in deltas {
+            assert!(assembler.push(0, &Some(delta), &None).is_none());
+        }
+        let calls = assembler.push(0, &None, &finished).unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(calls[0].function.name, "glossary");
+        assert_eq!(calls[0].function.arguments, r#"{"term":"Rust"}"#);
+        assert_eq!(calls[1].function.name, "clipboard");
+
+        // 没有工具调用的选项结束时不返回
+        assert!(assembler.push(0, &None, &finished).is_none());
+    }
+}
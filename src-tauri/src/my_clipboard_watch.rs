@@ -0,0 +1,288 @@
+use crate::my_config::{self, ClipboardWatchConfig};
+use crate::my_events::event_names;
+use crate::utils::clipboard::{self, ClipboardBackend, SystemClipboard};
+use crate::utils::text_translation;
+use crate::{my_tray, my_windows};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// 剪贴板监听开关，托盘菜单和命令共用
+pub struct ClipboardWatchState(Arc<AtomicBool>);
+
+impl ClipboardWatchState {
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// 剪贴板变化检测：去抖，并过滤空内容、超长内容和疑似密码
+struct ClipboardChangeDetector {
+    config: ClipboardWatchConfig,
+    last_seen: Option<String>,
+    pending: Option<(String, Instant)>,
+}
+
+impl ClipboardChangeDetector {
+    fn new(config: ClipboardWatchConfig) -> Self {
+        Self {
+            config,
+            last_seen: None,
+            pending: None,
+        }
+    }
+
+    /// 以当前剪贴板内容为基线，不触发翻译（刚开启监听或应用自身写入剪贴板之后）
+    fn rebaseline(&mut self, text: Option<String>) {
+        self.last_seen = text;
+        self.pending = None;
+    }
+
+    /// 输入一次剪贴板采样，新内容稳定超过去抖时间后返回需要翻译的文本
+    fn observe(&mut self, text: Option<String>, now: Instant) -> Option<String> {
+        let Some(text) = text else {
+            // 非文本内容（如图片）也算一次变化
+            self.rebaseline(None);
+            return None;
+        };
+
+        if let Some((pending, since)) = &self.pending {
+            if *pending == text {
+                if now.duration_since(*since) < Duration::from_millis(self.config.debounce_ms) {
+                    return None;
+                }
+                self.rebaseline(Some(text.clone()));
+                return self.accept(text);
+            }
+        }
+
+        if self.last_seen.as_deref() == Some(text.as_str()) {
+            self.pending = None;
+        } else {
+            self.pending = Some((text, now));
+        }
+        None
+    }
+
+    fn accept(&self, text: String) -> Option<String> {
+        if text.trim().is_empty() || text.chars().count() > self.config.max_chars {
+            return None;
+        }
+        if self.config.ignore_passwords && looks_like_password(&text) {
+            return None;
+        }
+        Some(text)
+    }
+}
+
+/// 单个无空白、混合大小写/数字/符号的 ASCII 串视为密码
+fn looks_like_password(text: &str) -> bool {
+    let text = text.trim();
+    let len = text.chars().count();
+    if !(8..=64).contains(&len) || !text.is_ascii() || text.contains(char::is_whitespace) {
+        return false;
+    }
+
+    let classes = [
+        text.chars().any(|c| c.is_ascii_lowercase()),
+        text.chars().any(|c| c.is_ascii_uppercase()),
+        text.chars().any(|c| c.is_ascii_digit()),
+        text.chars().any(|c| c.is_ascii_punctuation()),
+    ];
+    classes.iter().filter(|&&present| present).count() >= 3
+}
+
+pub fn init_clipboard_watch(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let config = my_config::get_global_config(app)?.clipboard_watch;
+    let enabled = Arc::new(AtomicBool::new(config.enabled));
+    app.manage(ClipboardWatchState(enabled.clone()));
+
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut clipboard = match SystemClipboard::new() {
+            Ok(clipboard) => clipboard,
+            Err(e) => {
+                eprintln!("剪贴板监听启动失败: {}", e);
+                return;
+            }
+        };
+
+        let mut detector = ClipboardChangeDetector::new(config);
+        let mut needs_rebaseline = true;
+
+        loop {
+            thread::sleep(Duration::from_millis(detector.config.poll_interval_ms));
+
+            if !enabled.load(Ordering::SeqCst) {
+                needs_rebaseline = true;
+                continue;
+            }
+
+            let text = clipboard.read_text();
+            if needs_rebaseline {
+                // 重新开启时读取最新配置
+                if let Ok(global_config) = my_config::get_global_config(&app) {
+                    detector.config = global_config.clipboard_watch;
+                }
+                detector.rebaseline(text);
+                needs_rebaseline = false;
+                continue;
+            }
+
+            // 应用自身写入的内容（选区捕获、回写、自定义动作的输出及恢复的原内容）
+            // 只作为新的基线，不翻译；写入后很快又被恢复时，去抖会丢弃中间的内容
+            if text.as_deref().is_some_and(clipboard::take_own_text) {
+                detector.rebaseline(text);
+                continue;
+            }
+
+            if let Some(text) = detector.observe(text, Instant::now()) {
+                translate_clipboard_text(&app, text);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn translate_clipboard_text(app: &AppHandle, text: String) {
+    let app_clone = app.clone();
+    if my_windows::is_translate_window_visible(app) {
+        text_translation::translate_text_bubble(app, text);
+    } else {
+        my_windows::window_translate_bubble_show(
+            app,
+            Some(move || {
+                text_translation::translate_text_bubble(&app_clone, text);
+            }),
+        );
+    }
+}
+
+/// 开关剪贴板监听，保存到配置并同步托盘菜单和各窗口
+pub fn set_clipboard_watch_enabled<R: Runtime>(
+    app: &AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    if let Some(state) = app.try_state::<ClipboardWatchState>() {
+        state.0.store(enabled, Ordering::SeqCst);
+    }
+
+    let mut global_config =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    global_config.clipboard_watch.enabled = enabled;
+    my_config::set_global_config(app, &global_config)
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    my_tray::set_clipboard_watch_checked(app, enabled);
+    let _ = app.emit(event_names::CLIPBOARD_WATCH_CHANGED, enabled);
+    Ok(())
+}
+
+pub fn toggle_clipboard_watch<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
+    let enabled = !app
+        .try_state::<ClipboardWatchState>()
+        .map(|state| state.is_enabled())
+        .unwrap_or(false);
+    set_clipboard_watch_enabled(app, enabled)?;
+    Ok(enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> ClipboardChangeDetector {
+        ClipboardChangeDetector::new(ClipboardWatchConfig {
+            enabled: true,
+            debounce_ms: 500,
+            max_chars: 20,
+            ..ClipboardWatchConfig::default()
+        })
+    }
+
+    fn after(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_debounce() {
+        let start = Instant::now();
+        let mut detector = detector();
+        detector.rebaseline(Some("old".into()));
+
+        assert_eq!(detector.observe(Some("hello".into()), start), None);
+        assert_eq!(
+            detector.observe(Some("hello".into()), after(start, 300)),
+            None
+        );
+        assert_eq!(
+            detector.observe(Some("hello".into()), after(start, 600)),
+            Some("hello".into())
+        );
+        // 同一内容只触发一次
+        assert_eq!(
+            detector.observe(Some("hello".into()), after(start, 1200)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_change_during_debounce_restarts_timer() {
+        let start = Instant::now();
+        let mut detector = detector();
+
+        detector.observe(Some("first".into()), start);
+        detector.observe(Some("second".into()), after(start, 400));
+        assert_eq!(
+            detector.observe(Some("second".into()), after(start, 700)),
+            None
+        );
+        assert_eq!(
+            detector.observe(Some("second".into()), after(start, 900)),
+            Some("second".into())
+        );
+    }
+
+    #[test]
+    fn test_rebaseline_ignores_existing_content() {
+        let start = Instant::now();
+        let mut detector = detector();
+        detector.rebaseline(Some("restored".into()));
+
+        detector.observe(Some("restored".into()), start);
+        assert_eq!(
+            detector.observe(Some("restored".into()), after(start, 600)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        let start = Instant::now();
+        let mut detector = detector();
+
+        detector.observe(Some("this text is far too long".into()), start);
+        assert_eq!(
+            detector.observe(Some("this text is far too long".into()), after(start, 600)),
+            None
+        );
+
+        detector.observe(Some("Tr0ub4dor&3".into()), after(start, 700));
+        assert_eq!(
+            detector.observe(Some("Tr0ub4dor&3".into()), after(start, 1300)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_looks_like_password() {
+        assert!(looks_like_password("Tr0ub4dor&3"));
+        assert!(looks_like_password("hunter2Secret"));
+        assert!(!looks_like_password("correct horse battery staple"));
+        assert!(!looks_like_password("translation"));
+        assert!(!looks_like_password("密码Password123"));
+    }
+}
@@ -1,15 +1,24 @@
+use crate::my_actions::{self, ActionInfo, ActionRegistry};
+use crate::my_config::{self, CustomAction, GestureConfig};
 use crate::utils::chat_message::ChatMessage;
-use crate::utils::{language_detection, translation_manager};
-use crate::{my_events::event_names, my_windows, states::setting_states};
+use crate::utils::quick_actions::{self, QuickAction, QuickActionInfo};
+use crate::utils::translation_manager::StreamDelta;
+use crate::utils::{language_detection, translation_manager, write_back};
+use crate::{
+    my_clipboard_watch, my_events::event_names, my_ocr, my_profiles, my_rdev, my_windows,
+    states::setting_states,
+};
 
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
-use tauri::{ipc::Channel, AppHandle, Emitter, Manager, State};
+use tauri::{ipc::Channel, AppHandle, Emitter, LogicalSize, Manager, State};
 
+/// 流式对话推送给页面的事件，Reasoning 为推理模型的思考过程，页面折叠显示
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "event", content = "data")]
 pub enum StreamEvent {
     Chunk { content: String },
+    Reasoning { content: String },
     Done,
     Error { message: String },
 }
@@ -30,15 +39,18 @@ pub async fn chat_stream(
     chat_message: ChatMessage,
     on_event: Channel<StreamEvent>,
 ) -> Result<(), String> {
+    // 前端只能引用截图目录中的图片
+    chat_message
+        .content
+        .check_image_paths(&my_ocr::screenshot_dir(&app)?)?;
     let translation_manager = app.state::<translation_manager::TranslationManager>();
     let app_clone = app.clone();
-    let content_clone = chat_message.content.clone();
     let on_event_clone = on_event.clone();
 
     match translation_manager
         .translate_stream(
             None,
-            &content_clone,
+            chat_message.content,
             None,
             |chat_history| {
                 let app_handle = app.clone();
@@ -46,11 +58,16 @@ pub async fn chat_stream(
                     let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
                 }
             },
-            move |chunk_content| {
-                let _ = on_event_clone.send(StreamEvent::Chunk {
-                    content: chunk_content.clone(),
-                });
-                let _ = app_clone.emit(event_names::AI_RESPONSE_STREAM, &chunk_content);
+            move |delta| match delta {
+                StreamDelta::Reasoning(content) => {
+                    let _ = on_event_clone.send(StreamEvent::Reasoning { content });
+                }
+                StreamDelta::Content(content) => {
+                    let _ = on_event_clone.send(StreamEvent::Chunk {
+                        content: content.clone(),
+                    });
+                    let _ = app_clone.emit(event_names::AI_RESPONSE_STREAM, &content);
+                }
             },
         )
         .await
@@ -75,30 +92,30 @@ pub fn detect_language(text: &str) -> String {
 }
 
 #[tauri::command]
-pub fn toggle_auto_close_translate(state: State<'_, Mutex<setting_states::AppState>>) -> bool {
-    let mut app_state = state.lock().unwrap();
-    app_state.auto_close_translate = !app_state.auto_close_translate;
-    app_state.auto_close_translate
+pub fn toggle_translate_pinned(app: AppHandle) -> Result<bool, String> {
+    setting_states::update_settings(&app, |settings| {
+        settings.pinned = !settings.pinned;
+    })
+    .map(|settings| settings.pinned)
 }
 
 #[tauri::command]
-pub fn get_auto_close_translate_state(state: State<'_, Mutex<setting_states::AppState>>) -> bool {
+pub fn get_translate_pinned_state(state: State<'_, Mutex<setting_states::AppState>>) -> bool {
     let app_state = state.lock().unwrap();
-    app_state.auto_close_translate
+    app_state.pinned
 }
 
 #[tauri::command]
-pub fn toggle_auto_speak(
-    state: State<'_, Mutex<setting_states::AppState>>,
-) -> setting_states::AutoSpeakState {
-    let mut app_state = state.lock().unwrap();
-    // Cycle through the three states: Off -> Single -> All -> Off
-    app_state.auto_speak = match app_state.auto_speak {
-        setting_states::AutoSpeakState::Off => setting_states::AutoSpeakState::Single,
-        setting_states::AutoSpeakState::Single => setting_states::AutoSpeakState::All,
-        setting_states::AutoSpeakState::All => setting_states::AutoSpeakState::Off,
-    };
-    app_state.auto_speak
+pub fn toggle_auto_speak(app: AppHandle) -> Result<setting_states::AutoSpeakState, String> {
+    setting_states::update_settings(&app, |settings| {
+        // Cycle through the three states: Off -> Single -> All -> Off
+        settings.auto_speak = match settings.auto_speak {
+            setting_states::AutoSpeakState::Off => setting_states::AutoSpeakState::Single,
+            setting_states::AutoSpeakState::Single => setting_states::AutoSpeakState::All,
+            setting_states::AutoSpeakState::All => setting_states::AutoSpeakState::Off,
+        };
+    })
+    .map(|settings| settings.auto_speak)
 }
 
 #[tauri::command]
@@ -109,6 +126,155 @@ pub fn get_auto_speak_state(
     app_state.auto_speak
 }
 
+#[tauri::command]
+pub fn get_settings(app: AppHandle) -> setting_states::AppState {
+    setting_states::get_settings(&app)
+}
+
+/// 整体替换用户设置，保存后广播 SETTINGS_CHANGED
+#[tauri::command]
+pub fn set_settings(
+    app: AppHandle,
+    settings: setting_states::AppState,
+) -> Result<setting_states::AppState, String> {
+    setting_states::update_settings(&app, |current| *current = settings)
+}
+
+#[tauri::command]
+pub fn toggle_clipboard_watch(app: AppHandle) -> Result<bool, String> {
+    my_clipboard_watch::toggle_clipboard_watch(&app)
+}
+
+#[tauri::command]
+pub fn get_clipboard_watch_state(
+    state: State<'_, my_clipboard_watch::ClipboardWatchState>,
+) -> bool {
+    state.is_enabled()
+}
+
+#[tauri::command]
+pub fn get_gesture_config(app: AppHandle) -> Result<GestureConfig, String> {
+    my_config::get_global_config(&app)
+        .map(|config| config.gesture)
+        .map_err(|e| format!("获取配置失败: {}", e))
+}
+
+/// 修改触发键手势，立即生效
+#[tauri::command]
+pub fn set_gesture_config(app: AppHandle, config: GestureConfig) -> Result<(), String> {
+    my_rdev::apply_gesture_config(&app, config)
+}
+
+/// 列出所有可绑定的动作及其快捷键
+#[tauri::command]
+pub fn list_actions(
+    app: AppHandle,
+    registry: State<'_, ActionRegistry>,
+) -> Result<Vec<ActionInfo>, String> {
+    let shortcuts = my_config::get_global_config(&app)
+        .map_err(|e| format!("获取配置失败: {}", e))?
+        .shortcuts;
+
+    Ok(registry
+        .list()
+        .into_iter()
+        .map(|action| ActionInfo {
+            hot_key: shortcuts
+                .iter()
+                .find(|s| my_actions::resolve_action_id(&s.name) == action.id)
+                .map(|s| s.hot_key.clone()),
+            id: action.id,
+            label: action.label,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn list_custom_actions(app: AppHandle) -> Result<Vec<CustomAction>, String> {
+    my_config::get_global_config(&app)
+        .map(|config| config.custom_actions)
+        .map_err(|e| format!("获取配置失败: {}", e))
+}
+
+/// 新增或更新自定义动作，同时设置其快捷键（为空则解除绑定）
+#[tauri::command]
+pub async fn save_custom_action(
+    app: AppHandle,
+    action: CustomAction,
+    hot_key: Option<String>,
+) -> Result<(), String> {
+    my_actions::save_custom_action(&app, action, hot_key).await
+}
+
+#[tauri::command]
+pub fn delete_custom_action(app: AppHandle, id: String) -> Result<(), String> {
+    my_actions::delete_custom_action(&app, &id)
+}
+
+#[tauri::command]
+pub fn list_quick_actions() -> Vec<QuickActionInfo> {
+    QuickAction::ALL
+        .into_iter()
+        .map(QuickAction::info)
+        .collect()
+}
+
+/// 对原文执行解释、润色等动作，结果显示在气泡中（气泡中切换动作）
+#[tauri::command]
+pub async fn run_quick_action(app: AppHandle, action: QuickAction, text: String) {
+    quick_actions::run_on_text_bubble(&app, action, text).await;
+}
+
+/// 页面临时改变气泡大小，如展开动作列表，按新大小重新定位
+#[tauri::command]
+pub fn resize_translate_bubble(app: AppHandle, width: f64, height: f64) {
+    my_windows::resize_translate_bubble(&app, LogicalSize::new(width, height));
+}
+
+/// 用当前会话最后一条译文替换原选区（气泡中的回写按钮）
+#[tauri::command]
+pub async fn write_back(app: AppHandle) -> Result<(), String> {
+    write_back::write_back_last(&app).await
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Result<my_profiles::ProfileList, String> {
+    my_profiles::list_profiles(&app)
+}
+
+/// 以当前配置为模板新建配置方案
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<(), String> {
+    my_profiles::create_profile(&app, &name)
+}
+
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    my_profiles::delete_profile(&app, &name)
+}
+
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    my_profiles::switch_profile(&app, &name).await
+}
+
+/// 导出配置方案，文件中不包含服务商密钥
+#[tauri::command]
+pub fn export_profile(app: AppHandle, name: String, path: String) -> Result<(), String> {
+    my_profiles::export_profile(&app, &name, &path)
+}
+
+/// 导入配置方案，返回导入后的方案名称
+#[tauri::command]
+pub async fn import_profile(
+    app: AppHandle,
+    path: String,
+    name: Option<String>,
+    conflict: Option<my_profiles::ImportConflict>,
+) -> Result<String, String> {
+    my_profiles::import_profile(&app, &path, name, conflict.unwrap_or_default()).await
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn command_window_translate_show(app: AppHandle, chat_message: Vec<ChatMessage>) {
     let app_clone = app.clone();
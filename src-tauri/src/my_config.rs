@@ -1,6 +1,12 @@
+use crate::my_actions::action_ids;
+use crate::my_api::traits::APIConfig;
+use crate::states::setting_states::AppState;
+use crate::utils::config_migration;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use tauri::{AppHandle, Runtime};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, Runtime};
 use tauri_plugin_store::StoreExt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,10 +15,420 @@ pub struct Shortcut {
     pub hot_key: String,
 }
 
+/// 获取选中文本的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionStrategy {
+    /// 系统辅助功能接口（selection crate）
+    Accessibility,
+    /// 模拟复制后读取剪贴板，并恢复原有剪贴板内容
+    Clipboard,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelectionConfig {
+    /// 按顺序尝试，直到取到非空文本
+    pub strategies: Vec<SelectionStrategy>,
+    pub accessibility_retry_delay_ms: u64,
+    /// 模拟复制后等待剪贴板更新的最长时间
+    pub clipboard_timeout_ms: u64,
+    pub restore_clipboard: bool,
+}
+
+impl Default for SelectionConfig {
+    fn default() -> Self {
+        SelectionConfig {
+            strategies: vec![
+                SelectionStrategy::Accessibility,
+                SelectionStrategy::Clipboard,
+            ],
+            accessibility_retry_delay_ms: 100,
+            clipboard_timeout_ms: 300,
+            restore_clipboard: true,
+        }
+    }
+}
+
+/// 剪贴板监听模式：复制即翻译
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardWatchConfig {
+    pub enabled: bool,
+    pub poll_interval_ms: u64,
+    /// 剪贴板内容稳定这么久之后才触发翻译
+    pub debounce_ms: u64,
+    /// 超过该字符数的内容不翻译
+    pub max_chars: usize,
+    /// 忽略看起来像密码的内容
+    pub ignore_passwords: bool,
+}
+
+impl Default for ClipboardWatchConfig {
+    fn default() -> Self {
+        ClipboardWatchConfig {
+            enabled: false,
+            poll_interval_ms: 300,
+            debounce_ms: 500,
+            max_chars: 5000,
+            ignore_passwords: true,
+        }
+    }
+}
+
+/// 全局按键手势：连按触发键执行绑定的动作（默认弹出翻译气泡），长按弹出输入法窗口
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GestureConfig {
+    /// 触发键名称，如 "ControlRight"、"MetaRight"，见 `utils::gesture::parse_key`
+    pub key: String,
+    /// 连按几次弹出翻译气泡：1 单击、2 双击、3 三击
+    pub tap_count: u8,
+    /// 相邻两次按键松开的最大间隔
+    pub tap_window_ms: u64,
+    /// 按住多久弹出输入法窗口，超过该时长的按键不计为单击
+    pub long_press_ms: u64,
+    /// 连按触发的动作 id
+    pub tap_action: String,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        #[cfg(target_os = "macos")]
+        let key = "MetaRight";
+        #[cfg(not(target_os = "macos"))]
+        let key = "ControlRight";
+
+        GestureConfig {
+            key: key.to_string(),
+            tap_count: 2,
+            tap_window_ms: 1000,
+            long_press_ms: 800,
+            tap_action: action_ids::TRANSLATE_BUBBLE.to_string(),
+        }
+    }
+}
+
+/// 自定义动作结果的输出位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionOutput {
+    TranslateWindow,
+    Bubble,
+    Clipboard,
+    WriteBack,
+    Notification,
+}
+
+/// 用户在配置中定义的动作，校验见 `utils::custom_actions::validate_custom_action`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomAction {
+    /// 动作 id，用于快捷键绑定，只能包含小写字母、数字和下划线
+    pub id: String,
+    pub name: String,
+    /// 提示词模板，`{text}` 替换为选中文本
+    pub prompt_template: String,
+    /// 服务商名称，如 "qwen"、"deepseek"，为空时使用当前模型
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// 模型名称，为空时使用服务商的默认模型
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    pub output: ActionOutput,
+}
+
+/// 语音合成引擎
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TtsEngineKind {
+    /// 交给页面的 speechSynthesis 朗读
+    #[default]
+    Webview,
+    /// OpenAI 兼容的 `/audio/speech` 接口
+    OpenAi,
+    /// 本地的 espeak-ng 或 piper
+    Local,
+}
+
+/// OpenAI 兼容的语音合成接口
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenAiTtsConfig {
+    pub base_url: String,
+    /// 为空时不发送 Authorization，适用于本地部署的兼容服务
+    pub api_key: String,
+    pub model: String,
+    /// 按语言选择的声音，键为语言代码，如 "en-US" 或 "en"
+    pub voices: BTreeMap<String, String>,
+    /// 没有对应语言时使用的声音
+    pub default_voice: String,
+}
+
+impl Default for OpenAiTtsConfig {
+    fn default() -> Self {
+        OpenAiTtsConfig {
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: String::new(),
+            model: "tts-1".to_string(),
+            voices: BTreeMap::new(),
+            default_voice: "alloy".to_string(),
+        }
+    }
+}
+
+/// 本地语音合成程序
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalTtsProgram {
+    #[default]
+    EspeakNg,
+    /// 声音为 .onnx 模型文件的路径
+    Piper,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalTtsConfig {
+    pub program: LocalTtsProgram,
+    /// 可执行文件路径，为空时从 PATH 中查找
+    pub path: Option<String>,
+    /// 按语言选择的声音，espeak-ng 为声音名称，piper 为模型路径
+    pub voices: BTreeMap<String, String>,
+    pub default_voice: String,
+}
+
+impl Default for LocalTtsConfig {
+    fn default() -> Self {
+        LocalTtsConfig {
+            program: LocalTtsProgram::EspeakNg,
+            path: None,
+            voices: BTreeMap::from([
+                ("zh-CN".to_string(), "cmn".to_string()),
+                ("en-US".to_string(), "en-us".to_string()),
+            ]),
+            default_voice: "en-us".to_string(),
+        }
+    }
+}
+
+/// 自动朗读和朗读按钮使用的语音合成
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TtsConfig {
+    pub engine: TtsEngineKind,
+    pub openai: OpenAiTtsConfig,
+    pub local: LocalTtsConfig,
+    /// 语速倍率，1.0 为正常语速
+    pub speed: f32,
+    /// 音频缓存的上限，超出后删除最久未使用的音频
+    pub cache_max_mb: u64,
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        TtsConfig {
+            engine: TtsEngineKind::Webview,
+            openai: OpenAiTtsConfig::default(),
+            local: LocalTtsConfig::default(),
+            speed: 1.0,
+            cache_max_mb: 50,
+        }
+    }
+}
+
+/// 语音识别引擎
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SttEngineKind {
+    /// OpenAI 兼容的 `/audio/transcriptions` 接口
+    #[default]
+    OpenAi,
+    /// 本地的 whisper.cpp
+    Local,
+}
+
+/// OpenAI 兼容的语音识别接口
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenAiSttConfig {
+    pub base_url: String,
+    /// 为空时不发送 Authorization，适用于本地部署的兼容服务
+    pub api_key: String,
+    pub model: String,
+}
+
+impl Default for OpenAiSttConfig {
+    fn default() -> Self {
+        OpenAiSttConfig {
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: String::new(),
+            model: "whisper-1".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalSttConfig {
+    /// whisper.cpp 可执行文件路径，为空时从 PATH 中查找 whisper-cli
+    pub path: Option<String>,
+    /// ggml 模型文件路径
+    pub model: String,
+}
+
+/// 语音输入：长按触发键录音，松开后识别并翻译
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SttConfig {
+    /// 是否启用语音输入，默认关闭；关闭时长按触发键只显示输入法窗口，不录音
+    pub enabled: bool,
+    pub engine: SttEngineKind,
+    pub openai: OpenAiSttConfig,
+    pub local: LocalSttConfig,
+    /// 说话的语言，ISO 639-1 代码如 "zh"、"en"，为空时自动识别
+    pub language: Option<String>,
+    /// 提示词，可以放入专有名词提高识别准确率
+    pub prompt: Option<String>,
+}
+
+/// 截图文字识别引擎
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrEngineKind {
+    /// 本地的 tesseract
+    #[default]
+    Tesseract,
+    /// 支持视觉的模型，通过服务商接口识别
+    Vision,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TesseractConfig {
+    /// 可执行文件路径，为空时从 PATH 中查找
+    pub path: Option<String>,
+    /// 识别的语言，多个语言用 "+" 连接
+    pub languages: String,
+}
+
+impl Default for TesseractConfig {
+    fn default() -> Self {
+        TesseractConfig {
+            path: None,
+            languages: "chi_sim+eng".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisionOcrConfig {
+    /// 服务商名称，为空时使用当前模型
+    pub provider: Option<String>,
+    pub model: String,
+}
+
+impl Default for VisionOcrConfig {
+    fn default() -> Self {
+        VisionOcrConfig {
+            provider: Some("qwen".to_string()),
+            model: "qwen-vl-plus".to_string(),
+        }
+    }
+}
+
+/// 截图翻译：框选屏幕区域，识别其中的文字后翻译
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OcrConfig {
+    pub engine: OcrEngineKind,
+    pub tesseract: TesseractConfig,
+    pub vision: VisionOcrConfig,
+}
+
+/// 对话时模型可以调用的工具
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolsConfig {
+    pub enabled: bool,
+    /// 一次回复中最多连续调用工具的轮数
+    pub max_rounds: usize,
+    /// 术语表：原文 → 指定译法
+    pub glossary: BTreeMap<String, String>,
+    /// 词典查询地址，`{word}` 替换为要查的单词；为空时不提供词典工具
+    pub dictionary_url: String,
+    /// 是否允许模型读取剪贴板
+    pub clipboard: bool,
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        ToolsConfig {
+            enabled: false,
+            max_rounds: 5,
+            glossary: BTreeMap::new(),
+            dictionary_url: "https://api.dictionaryapi.dev/api/v2/entries/en/{word}".to_string(),
+            clipboard: false,
+        }
+    }
+}
+
+/// 单词查询：选中单个词时显示词典释义而不是译文
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DictionaryConfig {
+    /// 默认关闭，开启后选中单个词时改为查询词典
+    pub enabled: bool,
+    /// 离线词典目录，放置 StarDict（.ifo/.idx/.dict）或 ECDICT（.csv）词典；
+    /// 离线词典查不到时才请求模型
+    pub offline_dir: Option<String>,
+}
+
+impl Default for DictionaryConfig {
+    fn default() -> Self {
+        DictionaryConfig {
+            enabled: false,
+            offline_dir: None,
+        }
+    }
+}
+
+/// 导出配置方案时清除的字段：密钥和可能带有私有参数的地址。
+/// 按 `/` 分隔的 JSON 路径，`*` 匹配任意键；新增此类配置项时同时加入
+pub const SECRET_FIELDS: &[&str] = &[
+    "providers/*/api_key",
+    "tts/openai/api_key",
+    "stt/openai/api_key",
+    "tools/dictionary_url",
+];
+
+/// 全局配置，格式变化见 `utils::config_migration`
+///
+/// 缺少的字段使用默认值，不认识的字段（如新版应用写入的）保存在 `extra` 中，写回时保留
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GlobalConfig {
+    /// 配置格式版本
+    pub version: u32,
     pub shortcuts: Vec<Shortcut>,
-    pub test_field: String,
+    pub selection: SelectionConfig,
+    pub clipboard_watch: ClipboardWatchConfig,
+    pub gesture: GestureConfig,
+    pub custom_actions: Vec<CustomAction>,
+    pub settings: AppState,
+    /// 服务商配置，覆盖同名的内置服务商；api_key 为空时使用内置的密钥
+    pub providers: BTreeMap<String, APIConfig>,
+    /// 当前使用的服务商，为空时使用默认服务商
+    pub active_provider: Option<String>,
+    pub tts: TtsConfig,
+    pub stt: SttConfig,
+    pub ocr: OcrConfig,
+    pub tools: ToolsConfig,
+    pub dictionary: DictionaryConfig,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Default for GlobalConfig {
@@ -23,17 +439,30 @@ impl Default for GlobalConfig {
         let cmd_ctrl_modifier = "Ctrl";
 
         GlobalConfig {
+            version: config_migration::CONFIG_VERSION,
             shortcuts: vec![
                 Shortcut {
                     name: "okey_ai".to_string(),
                     hot_key: format!("{}+G", cmd_ctrl_modifier),
                 },
                 Shortcut {
-                    name: "test".to_string(),
-                    hot_key: format!("{}+H", cmd_ctrl_modifier),
+                    name: "write_back".to_string(),
+                    hot_key: format!("{}+Shift+R", cmd_ctrl_modifier),
                 },
             ],
-            test_field: "default_value".to_string(),
+            selection: SelectionConfig::default(),
+            clipboard_watch: ClipboardWatchConfig::default(),
+            gesture: GestureConfig::default(),
+            custom_actions: Vec::new(),
+            settings: AppState::default(),
+            providers: BTreeMap::new(),
+            active_provider: None,
+            tts: TtsConfig::default(),
+            stt: SttConfig::default(),
+            ocr: OcrConfig::default(),
+            tools: ToolsConfig::default(),
+            dictionary: DictionaryConfig::default(),
+            extra: Map::new(),
         }
     }
 }
@@ -42,15 +471,65 @@ pub fn get_global_config<R: Runtime>(
     app: &AppHandle<R>,
 ) -> Result<GlobalConfig, Box<dyn std::error::Error>> {
     let store = app.store("store.json")?;
-    if let Some(value) = store.get("global_config") {
-        let config: GlobalConfig = serde_json::from_value(value.clone())?;
-        Ok(config)
+    let Some(value) = store.get("global_config") else {
+        return init_global_config(app);
+    };
+
+    let migrated = config_migration::needs_migration(&value);
+    let value = if migrated {
+        let version = config_migration::config_version(&value);
+        if let Some(backup) = backup_store(app, &format!("v{}", version)) {
+            println!("配置已备份到 {}，开始迁移", backup.display());
+        }
+        config_migration::migrate_config(value.clone())
     } else {
-        let config = init_global_config(app)?;
-        Ok(config)
+        value.clone()
+    };
+
+    match config_migration::parse_config(value) {
+        Ok((config, reset)) => {
+            if !reset.is_empty() {
+                // 只有无法解析的字段恢复默认值，写回前备份原配置
+                let backup = backup_store(app, "invalid");
+                for field in &reset {
+                    eprintln!("配置字段解析失败，已恢复默认值: {}", field);
+                }
+                if let Some(backup) = backup {
+                    eprintln!("原配置已备份到 {}", backup.display());
+                }
+            }
+            if migrated || !reset.is_empty() {
+                set_global_config(app, &config)?;
+            }
+            Ok(config)
+        }
+        Err(e) => {
+            // 整个配置都无法使用时才恢复默认配置
+            eprintln!("配置解析失败: {}，恢复默认配置", e);
+            if let Some(backup) = backup_store(app, "invalid") {
+                eprintln!("原配置已备份到 {}", backup.display());
+            }
+            init_global_config(app)
+        }
     }
 }
 
+/// 把 store.json 复制为 `store.<suffix>.bak.json`，失败时只记录日志，不影响读取配置
+fn backup_store<R: Runtime>(app: &AppHandle<R>, suffix: &str) -> Option<PathBuf> {
+    let result = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|dir| {
+            let path = dir.join("store.json");
+            let backup = path.with_file_name(format!("store.{}.bak.json", suffix));
+            std::fs::copy(&path, &backup)
+                .map(|_| backup)
+                .map_err(|e| e.to_string())
+        });
+    result.map_err(|e| eprintln!("备份配置失败: {}", e)).ok()
+}
+
 pub fn init_global_config<R: Runtime>(
     app: &AppHandle<R>,
 ) -> Result<GlobalConfig, Box<dyn std::error::Error>> {
@@ -0,0 +1,21 @@
+use crate::my_config::{self, DictionaryConfig};
+use crate::my_dictionary::{self, DictionaryInfo, OfflineDictionaries};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn get_dictionary_config(app: AppHandle) -> Result<DictionaryConfig, String> {
+    my_config::get_global_config(&app)
+        .map(|config| config.dictionary)
+        .map_err(|e| format!("获取配置失败: {}", e))
+}
+
+#[tauri::command]
+pub fn set_dictionary_config(app: AppHandle, config: DictionaryConfig) -> Result<(), String> {
+    my_dictionary::set_dictionary_config(&app, config)
+}
+
+/// 已加载的离线词典，修改目录后需等待后台加载完成
+#[tauri::command]
+pub fn list_offline_dictionaries(state: State<'_, OfflineDictionaries>) -> Vec<DictionaryInfo> {
+    state.infos()
+}
@@ -0,0 +1,207 @@
+//! 单词查询结果。模型以 JSON 返回，字段与前端的 `DictionaryEntry` 一致
+
+use crate::utils::translation_manager::StructuredReply;
+use serde::{Deserialize, Serialize};
+
+pub const DICTIONARY_SYSTEM_PROMPT: &str = r#"你是一部英汉双解词典。用户给出一个词，你只输出一个 JSON 对象，不要输出任何其他内容。格式如下：
+{"word":"run","phonetics":[{"region":"UK","ipa":"rʌn"},{"region":"US","ipa":"rʌn"}],"senses":[{"partOfSpeech":"v.","definition":"跑；奔跑","examples":[{"text":"He runs every morning.","translation":"他每天早上跑步。"}]}],"forms":[{"kind":"过去式","word":"ran"},{"kind":"过去分词","word":"run"}]}
+word 为单词原形。英文单词的 definition 用中文；中文词语的 definition 给出对应的英文单词，phonetics 使用拼音。按常用程度列出释义，每个释义最多两个例句；没有的字段输出空数组。"#;
+
+pub fn build_prompt(word: &str) -> String {
+    format!("查询：{}", word.trim())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryEntry {
+    pub word: String,
+    #[serde(default)]
+    pub phonetics: Vec<Phonetic>,
+    pub senses: Vec<Sense>,
+    /// 词形变化，如过去式、复数
+    #[serde(default)]
+    pub forms: Vec<WordForm>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Phonetic {
+    /// 英音 "UK"、美音 "US" 等
+    #[serde(default)]
+    pub region: Option<String>,
+    pub ipa: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sense {
+    /// 词性缩写，如 "n."、"v."
+    pub part_of_speech: String,
+    pub definition: String,
+    #[serde(default)]
+    pub examples: Vec<Example>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Example {
+    pub text: String,
+    #[serde(default)]
+    pub translation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordForm {
+    pub kind: String,
+    pub word: String,
+}
+
+impl Sense {
+    /// 词性和释义，离线词典的释义可能没有词性
+    fn label(&self) -> String {
+        if self.part_of_speech.is_empty() {
+            self.definition.clone()
+        } else {
+            format!("{} {}", self.part_of_speech, self.definition)
+        }
+    }
+}
+
+fn is_blank(text: &str) -> bool {
+    text.trim().is_empty()
+}
+
+impl DictionaryEntry {
+    fn phonetics_text(&self) -> String {
+        self.phonetics
+            .iter()
+            .map(|phonetic| match &phonetic.region {
+                Some(region) => format!("{} /{}/", region, phonetic.ipa),
+                None => format!("/{}/", phonetic.ipa),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    /// 单行摘要：单词、第一个音标和各释义，用于气泡
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.word.clone()];
+        if let Some(phonetic) = self.phonetics.first() {
+            parts.push(format!("/{}/", phonetic.ipa));
+        }
+        parts.push(
+            self.senses
+                .iter()
+                .map(Sense::label)
+                .collect::<Vec<_>>()
+                .join("；"),
+        );
+        parts.join(" ")
+    }
+}
+
+impl StructuredReply for DictionaryEntry {
+    fn validate(&self) -> Result<(), String> {
+        if is_blank(&self.word) {
+            return Err("word 不能为空".to_string());
+        }
+        if self.senses.is_empty() {
+            return Err("senses 至少需要一个释义".to_string());
+        }
+        if self
+            .phonetics
+            .iter()
+            .any(|phonetic| is_blank(&phonetic.ipa))
+        {
+            return Err("phonetics 中的 ipa 不能为空".to_string());
+        }
+        for sense in &self.senses {
+            if is_blank(&sense.definition) {
+                return Err("senses 中的 definition 不能为空".to_string());
+            }
+            if sense.examples.iter().any(|example| is_blank(&example.text)) {
+                return Err("examples 中的 text 不能为空".to_string());
+            }
+        }
+        if self.forms.iter().any(|form| is_blank(&form.word)) {
+            return Err("forms 中的 word 不能为空".to_string());
+        }
+        Ok(())
+    }
+
+    /// Markdown 格式的释义
+    fn to_text(&self) -> String {
+        let mut lines = vec![format!("**{}**  {}", self.word, self.phonetics_text())
+            .trim_end()
+            .to_string()];
+        lines.push(String::new());
+        for (i, sense) in self.senses.iter().enumerate() {
+            match sense.part_of_speech.as_str() {
+                "" => lines.push(format!("{}. {}", i + 1, sense.definition)),
+                part_of_speech => lines.push(format!(
+                    "{}. *{}* {}",
+                    i + 1,
+                    part_of_speech,
+                    sense.definition
+                )),
+            }
+            for example in &sense.examples {
+                match &example.translation {
+                    Some(translation) => {
+                        lines.push(format!("   - {} {}", example.text, translation))
+                    }
+                    None => lines.push(format!("   - {}", example.text)),
+                }
+            }
+        }
+        if !self.forms.is_empty() {
+            lines.push(String::new());
+            lines.push(
+                self.forms
+                    .iter()
+                    .map(|form| format!("{} {}", form.kind, form.word))
+                    .collect::<Vec<_>>()
+                    .join(" · "),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::translation_manager::parse_structured;
+
+    const REPLY: &str = r#"```json
+{"word":"run","phonetics":[{"region":"US","ipa":"rʌn"}],
+ "senses":[{"partOfSpeech":"v.","definition":"跑","examples":[{"text":"Run!","translation":"快跑！"}]},
+           {"partOfSpeech":"n.","definition":"跑步"}],
+ "forms":[{"kind":"过去式","word":"ran"}]}
+```"#;
+
+    #[test]
+    fn test_parse_entry() {
+        let entry: DictionaryEntry = parse_structured(REPLY).unwrap();
+        assert_eq!(entry.word, "run");
+        assert_eq!(entry.senses[1].examples, vec![]);
+        assert_eq!(entry.summary(), "run /rʌn/ v. 跑；n. 跑步");
+        assert_eq!(
+            entry.to_text(),
+            "**run**  US /rʌn/\n\n1. *v.* 跑\n   - Run! 快跑！\n2. *n.* 跑步\n\n过去式 ran"
+        );
+    }
+
+    #[test]
+    fn test_invalid_entry() {
+        assert!(parse_structured::<DictionaryEntry>("run: 跑").is_err());
+        let error =
+            parse_structured::<DictionaryEntry>(r#"{"word":"run","senses":[]}"#).unwrap_err();
+        assert!(error.contains("senses"));
+        assert!(parse_structured::<DictionaryEntry>(
+            r#"{"word":"run","senses":[{"partOfSpeech":"v.","definition":" "}]}"#
+        )
+        .is_err());
+    }
+}
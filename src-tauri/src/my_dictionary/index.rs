@@ -0,0 +1,294 @@
+//! 离线词典的磁盘索引：按小写词头排序，记录词条在数据文件中的位置
+//!
+//! 索引保存在缓存目录，记录数据文件的大小和修改时间，数据文件变化后重新建立。
+
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+const MAGIC: &[u8; 8] = b"OKDIDX01";
+
+/// 建立索引时的一条记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub word: String,
+    pub offset: u64,
+    pub size: u32,
+}
+
+/// 词条在数据文件中的位置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub offset: u64,
+    pub size: u32,
+}
+
+/// 数据文件的大小和修改时间，用于判断索引是否过期
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fingerprint {
+    len: u64,
+    modified: u64,
+}
+
+impl Fingerprint {
+    pub fn of(path: &Path) -> Result<Self, String> {
+        let metadata =
+            fs::metadata(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_secs());
+        Ok(Self {
+            len: metadata.len(),
+            modified,
+        })
+    }
+}
+
+/// 记录紧凑保存：所有词头拼接为一个字符串，记录只保存词头的范围，
+/// ECDICT 这样几十万词条的词典也只占十几 MB 内存
+#[derive(Debug, Clone, Copy)]
+struct Record {
+    key_start: u32,
+    key_len: u16,
+    offset: u64,
+    size: u32,
+}
+
+const RECORD_BYTES: usize = 4 + 2 + 8 + 4;
+
+#[derive(Debug, Default)]
+pub struct DiskIndex {
+    keys: String,
+    records: Vec<Record>,
+}
+
+/// 查找时忽略大小写和首尾空白
+pub fn normalize_key(word: &str) -> String {
+    word.trim().to_lowercase()
+}
+
+impl DiskIndex {
+    pub fn new(entries: Vec<IndexEntry>) -> Self {
+        let mut entries: Vec<_> = entries
+            .into_iter()
+            .map(|entry| (normalize_key(&entry.word), entry.offset, entry.size))
+            .filter(|(key, _, _)| !key.is_empty() && key.len() <= u16::MAX as usize)
+            .collect();
+        // 稳定排序，同一词头的多个词条保持在数据文件中的顺序
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut index = DiskIndex::default();
+        for (key, offset, size) in entries {
+            index.records.push(Record {
+                key_start: index.keys.len() as u32,
+                key_len: key.len() as u16,
+                offset,
+                size,
+            });
+            index.keys.push_str(&key);
+        }
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    fn key(&self, record: &Record) -> &str {
+        let start = record.key_start as usize;
+        &self.keys[start..start + record.key_len as usize]
+    }
+
+    /// 词头对应的所有词条位置
+    pub fn find(&self, word: &str) -> Vec<Location> {
+        let key = normalize_key(word);
+        let start = self
+            .records
+            .partition_point(|record| self.key(record) < key.as_str());
+        self.records[start..]
+            .iter()
+            .take_while(|record| self.key(record) == key)
+            .map(|record| Location {
+                offset: record.offset,
+                size: record.size,
+            })
+            .collect()
+    }
+
+    fn encode(&self, fingerprint: Fingerprint) -> Vec<u8> {
+        let mut bytes =
+            Vec::with_capacity(32 + self.keys.len() + self.records.len() * RECORD_BYTES);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&fingerprint.len.to_le_bytes());
+        bytes.extend_from_slice(&fingerprint.modified.to_le_bytes());
+        bytes.extend_from_slice(&(self.keys.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(self.keys.as_bytes());
+        bytes.extend_from_slice(&(self.records.len() as u32).to_le_bytes());
+        for record in &self.records {
+            bytes.extend_from_slice(&record.key_start.to_le_bytes());
+            bytes.extend_from_slice(&record.key_len.to_le_bytes());
+            bytes.extend_from_slice(&record.offset.to_le_bytes());
+            bytes.extend_from_slice(&record.size.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// 解析索引文件，格式不对、数据文件已变化或内容损坏时返回 None
+    fn decode(bytes: &[u8], fingerprint: Fingerprint) -> Option<Self> {
+        let mut reader = ByteReader(bytes);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return None;
+        }
+        let stored = Fingerprint {
+            len: reader.u64()?,
+            modified: reader.u64()?,
+        };
+        if stored != fingerprint {
+            return None;
+        }
+        let keys_len = reader.u32()? as usize;
+        let keys = std::str::from_utf8(reader.take(keys_len)?)
+            .ok()?
+            .to_string();
+        let count = reader.u32()? as usize;
+        if reader.0.len() != count * RECORD_BYTES {
+            return None;
+        }
+        let mut records = Vec::with_capacity(count);
+        for _ in 0..count {
+            let record = Record {
+                key_start: reader.u32()?,
+                key_len: reader.u16()?,
+                offset: reader.u64()?,
+                size: reader.u32()?,
+            };
+            let start = record.key_start as usize;
+            keys.get(start..start + record.key_len as usize)?;
+            records.push(record);
+        }
+        Some(Self { keys, records })
+    }
+
+    /// 读取缓存的索引，不存在或已过期时用 `build` 重新建立并保存
+    pub fn load_or_build(
+        index_path: &Path,
+        fingerprint: Fingerprint,
+        build: impl FnOnce() -> Result<Vec<IndexEntry>, String>,
+    ) -> Result<Self, String> {
+        if let Some(index) = fs::read(index_path)
+            .ok()
+            .and_then(|bytes| Self::decode(&bytes, fingerprint))
+        {
+            return Ok(index);
+        }
+
+        let index = Self::new(build()?);
+        if let Some(dir) = index_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("创建索引目录失败: {}", e))?;
+        }
+        // 保存失败不影响本次使用，下次启动重新建立
+        if let Err(e) = fs::write(index_path, index.encode(fingerprint)) {
+            eprintln!("保存词典索引失败: {}", e);
+        }
+        Ok(index)
+    }
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, offset: u64) -> IndexEntry {
+        IndexEntry {
+            word: word.to_string(),
+            offset,
+            size: 10,
+        }
+    }
+
+    fn offsets(index: &DiskIndex, word: &str) -> Vec<u64> {
+        index
+            .find(word)
+            .into_iter()
+            .map(|location| location.offset)
+            .collect()
+    }
+
+    #[test]
+    fn test_find() {
+        let index = DiskIndex::new(vec![
+            entry("run", 0),
+            entry("Apple", 10),
+            entry("Run", 20),
+            entry("running", 30),
+        ]);
+        assert_eq!(index.len(), 4);
+        assert_eq!(offsets(&index, " RUN "), [0, 20]);
+        assert_eq!(offsets(&index, "apple"), [10]);
+        assert!(offsets(&index, "ru").is_empty());
+        assert!(offsets(&index, "zebra").is_empty());
+    }
+
+    #[test]
+    fn test_load_or_build() {
+        let path = std::env::temp_dir().join("okey_ai_dictionary_index_test.idx");
+        let _ = fs::remove_file(&path);
+        let fingerprint = Fingerprint {
+            len: 100,
+            modified: 1,
+        };
+
+        let built = DiskIndex::load_or_build(&path, fingerprint, || {
+            Ok(vec![entry("run", 0), entry("跑", 10)])
+        })
+        .unwrap();
+        assert_eq!(offsets(&built, "跑"), [10]);
+
+        // 索引有效时不再建立
+        let loaded =
+            DiskIndex::load_or_build(&path, fingerprint, || Err("不应重建".to_string())).unwrap();
+        assert_eq!(offsets(&loaded, "run"), [0]);
+
+        // 数据文件变化后重新建立
+        let changed = Fingerprint {
+            len: 200,
+            modified: 1,
+        };
+        let rebuilt =
+            DiskIndex::load_or_build(&path, changed, || Ok(vec![entry("walk", 5)])).unwrap();
+        assert_eq!(offsets(&rebuilt, "walk"), [5]);
+        assert!(offsets(&rebuilt, "run").is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+}
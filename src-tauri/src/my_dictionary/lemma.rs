@@ -0,0 +1,220 @@
+//! 英文词形还原：由屈折变化形式推出可能的原形，供离线词典依次查询
+//!
+//! 先查不规则变化表，再按后缀规则生成候选。候选不保证是真实的单词，查不到的由词典过滤。
+
+/// 常见的不规则变化
+const IRREGULAR_FORMS: &[(&str, &str)] = &[
+    ("am", "be"),
+    ("is", "be"),
+    ("are", "be"),
+    ("was", "be"),
+    ("were", "be"),
+    ("been", "be"),
+    ("has", "have"),
+    ("had", "have"),
+    ("does", "do"),
+    ("did", "do"),
+    ("done", "do"),
+    ("went", "go"),
+    ("gone", "go"),
+    ("ran", "run"),
+    ("saw", "see"),
+    ("seen", "see"),
+    ("came", "come"),
+    ("took", "take"),
+    ("taken", "take"),
+    ("made", "make"),
+    ("said", "say"),
+    ("got", "get"),
+    ("gotten", "get"),
+    ("gave", "give"),
+    ("given", "give"),
+    ("knew", "know"),
+    ("known", "know"),
+    ("thought", "think"),
+    ("brought", "bring"),
+    ("bought", "buy"),
+    ("caught", "catch"),
+    ("taught", "teach"),
+    ("found", "find"),
+    ("told", "tell"),
+    ("felt", "feel"),
+    ("left", "leave"),
+    ("kept", "keep"),
+    ("held", "hold"),
+    ("met", "meet"),
+    ("paid", "pay"),
+    ("sent", "send"),
+    ("spent", "spend"),
+    ("built", "build"),
+    ("lost", "lose"),
+    ("slept", "sleep"),
+    ("sold", "sell"),
+    ("sat", "sit"),
+    ("stood", "stand"),
+    ("understood", "understand"),
+    ("began", "begin"),
+    ("begun", "begin"),
+    ("wrote", "write"),
+    ("written", "write"),
+    ("spoke", "speak"),
+    ("spoken", "speak"),
+    ("broke", "break"),
+    ("broken", "break"),
+    ("chose", "choose"),
+    ("chosen", "choose"),
+    ("drove", "drive"),
+    ("driven", "drive"),
+    ("ate", "eat"),
+    ("eaten", "eat"),
+    ("fell", "fall"),
+    ("fallen", "fall"),
+    ("flew", "fly"),
+    ("flown", "fly"),
+    ("grew", "grow"),
+    ("grown", "grow"),
+    ("drew", "draw"),
+    ("drawn", "draw"),
+    ("threw", "throw"),
+    ("thrown", "throw"),
+    ("forgot", "forget"),
+    ("forgotten", "forget"),
+    ("sang", "sing"),
+    ("sung", "sing"),
+    ("drank", "drink"),
+    ("drunk", "drink"),
+    ("swam", "swim"),
+    ("swum", "swim"),
+    ("won", "win"),
+    ("men", "man"),
+    ("women", "woman"),
+    ("children", "child"),
+    ("people", "person"),
+    ("feet", "foot"),
+    ("teeth", "tooth"),
+    ("mice", "mouse"),
+    ("geese", "goose"),
+    ("better", "good"),
+    ("best", "good"),
+    ("worse", "bad"),
+    ("worst", "bad"),
+];
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// 以“辅音+元音+辅音”结尾，如 hop、bak，原形多半以 e 结尾
+fn ends_with_cvc(stem: &str) -> bool {
+    let chars: Vec<char> = stem.chars().collect();
+    match chars[..] {
+        [.., a, b, c] => {
+            !is_vowel(a) && is_vowel(b) && !is_vowel(c) && !matches!(c, 'w' | 'x' | 'y')
+        }
+        _ => false,
+    }
+}
+
+/// 去掉重复的末尾辅音，如 runn -> run
+fn undouble(stem: &str) -> Option<&str> {
+    let mut chars = stem.chars().rev();
+    let (last, previous) = (chars.next()?, chars.next()?);
+    (last == previous && !is_vowel(last)).then(|| &stem[..stem.len() - 1])
+}
+
+/// -ing、-ed、-er、-est 去掉后缀后的候选
+fn verb_stems(stem: &str, candidates: &mut Vec<String>) {
+    if let Some(base) = undouble(stem) {
+        candidates.push(base.to_string());
+    }
+    let with_e = format!("{}e", stem);
+    if ends_with_cvc(stem) {
+        candidates.push(with_e);
+        candidates.push(stem.to_string());
+    } else {
+        candidates.push(stem.to_string());
+        candidates.push(with_e);
+    }
+}
+
+/// 按可能性排列的候选原形，第一个是原词，第二个是其小写形式
+pub fn candidates(word: &str) -> Vec<String> {
+    let word = word.trim();
+    let lower = word.to_lowercase();
+    let mut candidates = vec![word.to_string(), lower.clone()];
+    if !lower
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c == '\'' || c == '-')
+    {
+        candidates.dedup();
+        return candidates;
+    }
+
+    let lower = lower.strip_suffix("'s").unwrap_or(&lower).to_string();
+    candidates.push(lower.clone());
+    if let Some((_, base)) = IRREGULAR_FORMS.iter().find(|(form, _)| *form == lower) {
+        candidates.push(base.to_string());
+    }
+
+    let suffix = |suffix: &str| {
+        lower
+            .strip_suffix(suffix)
+            .filter(|stem| stem.chars().count() >= 2)
+    };
+    if let Some(stem) = suffix("ies").or_else(|| suffix("ied")) {
+        candidates.push(format!("{}y", stem));
+    }
+    if let Some(stem) = suffix("ier").or_else(|| suffix("iest")) {
+        candidates.push(format!("{}y", stem));
+    }
+    if let Some(stem) = suffix("ves") {
+        candidates.push(format!("{}f", stem));
+        candidates.push(format!("{}fe", stem));
+    }
+    if let Some(stem) = suffix("es") {
+        candidates.push(stem.to_string());
+    }
+    if let Some(stem) = suffix("s").filter(|stem| !stem.ends_with('s')) {
+        candidates.push(stem.to_string());
+    }
+    for ending in ["ing", "ed", "est", "er"] {
+        if let Some(stem) = suffix(ending) {
+            verb_stems(stem, &mut candidates);
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|candidate| seen.insert(candidate.clone()));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_base(word: &str) -> String {
+        candidates(word)
+            .into_iter()
+            .find(|candidate| candidate.to_lowercase() != word.to_lowercase())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_candidates() {
+        assert_eq!(candidates("Hello"), ["Hello", "hello"]);
+        assert_eq!(candidates("你好"), ["你好"]);
+        assert_eq!(first_base("went"), "go");
+        assert_eq!(first_base("studies"), "study");
+        assert_eq!(first_base("happier"), "happy");
+        assert_eq!(first_base("running"), "run");
+        assert_eq!(first_base("stopped"), "stop");
+        assert_eq!(first_base("hoping"), "hope");
+        assert_eq!(first_base("jumped"), "jump");
+        assert_eq!(first_base("cats"), "cat");
+        assert_eq!(first_base("John's"), "john");
+        assert!(candidates("boxes").contains(&"box".to_string()));
+        assert!(candidates("knives").contains(&"knife".to_string()));
+        assert!(candidates("opened").contains(&"open".to_string()));
+        assert!(!candidates("glass").contains(&"glas".to_string()));
+    }
+}
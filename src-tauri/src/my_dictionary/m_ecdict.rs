@@ -0,0 +1,314 @@
+//! ECDICT 词典的 CSV 版本（ecdict.csv）：首行为列名，之后每行一个词条，字段中的换行转义为 `\n`
+//!
+//! 屈折变化形式的词条（如 ran）在 exchange 列中记录原形，没有释义时转查原形。
+
+use crate::my_dictionary::entry::{DictionaryEntry, Phonetic, WordForm};
+use crate::my_dictionary::index::{DiskIndex, Fingerprint, IndexEntry, Location};
+use crate::my_dictionary::traits::{self, OfflineDictionary};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// exchange 列中的变化类型，"1" 记录原形的变化方式，不显示
+const EXCHANGE_KINDS: &[(&str, &str)] = &[
+    ("0", "原形"),
+    ("p", "过去式"),
+    ("d", "过去分词"),
+    ("i", "现在分词"),
+    ("3", "第三人称单数"),
+    ("s", "复数"),
+    ("r", "比较级"),
+    ("t", "最高级"),
+];
+
+/// 拆分一行 CSV，支持双引号包围的字段和其中的 `""` 转义
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            (c, _) => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// 各列的位置，按首行的列名确定
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Columns {
+    word: usize,
+    phonetic: Option<usize>,
+    definition: Option<usize>,
+    translation: usize,
+    exchange: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(header: &str) -> Result<Self, String> {
+        let names = parse_csv_line(header.trim_start_matches('\u{feff}').trim_end());
+        let column = |name: &str| names.iter().position(|column| column.trim() == name);
+        Ok(Self {
+            word: column("word").ok_or("不是 ECDICT 格式：缺少 word 列")?,
+            phonetic: column("phonetic"),
+            definition: column("definition"),
+            translation: column("translation").ok_or("不是 ECDICT 格式：缺少 translation 列")?,
+            exchange: column("exchange"),
+        })
+    }
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", "\n").replace("\\r", "")
+}
+
+/// 解析 exchange 列，如 "p:ran/d:run/i:running/3:runs"，返回词形变化和原形
+fn parse_exchange(exchange: &str) -> (Vec<WordForm>, Option<String>) {
+    let mut forms = Vec::new();
+    let mut lemma = None;
+    for (kind, word) in exchange.split('/').filter_map(|item| item.split_once(':')) {
+        if word.is_empty() {
+            continue;
+        }
+        if kind == "0" {
+            lemma = Some(word.to_string());
+        }
+        if let Some((_, name)) = EXCHANGE_KINDS.iter().find(|(code, _)| *code == kind) {
+            forms.push(WordForm {
+                kind: name.to_string(),
+                word: word.to_string(),
+            });
+        }
+    }
+    (forms, lemma)
+}
+
+/// 转为词条，优先使用中文释义；没有任何释义时返回 None 和原形
+fn row_to_entry(row: &[String], columns: Columns) -> (Option<DictionaryEntry>, Option<String>) {
+    let field = |index: Option<usize>| {
+        index
+            .and_then(|index| row.get(index))
+            .map(|value| unescape(value))
+            .unwrap_or_default()
+    };
+    let word = field(Some(columns.word));
+    let (forms, lemma) = parse_exchange(&field(columns.exchange));
+
+    let mut text = field(Some(columns.translation));
+    if text.trim().is_empty() {
+        text = field(columns.definition);
+    }
+    let (_, senses) = traits::parse_definition(&word, &text);
+    if senses.is_empty() {
+        return (None, lemma);
+    }
+
+    let phonetic = field(columns.phonetic);
+    let phonetics = (!phonetic.trim().is_empty())
+        .then(|| Phonetic {
+            region: None,
+            ipa: phonetic.trim().to_string(),
+        })
+        .into_iter()
+        .collect();
+    let entry = DictionaryEntry {
+        word,
+        phonetics,
+        senses,
+        forms,
+    };
+    (Some(entry), lemma)
+}
+
+/// 逐行读取建立索引，记录每行的位置，不含换行符
+fn build_index(path: &Path) -> Result<Vec<IndexEntry>, String> {
+    let file = File::open(path).map_err(|e| format!("打开 {} 失败: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let mut entries = Vec::new();
+    let mut line = Vec::new();
+    let mut offset = 0u64;
+    let mut is_header = true;
+    loop {
+        line.clear();
+        let len = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+        if len == 0 {
+            break;
+        }
+        let content = line.strip_suffix(b"\n").unwrap_or(&line);
+        let content = content.strip_suffix(b"\r").unwrap_or(content);
+        if !std::mem::take(&mut is_header) {
+            let text = String::from_utf8_lossy(content);
+            // 词头在第一列，只需解析到第一个字段
+            let word = match text.strip_prefix('"') {
+                Some(_) => parse_csv_line(&text).swap_remove(0),
+                None => text.split(',').next().unwrap_or_default().to_string(),
+            };
+            entries.push(IndexEntry {
+                word,
+                offset,
+                size: content.len() as u32,
+            });
+        }
+        offset += len as u64;
+    }
+    Ok(entries)
+}
+
+pub struct Ecdict {
+    name: String,
+    path: PathBuf,
+    columns: Columns,
+    index: DiskIndex,
+}
+
+impl Ecdict {
+    /// 打开 CSV 格式的词典，索引保存在 `cache_dir`
+    pub fn open(path: &Path, cache_dir: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("打开 {} 失败: {}", path.display(), e))?;
+        let mut header = String::new();
+        BufReader::new(file)
+            .read_line(&mut header)
+            .map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+        let columns = Columns::from_header(&header)?;
+
+        let file_name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let index = DiskIndex::load_or_build(
+            &cache_dir.join(format!("{}.index", file_name)),
+            Fingerprint::of(path)?,
+            || build_index(path),
+        )?;
+
+        Ok(Self {
+            name: path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            path: path.to_path_buf(),
+            columns,
+            index,
+        })
+    }
+
+    fn read_row(&self, file: &mut File, location: Location) -> Result<Vec<String>, String> {
+        let mut data = vec![0; location.size as usize];
+        file.seek(SeekFrom::Start(location.offset))
+            .and_then(|_| file.read_exact(&mut data))
+            .map_err(|e| format!("读取词典 {} 失败: {}", self.name, e))?;
+        Ok(parse_csv_line(&String::from_utf8_lossy(&data)))
+    }
+
+    /// 查询词头，返回词条或没有释义时记录的原形
+    fn lookup_row(&self, word: &str) -> Result<(Option<DictionaryEntry>, Option<String>), String> {
+        let Some(location) = self.index.find(word).into_iter().next() else {
+            return Ok((None, None));
+        };
+        let mut file = File::open(&self.path)
+            .map_err(|e| format!("打开 {} 失败: {}", self.path.display(), e))?;
+        let row = self.read_row(&mut file, location)?;
+        Ok(row_to_entry(&row, self.columns))
+    }
+}
+
+impl OfflineDictionary for Ecdict {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn word_count(&self) -> usize {
+        self.index.len()
+    }
+
+    fn lookup(&self, word: &str) -> Result<Option<DictionaryEntry>, String> {
+        match self.lookup_row(word)? {
+            (Some(entry), _) => Ok(Some(entry)),
+            (None, Some(lemma)) if !lemma.eq_ignore_ascii_case(word.trim()) => {
+                Ok(self.lookup_row(&lemma)?.0)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str =
+        "word,phonetic,definition,translation,pos,collins,oxford,tag,bnc,frq,exchange,detail,audio";
+
+    #[test]
+    fn test_parse_csv_line() {
+        assert_eq!(
+            parse_csv_line(r#"run,rʌn,"a score, in baseball","n. 跑步\nv. ""跑""",,"#),
+            [
+                "run",
+                "rʌn",
+                "a score, in baseball",
+                r#"n. 跑步\nv. "跑""#,
+                "",
+                ""
+            ]
+        );
+    }
+
+    #[test]
+    fn test_row_to_entry() {
+        let columns = Columns::from_header(HEADER).unwrap();
+        assert!(Columns::from_header("word,phonetic").is_err());
+
+        let row = parse_csv_line(
+            r#"run,rʌn,v. move fast,"n. 跑步\nv. 跑, 奔跑",,,,,,,p:ran/d:run/i:running/3:runs,,"#,
+        );
+        let (entry, lemma) = row_to_entry(&row, columns);
+        let entry = entry.unwrap();
+        assert_eq!(lemma, None);
+        assert_eq!(entry.summary(), "run /rʌn/ n. 跑步；v. 跑, 奔跑");
+        assert_eq!(entry.forms[0].kind, "过去式");
+        assert_eq!(entry.forms.len(), 4);
+
+        let row = parse_csv_line("ran,,,,,,,,,,0:run/1:p,,");
+        let (entry, lemma) = row_to_entry(&row, columns);
+        assert!(entry.is_none());
+        assert_eq!(lemma.as_deref(), Some("run"));
+    }
+
+    #[test]
+    fn test_lookup() {
+        let dir = std::env::temp_dir().join("okey_ai_ecdict_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ecdict.csv");
+        let rows = [
+            HEADER,
+            r#""apple",ˈæpl,,n. 苹果,,,,,,,s:apples,,"#,
+            "ran,,,,,,,,,,0:run/1:p,,",
+            "run,rʌn,,v. 跑,,,,,,,p:ran,,",
+        ];
+        std::fs::write(&path, rows.join("\r\n")).unwrap();
+
+        let dictionary = Ecdict::open(&path, &dir.join("cache")).unwrap();
+        assert_eq!(dictionary.name(), "ecdict");
+        assert_eq!(dictionary.word_count(), 3);
+        let apple = dictionary.lookup("Apple").unwrap().unwrap();
+        assert_eq!(apple.senses[0].definition, "苹果");
+        assert_eq!(dictionary.lookup("ran").unwrap().unwrap().word, "run");
+        assert!(dictionary.lookup("walk").unwrap().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
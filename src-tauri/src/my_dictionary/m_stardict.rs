@@ -0,0 +1,355 @@
+//! StarDict 词典：.ifo 为词典信息，.idx（或 .idx.gz）为词头索引，.dict（或 .dict.dz）为释义数据
+//!
+//! .dict.dz 首次打开时解压到缓存目录，之后按索引中的位置直接读取。
+
+use crate::my_dictionary::entry::{DictionaryEntry, Phonetic};
+use crate::my_dictionary::index::{DiskIndex, Fingerprint, IndexEntry, Location};
+use crate::my_dictionary::traits::{self, OfflineDictionary};
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const IFO_HEADER: &str = "StarDict's dict ifo file";
+
+#[derive(Debug, PartialEq)]
+struct IfoInfo {
+    name: String,
+    /// 每个词条包含的字段类型，为空时每个字段自带类型标记
+    same_type_sequence: Option<String>,
+    /// idx 中的偏移量为 64 位
+    offset_bits_64: bool,
+}
+
+fn parse_ifo(text: &str) -> Result<IfoInfo, String> {
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some(IFO_HEADER) {
+        return Err("不是 StarDict 的 .ifo 文件".to_string());
+    }
+    let mut info = IfoInfo {
+        name: String::new(),
+        same_type_sequence: None,
+        offset_bits_64: false,
+    };
+    for (key, value) in lines.filter_map(|line| line.split_once('=')) {
+        match key.trim() {
+            "bookname" => info.name = value.trim().to_string(),
+            "sametypesequence" => info.same_type_sequence = Some(value.trim().to_string()),
+            "idxoffsetbits" => info.offset_bits_64 = value.trim() == "64",
+            _ => {}
+        }
+    }
+    Ok(info)
+}
+
+/// 读取文件，.gz 和 .dz 结尾的先解压
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    if !path
+        .extension()
+        .is_some_and(|ext| ext == "gz" || ext == "dz")
+    {
+        return Ok(bytes);
+    }
+    let mut decoded = Vec::new();
+    GzDecoder::new(bytes.as_slice())
+        .read_to_end(&mut decoded)
+        .map_err(|e| format!("解压 {} 失败: {}", path.display(), e))?;
+    Ok(decoded)
+}
+
+/// idx 的每条记录为：以 0 结尾的词头、偏移量（32 或 64 位）、长度（32 位），均为大端序
+fn parse_idx(bytes: &[u8], offset_bits_64: bool) -> Result<Vec<IndexEntry>, String> {
+    let offset_len = if offset_bits_64 { 8 } else { 4 };
+    let mut entries = Vec::new();
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let end = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or("idx 文件格式错误")?;
+        let word = String::from_utf8_lossy(&rest[..end]).into_owned();
+        let numbers = rest
+            .get(end + 1..end + 1 + offset_len + 4)
+            .ok_or("idx 文件格式错误")?;
+        let (offset, size) = numbers.split_at(offset_len);
+        let offset = offset
+            .iter()
+            .fold(0u64, |value, &byte| (value << 8) | byte as u64);
+        let size = u32::from_be_bytes(size.try_into().unwrap());
+        entries.push(IndexEntry { word, offset, size });
+        rest = &rest[end + 1 + offset_len + 4..];
+    }
+    Ok(entries)
+}
+
+/// 拆分词条数据中的文本字段，返回 (类型, 内容)。
+/// 小写类型为以 0 结尾的文本，大写类型为带 32 位长度的二进制数据（图片、声音等），跳过；
+/// 使用 sametypesequence 时省略类型标记，且最后一个字段没有结尾的 0 或长度
+fn parse_fields(data: &[u8], same_type_sequence: Option<&str>) -> Vec<(char, String)> {
+    let mut fields = Vec::new();
+    let mut rest = data;
+    let mut types = same_type_sequence.map(|sequence| sequence.chars().peekable());
+    loop {
+        let (kind, is_last) = match &mut types {
+            Some(types) => match types.next() {
+                Some(kind) => (kind, types.peek().is_none()),
+                None => break,
+            },
+            None => match rest.split_first() {
+                Some((&kind, tail)) => {
+                    rest = tail;
+                    (kind as char, false)
+                }
+                None => break,
+            },
+        };
+        let len = if is_last {
+            rest.len()
+        } else if kind.is_ascii_lowercase() {
+            rest.iter()
+                .position(|&byte| byte == 0)
+                .unwrap_or(rest.len())
+        } else {
+            let Some(size) = rest.get(..4) else { break };
+            rest = &rest[4..];
+            u32::from_be_bytes(size.try_into().unwrap()) as usize
+        };
+        let Some(content) = rest.get(..len) else {
+            break;
+        };
+        if kind.is_ascii_lowercase() {
+            fields.push((kind, String::from_utf8_lossy(content).into_owned()));
+        }
+        // 跳过文本字段结尾的 0
+        let skip = usize::from(kind.is_ascii_lowercase() && !is_last);
+        rest = rest.get(len + skip..).unwrap_or_default();
+    }
+    fields
+}
+
+/// 't' 为音标，'h'、'x'、'g' 为带标记的文本，其余文本类型按纯文本处理
+fn fields_to_entry(word: &str, fields: Vec<(char, String)>) -> Option<DictionaryEntry> {
+    let mut phonetics = Vec::new();
+    let mut text = String::new();
+    for (kind, content) in fields {
+        match kind {
+            't' => phonetics.push(Phonetic {
+                region: None,
+                ipa: content.trim().to_string(),
+            }),
+            'h' | 'x' | 'g' => text.push_str(&traits::strip_markup(&content)),
+            'm' | 'l' | 'y' | 'k' | 'w' => text.push_str(&content),
+            _ => continue,
+        }
+        text.push('\n');
+    }
+    let (parsed_phonetics, senses) = traits::parse_definition(word, &text);
+    if senses.is_empty() {
+        return None;
+    }
+    phonetics.extend(parsed_phonetics);
+    phonetics.retain(|phonetic| !phonetic.ipa.is_empty());
+    Some(DictionaryEntry {
+        word: word.to_string(),
+        phonetics,
+        senses,
+        forms: Vec::new(),
+    })
+}
+
+/// 与 .ifo 同名、扩展名为 `extensions` 之一的文件
+fn sibling(ifo_path: &Path, extensions: &[&str]) -> Option<PathBuf> {
+    extensions
+        .iter()
+        .map(|ext| ifo_path.with_extension(ext))
+        .find(|path| path.exists())
+}
+
+fn is_outdated(target: &Path, source: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+    match (modified(target), modified(source)) {
+        (Ok(target), Ok(source)) => target < source,
+        _ => true,
+    }
+}
+
+pub struct StarDict {
+    name: String,
+    /// 未压缩的 .dict 文件
+    data_path: PathBuf,
+    same_type_sequence: Option<String>,
+    index: DiskIndex,
+}
+
+impl StarDict {
+    /// 打开 .ifo 描述的词典，索引和解压后的数据保存在 `cache_dir`
+    pub fn open(ifo_path: &Path, cache_dir: &Path) -> Result<Self, String> {
+        let ifo = fs::read_to_string(ifo_path)
+            .map_err(|e| format!("读取 {} 失败: {}", ifo_path.display(), e))?;
+        let info = parse_ifo(&ifo)?;
+        let idx_path = sibling(ifo_path, &["idx", "idx.gz"]).ok_or("找不到 .idx 文件")?;
+        let dict_path = sibling(ifo_path, &["dict", "dict.dz"]).ok_or("找不到 .dict 文件")?;
+        let file_name = ifo_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+
+        let data_path = if dict_path.extension().is_some_and(|ext| ext == "dz") {
+            let data_path = cache_dir.join(dict_path.file_stem().unwrap_or_default());
+            if is_outdated(&data_path, &dict_path) {
+                fs::create_dir_all(cache_dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+                fs::write(&data_path, read_file(&dict_path)?)
+                    .map_err(|e| format!("保存解压的词典失败: {}", e))?;
+            }
+            data_path
+        } else {
+            dict_path
+        };
+
+        let index = DiskIndex::load_or_build(
+            &cache_dir.join(format!("{}.index", file_name)),
+            Fingerprint::of(&idx_path)?,
+            || parse_idx(&read_file(&idx_path)?, info.offset_bits_64),
+        )?;
+
+        Ok(Self {
+            name: if info.name.is_empty() {
+                file_name
+            } else {
+                info.name
+            },
+            data_path,
+            same_type_sequence: info.same_type_sequence,
+            index,
+        })
+    }
+
+    fn read_record(&self, file: &mut File, location: Location) -> Result<Vec<u8>, String> {
+        let mut data = vec![0; location.size as usize];
+        file.seek(SeekFrom::Start(location.offset))
+            .and_then(|_| file.read_exact(&mut data))
+            .map_err(|e| format!("读取词典 {} 失败: {}", self.name, e))?;
+        Ok(data)
+    }
+}
+
+impl OfflineDictionary for StarDict {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn word_count(&self) -> usize {
+        self.index.len()
+    }
+
+    fn lookup(&self, word: &str) -> Result<Option<DictionaryEntry>, String> {
+        let locations = self.index.find(word);
+        if locations.is_empty() {
+            return Ok(None);
+        }
+        let mut file = File::open(&self.data_path)
+            .map_err(|e| format!("打开 {} 失败: {}", self.data_path.display(), e))?;
+        // 同一词头的多个词条合并为一个
+        let mut fields = Vec::new();
+        for location in locations {
+            let data = self.read_record(&mut file, location)?;
+            fields.extend(parse_fields(&data, self.same_type_sequence.as_deref()));
+        }
+        Ok(fields_to_entry(word.trim(), fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ifo() {
+        let info = parse_ifo(
+            "StarDict's dict ifo file\nversion=2.4.2\nbookname=朗道英汉字典\nwordcount=2\nsametypesequence=tm\n",
+        )
+        .unwrap();
+        assert_eq!(info.name, "朗道英汉字典");
+        assert_eq!(info.same_type_sequence.as_deref(), Some("tm"));
+        assert!(!info.offset_bits_64);
+        assert!(parse_ifo("bookname=x").is_err());
+    }
+
+    #[test]
+    fn test_parse_idx() {
+        let mut bytes = b"run\0".to_vec();
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 12]);
+        bytes.extend_from_slice("跑\0".as_bytes());
+        bytes.extend_from_slice(&[0, 0, 1, 0, 0, 0, 0, 5]);
+        let entries = parse_idx(&bytes, false).unwrap();
+        assert_eq!(entries[0].word, "run");
+        assert_eq!(entries[0].size, 12);
+        assert_eq!(entries[1].word, "跑");
+        assert_eq!(entries[1].offset, 256);
+        assert!(parse_idx(b"run\0\0\0", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(
+            parse_fields("rʌn\0n. 跑步\nv. 跑".as_bytes(), Some("tm")),
+            [
+                ('t', "rʌn".to_string()),
+                ('m', "n. 跑步\nv. 跑".to_string())
+            ]
+        );
+        let mut data = b"W".to_vec();
+        data.extend_from_slice(&[0, 0, 0, 2, 0xff, 0xfe]);
+        data.extend_from_slice(b"h<b>run</b><br>v. run\0");
+        assert_eq!(
+            parse_fields(&data, None),
+            [('h', "<b>run</b><br>v. run".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_fields_to_entry() {
+        let entry = fields_to_entry(
+            "run",
+            parse_fields("rʌn\0n. 跑步\nv. 跑".as_bytes(), Some("tm")),
+        )
+        .unwrap();
+        assert_eq!(entry.phonetics[0].ipa, "rʌn");
+        assert_eq!(entry.summary(), "run /rʌn/ n. 跑步；v. 跑");
+        assert!(fields_to_entry("run", vec![('t', "rʌn".to_string())]).is_none());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let dir = std::env::temp_dir().join("okey_ai_stardict_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let definitions = ["n. 苹果", "v. 跑"];
+        let mut idx = Vec::new();
+        let mut dict = Vec::new();
+        for (word, definition) in ["apple", "Run"].iter().zip(definitions) {
+            idx.extend_from_slice(word.as_bytes());
+            idx.push(0);
+            idx.extend_from_slice(&(dict.len() as u32).to_be_bytes());
+            idx.extend_from_slice(&(definition.len() as u32).to_be_bytes());
+            dict.extend_from_slice(definition.as_bytes());
+        }
+        fs::write(
+            dir.join("test.ifo"),
+            "StarDict's dict ifo file\nbookname=测试\nsametypesequence=m\n",
+        )
+        .unwrap();
+        fs::write(dir.join("test.idx"), idx).unwrap();
+        fs::write(dir.join("test.dict"), dict).unwrap();
+
+        let dictionary = StarDict::open(&dir.join("test.ifo"), &dir.join("cache")).unwrap();
+        assert_eq!(dictionary.name(), "测试");
+        assert_eq!(dictionary.word_count(), 2);
+        let entry = dictionary.lookup("run").unwrap().unwrap();
+        assert_eq!(entry.senses[0].definition, "跑");
+        assert!(dictionary.lookup("walk").unwrap().is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,273 @@
+pub mod commands;
+pub mod entry;
+pub mod index;
+pub mod lemma;
+pub mod m_ecdict;
+pub mod m_stardict;
+pub mod traits;
+
+use crate::my_config::{self, DictionaryConfig};
+use crate::utils::chat_message::ChatMessage;
+use crate::utils::translation_manager::{StructuredReply, TranslationManager};
+use entry::DictionaryEntry;
+use m_ecdict::Ecdict;
+use m_stardict::StarDict;
+use serde::Serialize;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tauri::{async_runtime, AppHandle, Manager};
+use traits::OfflineDictionary;
+
+type SharedDictionary = Arc<dyn OfflineDictionary + Send + Sync>;
+
+/// 推送给前端的查询结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryResult {
+    /// 会话中对应助手消息的文本，前端据此找到要替换显示的消息
+    pub content: String,
+    /// 单行摘要，气泡中显示
+    pub summary: String,
+    pub entry: DictionaryEntry,
+}
+
+impl DictionaryResult {
+    pub fn new(entry: DictionaryEntry) -> Self {
+        Self {
+            content: entry.to_text(),
+            summary: entry.summary(),
+            entry,
+        }
+    }
+}
+
+/// 已加载的离线词典
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryInfo {
+    pub name: String,
+    pub word_count: usize,
+}
+
+/// 离线词典，按文件路径的顺序查询
+#[derive(Default)]
+pub struct OfflineDictionaries {
+    dictionaries: RwLock<Vec<SharedDictionary>>,
+    /// 每次重新加载加一，加载完成时已有更新的加载则丢弃结果
+    generation: AtomicU64,
+}
+
+impl OfflineDictionaries {
+    pub fn infos(&self) -> Vec<DictionaryInfo> {
+        self.dictionaries
+            .read()
+            .unwrap()
+            .iter()
+            .map(|dictionary| DictionaryInfo {
+                name: dictionary.name().to_string(),
+                word_count: dictionary.word_count(),
+            })
+            .collect()
+    }
+
+    /// 依次查询原词和词形还原的候选，原词在所有词典中都查不到时才查原形
+    pub fn lookup(&self, word: &str) -> Option<DictionaryEntry> {
+        let dictionaries = self.dictionaries.read().unwrap().clone();
+        if dictionaries.is_empty() {
+            return None;
+        }
+        for candidate in lemma::candidates(word) {
+            for dictionary in &dictionaries {
+                match dictionary.lookup(&candidate) {
+                    Ok(Some(entry)) => return Some(entry),
+                    Ok(None) => {}
+                    Err(e) => eprintln!("查询离线词典 {} 失败: {}", dictionary.name(), e),
+                }
+            }
+        }
+        None
+    }
+}
+
+/// 目录及其一级子目录中的词典文件：StarDict 的 .ifo 和 ECDICT 的 .csv
+fn dictionary_files(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = read_dir.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            if depth > 0 {
+                files.extend(dictionary_files(&path, depth - 1));
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == "ifo" || ext == "csv")
+        {
+            files.push(path);
+        }
+    }
+    files
+}
+
+fn open_dictionary(path: &Path, cache_dir: &Path) -> Result<SharedDictionary, String> {
+    if path.extension().is_some_and(|ext| ext == "ifo") {
+        Ok(Arc::new(StarDict::open(path, cache_dir)?))
+    } else {
+        Ok(Arc::new(Ecdict::open(path, cache_dir)?))
+    }
+}
+
+/// 打开目录中的所有词典，首次打开时建立索引，较大的词典需要几秒。无法打开的词典跳过
+fn load_dictionaries(dir: &Path, cache_dir: &Path) -> Vec<SharedDictionary> {
+    dictionary_files(dir, 1)
+        .into_iter()
+        .filter_map(|path| {
+            open_dictionary(&path, cache_dir)
+                .map_err(|e| eprintln!("加载词典 {} 失败: {}", path.display(), e))
+                .ok()
+        })
+        .collect()
+}
+
+pub fn setup_dictionary(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let config = my_config::get_global_config(app)?.dictionary;
+    app.manage(OfflineDictionaries::default());
+    apply_dictionary_config(app, &config);
+    Ok(())
+}
+
+/// 保存单词查询配置并立即生效
+pub fn set_dictionary_config(app: &AppHandle, config: DictionaryConfig) -> Result<(), String> {
+    let mut global_config =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    global_config.dictionary = config;
+    my_config::set_global_config(app, &global_config)
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    apply_dictionary_config(app, &global_config.dictionary);
+    Ok(())
+}
+
+/// 在后台重新加载离线词典目录
+pub fn apply_dictionary_config(app: &AppHandle, config: &DictionaryConfig) {
+    let Some(offline) = app.try_state::<OfflineDictionaries>() else {
+        return;
+    };
+    let generation = offline.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let dir = config
+        .offline_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    let cache_dir = match app.path().app_cache_dir() {
+        Ok(cache_dir) => cache_dir.join("dictionary"),
+        Err(e) => {
+            eprintln!("获取缓存目录失败: {}", e);
+            return;
+        }
+    };
+
+    let app = app.clone();
+    async_runtime::spawn_blocking(move || {
+        let dictionaries = dir
+            .map(|dir| load_dictionaries(&dir, &cache_dir))
+            .unwrap_or_default();
+        let offline = app.state::<OfflineDictionaries>();
+        if offline.generation.load(Ordering::SeqCst) == generation {
+            *offline.dictionaries.write().unwrap() = dictionaries;
+        }
+    });
+}
+
+/// 是否为单个词，与自动朗读的单词模式判断一致
+pub fn is_single_word(text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty() && text.split_whitespace().count() == 1
+}
+
+/// 选中的是单个词且开启了单词查询
+pub fn should_lookup(app: &AppHandle, text: &str) -> bool {
+    is_single_word(text)
+        && my_config::get_global_config(app)
+            .map(|config| config.dictionary.enabled)
+            .unwrap_or(false)
+}
+
+/// 在新会话中查询单词：先查离线词典，查不到时请求模型。失败时返回 None，由调用方按普通翻译处理
+pub async fn lookup_word<F, Fut>(
+    app: &AppHandle,
+    word: &str,
+    initial_callback: F,
+) -> Option<(DictionaryResult, Vec<ChatMessage>)>
+where
+    F: FnOnce(Vec<ChatMessage>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let offline = app
+        .try_state::<OfflineDictionaries>()
+        .and_then(|offline| offline.lookup(word));
+    let translation_manager = app.state::<TranslationManager>();
+    translation_manager
+        .create_session_with_system_prompt(entry::DICTIONARY_SYSTEM_PROMPT)
+        .await;
+    if let Some(entry) = offline {
+        let chat_history = translation_manager
+            .record_structured(
+                None,
+                &entry::build_prompt(word),
+                Some(word.trim().to_string()),
+                &entry,
+                initial_callback,
+            )
+            .await?;
+        return Some((DictionaryResult::new(entry), chat_history));
+    }
+    let (entry, chat_history) = translation_manager
+        .complete_structured::<DictionaryEntry, _, _>(
+            None,
+            &entry::build_prompt(word),
+            Some(word.trim().to_string()),
+            initial_callback,
+        )
+        .await?;
+    Some((DictionaryResult::new(entry), chat_history))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dictionary_files() {
+        let dir = std::env::temp_dir().join("okey_ai_dictionary_files_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("langdao/nested")).unwrap();
+        for file in [
+            "ecdict.csv",
+            "readme.txt",
+            "langdao/langdao.ifo",
+            "langdao/langdao.idx",
+            "langdao/nested/deep.ifo",
+        ] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+        assert_eq!(
+            dictionary_files(&dir, 1),
+            [dir.join("ecdict.csv"), dir.join("langdao/langdao.ifo")]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_single_word() {
+        assert!(is_single_word(" hello "));
+        assert!(is_single_word("你好"));
+        assert!(!is_single_word("hello world"));
+        assert!(!is_single_word("  "));
+    }
+}
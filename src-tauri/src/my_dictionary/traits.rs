@@ -0,0 +1,131 @@
+use crate::my_dictionary::entry::{DictionaryEntry, Phonetic, Sense};
+
+/// 一个词条最多显示的释义数，StarDict 的大型词典单个词条可能有上百行
+const MAX_SENSES: usize = 12;
+
+pub trait OfflineDictionary {
+    /// 词典名称，如 StarDict 的 bookname
+    fn name(&self) -> &str;
+
+    fn word_count(&self) -> usize;
+
+    /// 按词头查询，不做词形还原，大小写不敏感；查不到时返回 Ok(None)
+    fn lookup(&self, word: &str) -> Result<Option<DictionaryEntry>, String>;
+}
+
+/// 去掉 HTML、XDXF 等标记，换行类标签转为换行，并还原常见的字符实体
+pub fn strip_markup(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut tag: Option<String> = None;
+    for c in text.chars() {
+        match (&mut tag, c) {
+            (None, '<') => tag = Some(String::new()),
+            (Some(name), '>') => {
+                let name = name.trim_start_matches('/').to_ascii_lowercase();
+                let name = name.split([' ', '/']).next().unwrap_or_default();
+                if matches!(name, "br" | "p" | "div" | "li" | "def" | "ex") {
+                    plain.push('\n');
+                }
+                tag = None;
+            }
+            (Some(name), c) => name.push(c),
+            (None, c) => plain.push(c),
+        }
+    }
+    plain
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 拆出行首的词性缩写，如 "n. 跑步" -> ("n.", "跑步")；没有词性时词性为空
+pub fn split_part_of_speech(line: &str) -> (String, String) {
+    let line = line.trim();
+    if let Some((prefix, rest)) = line.split_once('.') {
+        let is_abbreviation =
+            (1..=6).contains(&prefix.len()) && prefix.chars().all(|c| c.is_ascii_lowercase());
+        // "e.g." 这样点后紧跟字母的不是词性
+        let followed_by_text = !rest.trim().is_empty()
+            && !rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '.');
+        if is_abbreviation && followed_by_text {
+            return (format!("{}.", prefix), rest.trim().to_string());
+        }
+    }
+    (String::new(), line.to_string())
+}
+
+/// 音标行，如 "/rʌn/"、"[rʌn]"，返回去掉斜线或括号的音标
+fn phonetic_line(line: &str) -> Option<String> {
+    let line = line.trim().trim_start_matches('*');
+    let inner = line
+        .strip_prefix('/')
+        .and_then(|rest| rest.strip_suffix('/'))
+        .or_else(|| {
+            line.strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+        })?;
+    (!inner.trim().is_empty()).then(|| inner.trim().to_string())
+}
+
+/// 把纯文本释义转为音标和释义：释义前的音标行作为音标，重复词头的行跳过，其余每行一个释义
+pub fn parse_definition(word: &str, text: &str) -> (Vec<Phonetic>, Vec<Sense>) {
+    let mut phonetics = Vec::new();
+    let mut senses = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if senses.is_empty() {
+            if line.eq_ignore_ascii_case(word) {
+                continue;
+            }
+            if let Some(ipa) = phonetic_line(line) {
+                phonetics.push(Phonetic { region: None, ipa });
+                continue;
+            }
+        }
+        if senses.len() == MAX_SENSES {
+            break;
+        }
+        let (part_of_speech, definition) = split_part_of_speech(line);
+        senses.push(Sense {
+            part_of_speech,
+            definition,
+            examples: Vec::new(),
+        });
+    }
+    (phonetics, senses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_markup() {
+        assert_eq!(
+            strip_markup("<b>run</b><br/>n. 跑步 &amp; 赛跑<br>v. 跑"),
+            "run\nn. 跑步 & 赛跑\nv. 跑"
+        );
+        assert_eq!(strip_markup("&lt;b&gt;"), "<b>");
+    }
+
+    #[test]
+    fn test_split_part_of_speech() {
+        let split = split_part_of_speech;
+        assert_eq!(split("n. 跑步"), ("n.".to_string(), "跑步".to_string()));
+        assert_eq!(split("vt.运行"), ("vt.".to_string(), "运行".to_string()));
+        assert_eq!(split("[计] 运行"), (String::new(), "[计] 运行".to_string()));
+        assert_eq!(split("e.g. run fast").0, "");
+        assert_eq!(split("Mr. Smith").0, "");
+    }
+
+    #[test]
+    fn test_parse_definition() {
+        let (phonetics, senses) = parse_definition("run", "run\n*[rʌn]\nn. 跑步\n\nv. 跑");
+        assert_eq!(phonetics[0].ipa, "rʌn");
+        assert_eq!(senses.len(), 2);
+        assert_eq!(senses[1].part_of_speech, "v.");
+        assert_eq!(senses[1].definition, "跑");
+    }
+}
@@ -1,4 +1,5 @@
 pub mod event_names {
+    /// 需要页面朗读的文本，只在未配置语音合成引擎或合成失败时发送
     pub const BUBBLE_AUTO_SPEAK: &str = "BUBBLE_AUTO_SPEAK";
     pub const BUBBLE_CLEAN: &str = "BUBBLE_CLEAN";
 
@@ -6,7 +7,32 @@ pub mod event_names {
 
     pub const AI_RESPONSE_STREAM: &str = "AI_RESPONSE_STREAM";
 
+    /// 气泡流式输出，载荷为目前已收到的完整译文
+    pub const BUBBLE_STREAM: &str = "BUBBLE_STREAM";
+
+    pub const AI_RESPONSE_CHUNK: &str = "AI_RESPONSE_CHUNK";
+
     pub const AI_ERROR: &str = "AI_ERROR";
 
+    /// 单词查询的结构化结果，载荷见 `my_dictionary::DictionaryResult`
+    pub const DICTIONARY_RESULT: &str = "DICTIONARY_RESULT";
+
+    pub const CLIPBOARD_WATCH_CHANGED: &str = "CLIPBOARD_WATCH_CHANGED";
+
+    /// 用户设置变化，载荷为完整的设置
+    pub const SETTINGS_CHANGED: &str = "SETTINGS_CHANGED";
+
+    /// 切换了配置方案，载荷为方案名称
+    pub const PROFILE_CHANGED: &str = "PROFILE_CHANGED";
+
+    /// 朗读播放状态变化，载荷为 "playing"、"paused" 或 "stopped"
+    pub const TTS_STATE_CHANGED: &str = "TTS_STATE_CHANGED";
+
+    /// 录音窗口开始、结束录音
+    pub const RECORDING_START: &str = "RECORDING_START";
+    pub const RECORDING_STOP: &str = "RECORDING_STOP";
+
+    pub const OPEN_HISTORY: &str = "OPEN_HISTORY";
+
     pub const PAGE_LOADED: &str = "PAGE_LOADED";
 }
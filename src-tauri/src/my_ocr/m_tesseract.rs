@@ -0,0 +1,62 @@
+use crate::my_config::TesseractConfig;
+use crate::my_ocr::traits::OcrEngine;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::process::Command;
+
+/// 调用本地的 tesseract 识别，结果输出到标准输出
+#[derive(Debug)]
+pub struct TesseractEngine {
+    config: TesseractConfig,
+}
+
+impl TesseractEngine {
+    pub fn new(config: TesseractConfig) -> Self {
+        Self { config }
+    }
+
+    fn program(&self) -> &str {
+        match &self.config.path {
+            Some(path) if !path.is_empty() => path,
+            _ => "tesseract",
+        }
+    }
+}
+
+fn run_tesseract(program: &str, image: &Path, languages: &str) -> Result<String, String> {
+    let mut command = Command::new(program);
+    command.arg(image).arg("stdout");
+    if !languages.is_empty() {
+        command.args(["-l", languages]);
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("无法启动 {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} 识别失败: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl OcrEngine for TesseractEngine {
+    fn recognize<'a>(
+        &'a self,
+        image: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        let program = self.program().to_string();
+        let image = image.to_path_buf();
+        let languages = self.config.languages.clone();
+        Box::pin(async move {
+            tauri::async_runtime::spawn_blocking(move || {
+                run_tesseract(&program, &image, &languages)
+            })
+            .await
+            .map_err(|e| e.to_string())?
+        })
+    }
+}
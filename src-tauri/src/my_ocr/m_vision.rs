@@ -0,0 +1,102 @@
+use crate::my_api::manager::APIManager;
+use crate::my_api::traits::ChatCompletionRequest;
+use crate::my_config::VisionOcrConfig;
+use crate::my_ocr::traits::OcrEngine;
+use crate::utils::chat_message::{ChatMessage, ContentPart, ImageDetail, ImageSource, Role};
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use tauri::async_runtime::RwLock;
+
+const OCR_PROMPT: &str =
+    "识别图片中的所有文字，按原有的段落和换行原样输出，不要翻译、解释或添加任何其他内容。";
+
+/// 通过支持视觉的模型识别文字
+pub struct VisionOcrEngine {
+    config: VisionOcrConfig,
+    api_manager: Arc<RwLock<APIManager>>,
+}
+
+impl VisionOcrEngine {
+    pub fn new(config: VisionOcrConfig, api_manager: Arc<RwLock<APIManager>>) -> Self {
+        Self {
+            config,
+            api_manager,
+        }
+    }
+}
+
+/// 截图在前、提示词在后，截图中的小字需要高精度识别
+fn ocr_message(image: &Path) -> ChatMessage {
+    ChatMessage::new(
+        Role::User,
+        vec![
+            ContentPart::Image {
+                source: ImageSource::Path(image.display().to_string()),
+                detail: Some(ImageDetail::High),
+            },
+            ContentPart::Text {
+                text: OCR_PROMPT.to_string(),
+            },
+        ],
+    )
+}
+
+impl OcrEngine for VisionOcrEngine {
+    fn recognize<'a>(
+        &'a self,
+        image: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        Box::pin(async move {
+            let message = ocr_message(image);
+            let request = ChatCompletionRequest {
+                model: self.config.model.clone(),
+                messages: vec![message.as_llm()?],
+                temperature: Some(0.0),
+                max_tokens: Some(4000),
+                top_p: None,
+                stream: Some(false),
+                tools: None,
+                response_format: None,
+            };
+
+            let manager = self.api_manager.read().await;
+            let response = manager
+                .chat_completion_with(self.config.provider.as_deref(), &request)
+                .await?;
+            response
+                .choices
+                .first()
+                .map(|choice| choice.message.content.text().into_owned())
+                .ok_or_else(|| "识别结果为空".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ocr_message_format() {
+        let path = std::env::temp_dir().join("okey_ai_ocr_test.png");
+        std::fs::write(&path, b"png").unwrap();
+        let message = ocr_message(&path);
+        let llm_message = message.as_llm().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            serde_json::to_value(&llm_message).unwrap(),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    {
+                        "type": "image_url",
+                        "image_url": { "url": "data:image/png;base64,cG5n", "detail": "high" }
+                    },
+                    { "type": "text", "text": OCR_PROMPT }
+                ]
+            })
+        );
+    }
+}
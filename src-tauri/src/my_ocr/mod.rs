@@ -0,0 +1,91 @@
+pub mod m_tesseract;
+pub mod m_vision;
+pub mod traits;
+
+use crate::my_api::manager::GlobalAPIManager;
+use crate::my_config::{self, OcrConfig, OcrEngineKind};
+use crate::my_events::event_names;
+use crate::my_windows;
+use crate::utils::{screen_capture, text_translation};
+use m_tesseract::TesseractEngine;
+use m_vision::VisionOcrEngine;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use traits::OcrEngine;
+
+fn create_engine(app: &AppHandle, config: &OcrConfig) -> Box<dyn OcrEngine + Send + Sync> {
+    match config.engine {
+        OcrEngineKind::Tesseract => Box::new(TesseractEngine::new(config.tesseract.clone())),
+        OcrEngineKind::Vision => Box::new(VisionOcrEngine::new(
+            config.vision.clone(),
+            app.state::<GlobalAPIManager>().0.clone(),
+        )),
+    }
+}
+
+/// 截图目录，会话中只允许引用其中的本地图片
+pub fn screenshot_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("screenshots");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建截图目录失败: {}", e))?;
+    Ok(dir)
+}
+
+/// 截图保存位置，文件名为截图时间
+fn screenshot_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = screenshot_dir(app)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    Ok(dir.join(format!("{}.png", timestamp)))
+}
+
+/// 识别图片中的文字
+pub async fn recognize(app: &AppHandle, image: &Path) -> Result<String, String> {
+    let config = my_config::get_global_config(app)
+        .map_err(|e| format!("获取配置失败: {}", e))?
+        .ocr;
+    let text = create_engine(app, &config).recognize(image).await?;
+    Ok(traits::clean_ocr_text(&text))
+}
+
+/// 框选屏幕区域，识别其中的文字并在翻译窗口中翻译，会话记录截图路径
+pub fn ocr_translate(app: &AppHandle) {
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        if let Err(e) = capture_and_translate(&app).await {
+            eprintln!("截图翻译失败: {}", e);
+            let app_clone = app.clone();
+            my_windows::window_translate_show(
+                &app,
+                Some(move || {
+                    let _ = app_clone.emit(event_names::AI_ERROR, e);
+                }),
+            );
+        }
+    });
+}
+
+async fn capture_and_translate(app: &AppHandle) -> Result<(), String> {
+    let path = screenshot_path(app)?;
+    let capture_path = path.clone();
+    let captured =
+        async_runtime::spawn_blocking(move || screen_capture::capture_region(&capture_path))
+            .await
+            .map_err(|e| e.to_string())??;
+    if !captured {
+        return Ok(());
+    }
+
+    let text = recognize(app, &path).await?;
+    if text.is_empty() {
+        return Err("截图中没有识别到文字".to_string());
+    }
+    text_translation::translate_image_text(app, text, path.display().to_string()).await;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+
+pub trait OcrEngine {
+    /// 识别图片中的文字，图片为 png 文件
+    fn recognize<'a>(
+        &'a self,
+        image: &'a Path,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3000..=0x303f | 0x4e00..=0x9fff | 0xff00..=0xffef)
+}
+
+/// 整理识别结果：去掉首尾空白和空行，删除中文字符之间多余的空格
+pub fn clean_ocr_text(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let chars: Vec<char> = line.trim().chars().collect();
+            chars
+                .iter()
+                .enumerate()
+                .filter(|&(i, c)| {
+                    !(c.is_whitespace()
+                        && i > 0
+                        && is_cjk(chars[i - 1])
+                        && chars.get(i + 1).is_some_and(|next| is_cjk(*next)))
+                })
+                .map(|(_, c)| *c)
+                .collect::<String>()
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_ocr_text() {
+        assert_eq!(
+            clean_ocr_text("  你 好 世 界 \n\n Hello world \n\u{c}"),
+            "你好世界\nHello world"
+        );
+        assert_eq!(clean_ocr_text("使用 API 接口"), "使用 API 接口");
+    }
+}
@@ -0,0 +1,516 @@
+//! 配置方案：在 store.json 中保存多套配置（如“工作”“个人”），可导出、导入和运行时切换
+//!
+//! 当前方案保存在 `global_config` 中，其他方案保存在 `profiles` 中，切换时互换。
+
+use crate::my_config::{self, GlobalConfig};
+use crate::my_events::event_names;
+use crate::states::setting_states;
+use crate::utils::config_migration;
+use crate::{
+    my_actions, my_api, my_clipboard_watch, my_dictionary, my_rdev, my_shortcut, my_tools, my_tts,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+const PROFILES_KEY: &str = "profiles";
+const ACTIVE_PROFILE_KEY: &str = "active_profile";
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 导出文件的格式标识
+const PROFILE_FILE_FORMAT: &str = "okey_ai.profile";
+
+/// 导出的配置方案文件
+#[derive(Debug, Serialize, Deserialize)]
+struct ProfileFile {
+    format: String,
+    name: String,
+    config: Value,
+}
+
+/// 导入时方案名称已存在的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflict {
+    /// 报错，不做修改
+    #[default]
+    Fail,
+    /// 以新名称导入，如 "work (2)"
+    Rename,
+    /// 替换已有方案，导入文件中没有的密钥沿用已有方案
+    Overwrite,
+    /// 快捷键、自定义动作和服务商合并到已有方案，同名项以导入的为准
+    Merge,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.chars().count() > 64 {
+        return Err("配置方案名称长度需在 1 到 64 之间".to_string());
+    }
+    if name.trim() != name {
+        return Err("配置方案名称首尾不能有空白".to_string());
+    }
+    Ok(())
+}
+
+/// 找到路径对应的字段，`*` 匹配对象的任意键；回调参数为字段的 JSON Pointer
+fn for_each_field(
+    value: &mut Value,
+    path: &[&str],
+    pointer: String,
+    f: &mut dyn FnMut(&str, &mut Value),
+) {
+    let Some((first, rest)) = path.split_first() else {
+        f(&pointer, value);
+        return;
+    };
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    for (key, child) in object.iter_mut() {
+        if *first == "*" || key == first {
+            let key = key.replace('~', "~0").replace('/', "~1");
+            for_each_field(child, rest, format!("{}/{}", pointer, key), f);
+        }
+    }
+}
+
+fn for_each_secret(value: &mut Value, f: &mut dyn FnMut(&str, &mut Value)) {
+    for path in my_config::SECRET_FIELDS {
+        let path: Vec<&str> = path.split('/').collect();
+        for_each_field(value, &path, String::new(), f);
+    }
+}
+
+/// 导出用的配置：清空 `SECRET_FIELDS` 中的字段。不认识的字段可能含有密钥，一并去掉
+fn strip_secrets(config: &GlobalConfig) -> Value {
+    let mut config = config.clone();
+    config.extra.clear();
+    let mut value = json!(config);
+    for_each_secret(&mut value, &mut |_, field| {
+        *field = Value::String(String::new());
+    });
+    value
+}
+
+/// 密钥所属服务的地址：api_key 取同一对象中的 base_url，dictionary_url 本身就是地址，返回 None
+fn secret_endpoint(config: &Value, pointer: &str) -> Option<Option<Value>> {
+    let parent = pointer.strip_suffix("/api_key")?;
+    Some(config.pointer(&format!("{}/base_url", parent)).cloned())
+}
+
+/// 导入的配置中为空的密钥字段沿用已有方案的值。
+/// 服务地址与已有方案不完全相同时保持为空，避免导入的文件把已有密钥引到其他地址
+fn keep_existing_secrets(imported: &mut GlobalConfig, existing: &GlobalConfig) {
+    let existing = json!(existing);
+    let mut value = json!(imported);
+    let imported_value = value.clone();
+    for_each_secret(&mut value, &mut |pointer, field| {
+        if field.as_str() != Some("") {
+            return;
+        }
+        if secret_endpoint(&imported_value, pointer) != secret_endpoint(&existing, pointer) {
+            return;
+        }
+        if let Some(existing) = existing.pointer(pointer) {
+            *field = existing.clone();
+        }
+    });
+    match serde_json::from_value(value) {
+        Ok(config) => *imported = config,
+        Err(e) => eprintln!("沿用已有密钥失败: {}", e),
+    }
+}
+
+/// 把导入的快捷键、自定义动作和服务商合并到已有方案
+fn merge_profile(mut existing: GlobalConfig, mut imported: GlobalConfig) -> GlobalConfig {
+    keep_existing_secrets(&mut imported, &existing);
+
+    for shortcut in imported.shortcuts {
+        let id = my_actions::resolve_action_id(&shortcut.name).to_string();
+        existing
+            .shortcuts
+            .retain(|s| my_actions::resolve_action_id(&s.name) != id);
+        existing.shortcuts.push(shortcut);
+    }
+    for action in imported.custom_actions {
+        match existing
+            .custom_actions
+            .iter_mut()
+            .find(|a| a.id == action.id)
+        {
+            Some(existing_action) => *existing_action = action,
+            None => existing.custom_actions.push(action),
+        }
+    }
+    existing.providers.append(&mut imported.providers);
+    existing
+}
+
+/// 名称冲突时生成 "name (2)"、"name (3)"……
+fn unique_profile_name(name: &str, existing: &[String]) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !existing.contains(candidate))
+        .unwrap()
+}
+
+fn parse_profile_config(value: Value) -> Result<GlobalConfig, String> {
+    serde_json::from_value(config_migration::migrate_config(value))
+        .map_err(|e| format!("配置方案解析失败: {}", e))
+}
+
+fn load_profiles(app: &AppHandle) -> Result<BTreeMap<String, Value>, String> {
+    let store = app.store("store.json").map_err(|e| e.to_string())?;
+    Ok(store
+        .get(PROFILES_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default())
+}
+
+fn save_profiles(
+    app: &AppHandle,
+    profiles: &BTreeMap<String, Value>,
+    active: &str,
+) -> Result<(), String> {
+    let store = app.store("store.json").map_err(|e| e.to_string())?;
+    store.set(PROFILES_KEY, json!(profiles));
+    store.set(ACTIVE_PROFILE_KEY, json!(active));
+    store.save().map_err(|e| format!("保存配置方案失败: {}", e))
+}
+
+pub fn active_profile(app: &AppHandle) -> String {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(ACTIVE_PROFILE_KEY))
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+pub fn list_profiles(app: &AppHandle) -> Result<ProfileList, String> {
+    let active = active_profile(app);
+    let mut profiles: Vec<String> = load_profiles(app)?.into_keys().collect();
+    profiles.push(active.clone());
+    profiles.sort();
+    profiles.dedup();
+    Ok(ProfileList { active, profiles })
+}
+
+fn profile_config(app: &AppHandle, name: &str) -> Result<GlobalConfig, String> {
+    if name == active_profile(app) {
+        return my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e));
+    }
+    let value = load_profiles(app)?
+        .remove(name)
+        .ok_or_else(|| format!("配置方案不存在: {}", name))?;
+    parse_profile_config(value)
+}
+
+/// 以当前配置为模板创建新方案
+pub fn create_profile(app: &AppHandle, name: &str) -> Result<(), String> {
+    validate_profile_name(name)?;
+    if list_profiles(app)?.profiles.iter().any(|p| p == name) {
+        return Err(format!("配置方案已存在: {}", name));
+    }
+    let config = my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    let mut profiles = load_profiles(app)?;
+    profiles.insert(name.to_string(), json!(config));
+    save_profiles(app, &profiles, &active_profile(app))
+}
+
+pub fn delete_profile(app: &AppHandle, name: &str) -> Result<(), String> {
+    if name == active_profile(app) {
+        return Err("不能删除当前使用的配置方案".to_string());
+    }
+    let mut profiles = load_profiles(app)?;
+    if profiles.remove(name).is_none() {
+        return Err(format!("配置方案不存在: {}", name));
+    }
+    save_profiles(app, &profiles, &active_profile(app))
+}
+
+/// 切换配置方案，重新加载服务商、自定义动作、快捷键和设置
+pub async fn switch_profile(app: &AppHandle, name: &str) -> Result<(), String> {
+    let active = active_profile(app);
+    if name == active {
+        return Ok(());
+    }
+    let previous_profiles = load_profiles(app)?;
+    let mut profiles = previous_profiles.clone();
+    let target = parse_profile_config(
+        profiles
+            .remove(name)
+            .ok_or_else(|| format!("配置方案不存在: {}", name))?,
+    )?;
+    let current = my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    profiles.insert(active.clone(), json!(current));
+
+    // 先保存方案列表，当前配置写入或应用失败时恢复原来的方案
+    save_profiles(app, &profiles, name)?;
+    let result = match my_config::set_global_config(app, &target) {
+        Ok(()) => apply_profile(app, &target).await,
+        Err(e) => Err(format!("保存配置失败: {}", e)),
+    };
+    if let Err(e) = result {
+        if let Err(e) = save_profiles(app, &previous_profiles, &active) {
+            eprintln!("恢复配置方案失败: {}", e);
+        }
+        restore_config(app, &current).await;
+        return Err(e);
+    }
+
+    let _ = app.emit(event_names::PROFILE_CHANGED, name);
+    Ok(())
+}
+
+/// 切换或导入失败后恢复原来的当前配置
+async fn restore_config(app: &AppHandle, config: &GlobalConfig) {
+    if let Err(e) = my_config::set_global_config(app, config) {
+        eprintln!("恢复配置失败: {}", e);
+    }
+    if let Err(e) = apply_profile(app, config).await {
+        eprintln!("恢复配置失败: {}", e);
+    }
+}
+
+/// 让当前配置在运行时生效
+async fn apply_profile(app: &AppHandle, config: &GlobalConfig) -> Result<(), String> {
+    if let Err(e) = my_api::reload_api_manager(app, config).await {
+        eprintln!("切换服务商失败: {}", e);
+    }
+    my_actions::load_custom_actions(app).map_err(|e| format!("加载自定义动作失败: {}", e))?;
+    my_shortcut::reload_shortcuts(app).map_err(|e| format!("注册快捷键失败: {}", e))?;
+    if let Err(e) = my_rdev::apply_gesture_config(app, config.gesture.clone()) {
+        eprintln!("按键手势配置无效: {}", e);
+    }
+    my_clipboard_watch::set_clipboard_watch_enabled(app, config.clipboard_watch.enabled)?;
+    let settings = config.settings.clone();
+    setting_states::update_settings(app, |current| *current = settings)?;
+    my_tts::apply_tts_config(app, &config.tts);
+    my_tools::apply_tools_config(app, &config.tools);
+    my_dictionary::apply_dictionary_config(app, &config.dictionary);
+    Ok(())
+}
+
+/// 导出配置方案到文件，密钥等敏感字段会被清除
+pub fn export_profile(app: &AppHandle, name: &str, path: &str) -> Result<(), String> {
+    let config = profile_config(app, name)?;
+    let file = ProfileFile {
+        format: PROFILE_FILE_FORMAT.to_string(),
+        name: name.to_string(),
+        config: strip_secrets(&config),
+    };
+    let content = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| format!("写入文件失败: {}", e))
+}
+
+/// 从文件导入配置方案，返回最终使用的方案名称
+pub async fn import_profile(
+    app: &AppHandle,
+    path: &str,
+    name: Option<String>,
+    conflict: ImportConflict,
+) -> Result<String, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let file: ProfileFile =
+        serde_json::from_str(&content).map_err(|e| format!("配置方案文件格式错误: {}", e))?;
+    if file.format != PROFILE_FILE_FORMAT {
+        return Err(format!("不支持的文件格式: {}", file.format));
+    }
+    let mut imported = parse_profile_config(file.config)?;
+    let mut name = name.unwrap_or(file.name);
+    validate_profile_name(&name)?;
+
+    let existing_names = list_profiles(app)?.profiles;
+    let config = if existing_names.contains(&name) {
+        match conflict {
+            ImportConflict::Fail => return Err(format!("配置方案已存在: {}", name)),
+            ImportConflict::Rename => {
+                name = unique_profile_name(&name, &existing_names);
+                imported
+            }
+            ImportConflict::Overwrite => {
+                keep_existing_secrets(&mut imported, &profile_config(app, &name)?);
+                imported
+            }
+            ImportConflict::Merge => merge_profile(profile_config(app, &name)?, imported),
+        }
+    } else {
+        imported
+    };
+
+    let active = active_profile(app);
+    if name == active {
+        let previous =
+            my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+        my_config::set_global_config(app, &config).map_err(|e| format!("保存配置失败: {}", e))?;
+        if let Err(e) = apply_profile(app, &config).await {
+            restore_config(app, &previous).await;
+            return Err(e);
+        }
+    } else {
+        let mut profiles = load_profiles(app)?;
+        profiles.insert(name.clone(), json!(config));
+        save_profiles(app, &profiles, &active)?;
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::my_api::traits::APIConfig;
+    use crate::my_config::{ActionOutput, CustomAction, Shortcut};
+
+    fn provider(api_key: &str, model: &str) -> APIConfig {
+        APIConfig {
+            api_key: api_key.to_string(),
+            base_url: "https://example.com/v1".to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    fn custom_action(id: &str, prompt_template: &str) -> CustomAction {
+        CustomAction {
+            id: id.to_string(),
+            name: id.to_string(),
+            prompt_template: prompt_template.to_string(),
+            provider: None,
+            model: None,
+            temperature: None,
+            output: ActionOutput::Bubble,
+        }
+    }
+
+    fn shortcut(name: &str, hot_key: &str) -> Shortcut {
+        Shortcut {
+            name: name.to_string(),
+            hot_key: hot_key.to_string(),
+        }
+    }
+
+    /// 所有非空的 api_key，包括不认识的字段中的
+    fn api_keys(value: &Value) -> Vec<String> {
+        match value {
+            Value::Object(object) => object
+                .iter()
+                .flat_map(|(key, value)| match value.as_str() {
+                    Some(text) if key == "api_key" && !text.is_empty() => vec![text.to_string()],
+                    _ => api_keys(value),
+                })
+                .collect(),
+            Value::Array(items) => items.iter().flat_map(api_keys).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_strip_secrets() {
+        let mut config = GlobalConfig::default();
+        config
+            .providers
+            .insert("work/cn".to_string(), provider("secret", "gpt-4o"));
+        config.tts.openai.api_key = "secret".to_string();
+        config.stt.openai.api_key = "secret".to_string();
+        config.tools.dictionary_url = "https://dict.example.com/{word}?key=secret".to_string();
+        config.extra.insert(
+            "future_provider".to_string(),
+            json!({ "api_key": "secret", "models": [{ "api_key": "secret" }] }),
+        );
+        assert_eq!(api_keys(&json!(config)).len(), 5);
+
+        let exported = strip_secrets(&config);
+        assert!(api_keys(&exported).is_empty());
+        assert!(!exported.to_string().contains("secret"));
+        assert_eq!(exported["providers"]["work/cn"]["model"], "gpt-4o");
+
+        // 导入时沿用已有方案的密钥
+        let mut imported: GlobalConfig = serde_json::from_value(exported).unwrap();
+        keep_existing_secrets(&mut imported, &config);
+        assert_eq!(imported.providers["work/cn"].api_key, "secret");
+        assert_eq!(imported.tts.openai.api_key, "secret");
+        assert_eq!(imported.tools.dictionary_url, config.tools.dictionary_url);
+    }
+
+    #[test]
+    fn test_changed_base_url_does_not_keep_secret() {
+        let mut existing = GlobalConfig::default();
+        existing
+            .providers
+            .insert("qwen".to_string(), provider("secret", "qwen-plus"));
+        existing.stt.openai.api_key = "secret".to_string();
+
+        let mut imported = existing.clone();
+        imported.providers.get_mut("qwen").unwrap().api_key = String::new();
+        imported.providers.get_mut("qwen").unwrap().base_url =
+            "https://attacker.example.com/v1".to_string();
+        imported.stt.openai.api_key = String::new();
+        imported.stt.openai.base_url = "https://attacker.example.com/v1".to_string();
+
+        keep_existing_secrets(&mut imported, &existing);
+        assert!(imported.providers["qwen"].api_key.is_empty());
+        assert!(imported.stt.openai.api_key.is_empty());
+
+        let merged = merge_profile(existing, imported);
+        assert!(merged.providers["qwen"].api_key.is_empty());
+    }
+
+    #[test]
+    fn test_merge_profile() {
+        let existing = GlobalConfig {
+            shortcuts: vec![
+                shortcut("okey_ai", "Ctrl+G"),
+                shortcut("write_back", "Ctrl+R"),
+            ],
+            custom_actions: vec![custom_action("emoji", "old {text}")],
+            providers: BTreeMap::from([("work".to_string(), provider("secret", "gpt-4"))]),
+            ..GlobalConfig::default()
+        };
+        let imported = GlobalConfig {
+            // 旧名称 okey_ai 与 translate_window 是同一个动作
+            shortcuts: vec![shortcut("translate_window", "Ctrl+T")],
+            custom_actions: vec![
+                custom_action("emoji", "new {text}"),
+                custom_action("glossary", "{text}"),
+            ],
+            providers: BTreeMap::from([("work".to_string(), provider("", "gpt-4o"))]),
+            ..GlobalConfig::default()
+        };
+
+        let merged = merge_profile(existing, imported);
+        let hot_keys: Vec<_> = merged
+            .shortcuts
+            .iter()
+            .map(|s| (s.name.as_str(), s.hot_key.as_str()))
+            .collect();
+        assert_eq!(
+            hot_keys,
+            vec![("write_back", "Ctrl+R"), ("translate_window", "Ctrl+T")]
+        );
+        assert_eq!(merged.custom_actions.len(), 2);
+        assert_eq!(merged.custom_actions[0].prompt_template, "new {text}");
+        assert_eq!(merged.providers["work"].api_key, "secret");
+        assert_eq!(merged.providers["work"].model, "gpt-4o");
+    }
+
+    #[test]
+    fn test_unique_profile_name() {
+        let existing = vec!["work".to_string(), "work (2)".to_string()];
+        assert_eq!(unique_profile_name("work", &existing), "work (3)");
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("work").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name(" work").is_err());
+    }
+}
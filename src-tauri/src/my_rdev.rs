@@ -1,194 +1,143 @@
+use crate::my_actions::{self, ActionRegistry};
+use crate::my_config::{self, GestureConfig};
 use crate::my_events::event_names;
-use crate::my_windows;
-use crate::utils::text_translation;
-use rdev::{listen, Button, Event, EventType, Key};
-use std::sync::{Arc, Mutex};
+use crate::utils::gesture::{Gesture, GestureRecognizer, GestureSettings, InputEvent, SystemClock};
+use crate::{my_shortcut, my_stt, my_windows};
+use rdev::{listen, Button, Event, EventType};
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
 use std::time::Duration;
-use std::time::Instant;
 use tauri::AppHandle;
 use tauri::Emitter;
 use tauri::Manager;
 
-struct GlobalState {
-    ime_handler: InputMethodEditorHandler,
-    translate_bubble_handler: TranslateBubbleHandler,
-    click_outside_handler: ClickOutsideHandler,
+pub struct GestureSettingsState {
+    settings: Arc<RwLock<GestureSettings>>,
+    tap_action: Arc<RwLock<String>>,
 }
 
-struct InputMethodEditorHandler {
-    was_pressed: bool,
-    press_start_time: Option<Instant>,
-}
-
-impl InputMethodEditorHandler {
-    fn new() -> Self {
-        Self {
-            was_pressed: false,
-            press_start_time: None,
-        }
+/// 校验、保存手势配置，并立即替换监听线程使用的设置
+pub fn apply_gesture_config(app: &AppHandle, config: GestureConfig) -> Result<(), String> {
+    let settings = GestureSettings::try_from(&config)?;
+    if !app.state::<ActionRegistry>().contains(&config.tap_action) {
+        return Err(format!("未知动作: {}", config.tap_action));
     }
+    let tap_action = config.tap_action.clone();
 
-    fn handle(&mut self, is_pressed: bool, app: &AppHandle) {
-        // 按键状态变化处理
-        if is_pressed && !self.was_pressed {
-            // 记录按键按下的开始时间
-            self.press_start_time = Some(Instant::now());
-        } else if !is_pressed && self.was_pressed {
-            // 按键抬起，清除计时器
-            self.press_start_time = None;
-            my_windows::window_input_method_editor_hide(app);
-        } else if is_pressed && self.was_pressed {
-            // 按键持续按下，检查是否超过800毫秒
-            if let Some(start_time) = self.press_start_time {
-                if start_time.elapsed() >= Duration::from_millis(800) {
-                    my_windows::window_input_method_editor_show(app);
-                    // 执行后清除计时器，避免重复触发
-                    self.press_start_time = None;
-                }
-            }
-        }
-
-        self.was_pressed = is_pressed;
+    let mut global_config =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    if let Some(other) = my_shortcut::find_gesture_conflict(&global_config.shortcuts, &config.key) {
+        return Err(format!("触发键 {} 已被快捷键 {} 使用", config.key, other));
     }
-}
+    global_config.gesture = config;
+    my_config::set_global_config(app, &global_config)
+        .map_err(|e| format!("保存配置失败: {}", e))?;
 
-struct TranslateBubbleHandler {
-    was_pressed: bool,
-    last_release_time: Option<Instant>,
-    double_click_timeout: u128,
+    if let Some(state) = app.try_state::<GestureSettingsState>() {
+        *state.settings.write().unwrap() = settings;
+        *state.tap_action.write().unwrap() = tap_action;
+    }
+    Ok(())
 }
 
-impl TranslateBubbleHandler {
-    fn new() -> Self {
-        Self {
-            was_pressed: false,
-            last_release_time: None,
-            double_click_timeout: 1000,
+/// 将识别出的手势分发到绑定的动作或窗口操作
+fn dispatch(
+    gesture: Gesture,
+    settings: &GestureSettings,
+    tap_action: &RwLock<String>,
+    app: &AppHandle,
+) {
+    match gesture {
+        // 未启用语音输入时长按只显示输入法窗口
+        Gesture::LongPressStart if my_stt::is_enabled(app) => my_stt::start_recording(app),
+        Gesture::LongPressStart => my_windows::window_input_method_editor_show(app),
+        Gesture::LongPressEnd => my_stt::stop_recording(app),
+        Gesture::Tap { count } if count == settings.tap_count => {
+            let action = tap_action.read().unwrap().clone();
+            if let Err(e) = my_actions::run_action(app, &action) {
+                eprintln!("{}", e);
+            }
         }
+        Gesture::Click { x, y } => hide_bubble_on_click_outside(app, x as i32, y as i32),
+        Gesture::Tap { .. } | Gesture::Chord(_) => {}
     }
+}
 
-    fn handle(&mut self, is_pressed: bool, app: &AppHandle) {
-        let now = Instant::now();
-
-        if !is_pressed && self.was_pressed {
-            if let Some(last_release) = self.last_release_time {
-                let elapsed = now.duration_since(last_release);
-
-                if elapsed.as_millis() < self.double_click_timeout {
-                    self.trigger_action(app);
+fn hide_bubble_on_click_outside(app: &AppHandle, mouse_x: i32, mouse_y: i32) {
+    if let Some(window) = app.get_webview_window("translate_bubble") {
+        if window.is_visible().unwrap_or(false) {
+            if let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) {
+                let win_x = pos.x;
+                let win_y = pos.y;
+                let win_w = size.width as i32;
+                let win_h = size.height as i32;
+
+                let inside = mouse_x >= win_x
+                    && mouse_x <= win_x + win_w
+                    && mouse_y >= win_y
+                    && mouse_y <= win_y + win_h;
+
+                if !inside {
+                    let _ = window.hide();
+                    let _ = app.emit(event_names::BUBBLE_CLEAN, {});
                 }
             }
-            self.last_release_time = Some(now);
-        }
-
-        self.was_pressed = is_pressed;
-    }
-
-    fn trigger_action(&self, app: &AppHandle) {
-        let app_clone = app.clone();
-        if let Some(_) = app_clone.get_webview_window("translate") {
-            text_translation::translate_selected_text_bubble(&app_clone);
-        } else {
-            my_windows::window_translate_bubble_show(
-                &app,
-                Some(move || {
-                    text_translation::translate_selected_text_bubble(&app_clone);
-                }),
-            );
         }
     }
 }
 
-struct ClickOutsideHandler {
-    mouse_x: i32,
-    mouse_y: i32,
-}
-
-impl ClickOutsideHandler {
-    fn new() -> Self {
-        Self {
-            mouse_x: 0,
-            mouse_y: 0,
-        }
-    }
-
-    fn update_mouse_position(&mut self, x: f64, y: f64) {
-        self.mouse_x = x as i32;
-        self.mouse_y = y as i32;
-    }
-
-    fn handle_click(&mut self, app: &AppHandle) {
-        if let Some(window) = app.get_webview_window("translate_bubble") {
-            if window.is_visible().unwrap_or(false) {
-                if let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) {
-                    let win_x = pos.x;
-                    let win_y = pos.y;
-                    let win_w = size.width as i32;
-                    let win_h = size.height as i32;
-
-                    let inside = self.mouse_x >= win_x
-                        && self.mouse_x <= win_x + win_w
-                        && self.mouse_y >= win_y
-                        && self.mouse_y <= win_y + win_h;
-
-                    if !inside {
-                        let _ = window.hide();
-                        let _ = app.emit(event_names::BUBBLE_CLEAN, {});
-                    }
-                }
-            }
-        }
+fn to_input_event(event: &Event) -> Option<InputEvent> {
+    match event.event_type {
+        EventType::KeyPress(key) => Some(InputEvent::KeyDown(key)),
+        EventType::KeyRelease(key) => Some(InputEvent::KeyUp(key)),
+        EventType::MouseMove { x, y } => Some(InputEvent::PointerMove { x, y }),
+        EventType::ButtonPress(Button::Left) => Some(InputEvent::PointerDown),
+        _ => None,
     }
 }
 
 pub fn init_global_input_listener(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let app_clone = app.clone();
 
-    let global_state = Arc::new(Mutex::new(GlobalState {
-        ime_handler: InputMethodEditorHandler::new(),
-        translate_bubble_handler: TranslateBubbleHandler::new(),
-        click_outside_handler: ClickOutsideHandler::new(),
-    }));
+    let gesture_config = my_config::get_global_config(app)?.gesture;
+    let initial_settings = GestureSettings::try_from(&gesture_config).unwrap_or_else(|e| {
+        eprintln!("手势配置无效，使用默认配置: {}", e);
+        GestureSettings::try_from(&GestureConfig::default()).unwrap()
+    });
+    let settings = Arc::new(RwLock::new(initial_settings));
+    let tap_action = Arc::new(RwLock::new(gesture_config.tap_action));
+    app.manage(GestureSettingsState {
+        settings: settings.clone(),
+        tap_action: tap_action.clone(),
+    });
+
+    let recognizer = Arc::new(Mutex::new(GestureRecognizer::new(
+        initial_settings,
+        SystemClock,
+    )));
 
-    let state_clone = global_state.clone();
+    let recognizer_clone = recognizer.clone();
+    let settings_clone = settings.clone();
+    let tap_action_clone = tap_action.clone();
 
     thread::spawn(move || {
         let app = app_clone;
 
         let callback = move |event: Event| {
-            let mut state = state_clone.lock().unwrap();
-
-            match event.event_type {
-                EventType::KeyPress(key) => {
-                    #[cfg(target_os = "macos")]
-                    let is_target_key = matches!(key, Key::MetaRight);
-                    #[cfg(not(target_os = "macos"))]
-                    let is_target_key = matches!(key, Key::ControlRight);
-
-                    if is_target_key {
-                        state.ime_handler.handle(true, &app);
-                        state.translate_bubble_handler.handle(true, &app);
-                    }
+            let Some(input) = to_input_event(&event) else {
+                return;
+            };
+            // 每次事件读取最新设置，配置修改后立即生效
+            let settings = *settings_clone.read().unwrap();
+            let gestures = {
+                let mut recognizer = recognizer_clone.lock().unwrap();
+                if recognizer.settings() != settings {
+                    recognizer.set_settings(settings);
                 }
-                EventType::KeyRelease(key) => {
-                    #[cfg(target_os = "macos")]
-                    let is_target_key = matches!(key, Key::MetaRight);
-                    #[cfg(not(target_os = "macos"))]
-                    let is_target_key = matches!(key, Key::ControlRight);
-
-                    if is_target_key {
-                        state.ime_handler.handle(false, &app);
-                        state.translate_bubble_handler.handle(false, &app);
-                    }
-                }
-                EventType::MouseMove { x, y } => {
-                    state.click_outside_handler.update_mouse_position(x, y);
-                }
-                EventType::ButtonPress(Button::Left) => {
-                    state.click_outside_handler.handle_click(&app);
-                }
-                _ => {}
+                recognizer.handle(input)
+            };
+            for gesture in gestures {
+                dispatch(gesture, &settings, &tap_action_clone, &app);
             }
         };
 
@@ -197,14 +146,13 @@ pub fn init_global_input_listener(app: &AppHandle) -> Result<(), Box<dyn std::er
         }
     });
 
-    let state_clone2 = global_state.clone();
+    // 按住期间没有新的按键事件，需要定时检测长按
     let app_clone2 = app.clone();
     thread::spawn(move || loop {
-        {
-            let mut state = state_clone2.lock().unwrap();
-            if state.ime_handler.was_pressed {
-                state.ime_handler.handle(true, &app_clone2);
-            }
+        let gesture = recognizer.lock().unwrap().tick();
+        if let Some(gesture) = gesture {
+            let settings = *settings.read().unwrap();
+            dispatch(gesture, &settings, &tap_action, &app_clone2);
         }
         thread::sleep(Duration::from_millis(16));
     });
@@ -1,19 +1,166 @@
+use crate::my_actions::{self, action_ids, ActionRegistry};
 use crate::my_config;
-use crate::utils::text_translation;
-use tauri::AppHandle;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use crate::utils::gesture;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut as GlobalShortcut, ShortcutState};
 
 #[tauri::command]
 pub fn register_hotkey_okey_ai(app: AppHandle, shortcut: String) -> Result<(), String> {
+    replace_hotkey(&app, action_ids::TRANSLATE_WINDOW, shortcut)
+}
+
+#[tauri::command]
+pub fn register_hotkey_write_back(app: AppHandle, shortcut: String) -> Result<(), String> {
+    replace_hotkey(&app, action_ids::WRITE_BACK, shortcut)
+}
+
+/// 为任意动作绑定快捷键
+#[tauri::command]
+pub fn register_hotkey(app: AppHandle, action: String, shortcut: String) -> Result<(), String> {
+    if !app.state::<ActionRegistry>().contains(&action) {
+        return Err(format!("未知动作: {}", action));
+    }
+    replace_hotkey(&app, my_actions::resolve_action_id(&action), shortcut)
+}
+
+/// 解除动作的快捷键绑定
+#[tauri::command]
+pub fn unregister_hotkey(app: AppHandle, action: String) -> Result<(), String> {
+    let action = my_actions::resolve_action_id(&action);
+    let mut global_config =
+        my_config::get_global_config(&app).map_err(|e| format!("获取配置失败: {}", e))?;
+
+    let Some(index) = global_config
+        .shortcuts
+        .iter()
+        .position(|s| my_actions::resolve_action_id(&s.name) == action)
+    else {
+        return Ok(());
+    };
+    let removed = global_config.shortcuts.remove(index);
+    my_config::set_global_config(&app, &global_config)
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    if let Err(e) = app.global_shortcut().unregister(removed.hot_key.as_str()) {
+        println!("注销快捷键失败 {}: {}", removed.hot_key, e);
+    }
+    Ok(())
+}
+
+/// 快捷键事件分发：需要模拟按键的动作等按键松开后再触发，其余在按下时触发
+fn handle_shortcut(app: &AppHandle, name: &str, state: ShortcutState) {
+    let Some(action) = app.state::<ActionRegistry>().get(name) else {
+        if state == ShortcutState::Pressed {
+            println!("快捷键未绑定动作: {}", name);
+        }
+        return;
+    };
+    let trigger = if action.on_release {
+        ShortcutState::Released
+    } else {
+        ShortcutState::Pressed
+    };
+    if state == trigger {
+        action.run(app);
+    }
+}
+
+/// 与手势触发键冲突时返回的绑定名称
+const GESTURE_BINDING: &str = "gesture";
+
+/// 快捷键是否就是手势触发键本身：不带修饰键单独按下时也会被计为触发键的单击，
+/// 带修饰键时手势识别视为组合键，不会冲突
+fn is_gesture_key(gesture_key: &str, hot_key: &GlobalShortcut) -> bool {
+    gesture::canonical_key_name(gesture_key)
+        .and_then(|name| name.parse::<GlobalShortcut>().ok())
+        .is_some_and(|key| key == *hot_key)
+}
+
+/// 查找已占用同一组合键的其他绑定（包括手势触发键），返回其名称
+pub fn find_conflict<'a>(
+    shortcuts: &'a [my_config::Shortcut],
+    gesture_key: &str,
+    action: &str,
+    hot_key: &str,
+) -> Result<Option<&'a str>, String> {
+    let target: GlobalShortcut = hot_key
+        .parse()
+        .map_err(|e| format!("无效的快捷键 {}: {}", hot_key, e))?;
+
+    if is_gesture_key(gesture_key, &target) {
+        return Ok(Some(GESTURE_BINDING));
+    }
+    Ok(shortcuts
+        .iter()
+        .filter(|s| my_actions::resolve_action_id(&s.name) != action)
+        .find(|s| {
+            s.hot_key
+                .parse::<GlobalShortcut>()
+                .is_ok_and(|key| key == target)
+        })
+        .map(|s| s.name.as_str()))
+}
+
+/// 查找与手势触发键冲突的快捷键，修改触发键时使用
+pub fn find_gesture_conflict<'a>(
+    shortcuts: &'a [my_config::Shortcut],
+    gesture_key: &str,
+) -> Option<&'a str> {
+    shortcuts
+        .iter()
+        .find(|s| {
+            s.hot_key
+                .parse::<GlobalShortcut>()
+                .is_ok_and(|key| is_gesture_key(gesture_key, &key))
+        })
+        .map(|s| s.name.as_str())
+}
+
+/// 动作当前是否已绑定到同一组合键
+fn is_current_hotkey(shortcuts: &[my_config::Shortcut], action: &str, hot_key: &str) -> bool {
+    let Ok(target) = hot_key.parse::<GlobalShortcut>() else {
+        return false;
+    };
+    shortcuts.iter().any(|s| {
+        my_actions::resolve_action_id(&s.name) == action
+            && s.hot_key
+                .parse::<GlobalShortcut>()
+                .is_ok_and(|key| key == target)
+    })
+}
+
+/// 注册新的快捷键，保存到配置后注销该动作的旧快捷键
+fn replace_hotkey(app: &AppHandle, action: &str, shortcut: String) -> Result<(), String> {
+    // Get the current configuration
+    let mut global_config: my_config::GlobalConfig =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+
+    // 重新提交当前的组合键（如只修改了动作的其他设置）时无需重新注册
+    if is_current_hotkey(&global_config.shortcuts, action, &shortcut)
+        && app.global_shortcut().is_registered(shortcut.as_str())
+    {
+        return Ok(());
+    }
+
+    if let Some(other) = find_conflict(
+        &global_config.shortcuts,
+        &global_config.gesture.key,
+        action,
+        &shortcut,
+    )? {
+        return Err(format!("快捷键 {} 已被 {} 使用", shortcut, other));
+    }
+
     // First, try to register the new shortcut
     let shortcut_for_closure = shortcut.clone();
+    let action_for_closure = action.to_string();
     match app
         .global_shortcut()
         .on_shortcut(shortcut.as_str(), move |app, _shortcut, event| {
             if event.state == ShortcutState::Pressed {
                 println!("动态快捷键触发: {}", shortcut_for_closure);
-                crate::utils::text_translation::translate_selected_text(&app);
             }
+            handle_shortcut(app, &action_for_closure, event.state);
         }) {
         Ok(_) => println!("成功注册动态快捷键: {}", shortcut),
         Err(e) => {
@@ -23,30 +170,26 @@ pub fn register_hotkey_okey_ai(app: AppHandle, shortcut: String) -> Result<(), S
         }
     }
 
-    // Get the current configuration
-    let mut global_config: my_config::GlobalConfig =
-        my_config::get_global_config(&app).map_err(|e| format!("获取配置失败: {}", e))?;
-
-    // Find and store the old shortcut for "okey_ai" if it exists
+    // Find and store the old shortcut for this action if it exists
     let mut old_shortcut: Option<String> = None;
     for shortcut_config in &mut global_config.shortcuts {
-        if shortcut_config.name == "okey_ai" {
+        if my_actions::resolve_action_id(&shortcut_config.name) == action {
             old_shortcut = Some(shortcut_config.hot_key.clone());
             shortcut_config.hot_key = shortcut.clone(); // Update the existing shortcut
             break;
         }
     }
 
-    // If the "okey_ai" shortcut wasn't found in config, add it
+    // If the shortcut wasn't found in config, add it
     if old_shortcut.is_none() {
         global_config.shortcuts.push(crate::my_config::Shortcut {
-            name: "okey_ai".to_string(),
+            name: action.to_string(),
             hot_key: shortcut.clone(),
         });
     }
 
     // Save the updated configuration only if shortcut registration was successful
-    my_config::set_global_config(&app, &global_config)
+    my_config::set_global_config(app, &global_config)
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
     // If there was an old shortcut, unregister it specifically
@@ -60,12 +203,40 @@ pub fn register_hotkey_okey_ai(app: AppHandle, shortcut: String) -> Result<(), S
     Ok(())
 }
 
+/// 注销所有快捷键并按当前配置重新注册，切换配置方案时使用
+pub fn reload_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    app.global_shortcut().unregister_all()?;
+    init_shortcuts(app)
+}
+
 pub fn init_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let global_config: crate::my_config::GlobalConfig = my_config::get_global_config(app)?;
 
-    for shortcut in global_config.shortcuts {
+    for (index, shortcut) in global_config.shortcuts.iter().enumerate() {
+        // 配置中重复的组合键只注册第一个
+        let earlier = &global_config.shortcuts[..index];
+        match find_conflict(
+            earlier,
+            &global_config.gesture.key,
+            &shortcut.name,
+            &shortcut.hot_key,
+        ) {
+            Ok(Some(other)) => {
+                eprintln!(
+                    "快捷键冲突，跳过 {} ({})：已被 {} 使用",
+                    shortcut.name, shortcut.hot_key, other
+                );
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        }
+
         let hot_key = shortcut.hot_key.clone();
-        let name = shortcut.name.clone();
+        let name = my_actions::resolve_action_id(&shortcut.name).to_string();
 
         let hot_key_for_message = hot_key.clone();
         let name_for_message = name.clone();
@@ -74,13 +245,8 @@ pub fn init_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>>
             .on_shortcut(hot_key.as_str(), move |app, shortcut, event| {
                 if event.state == ShortcutState::Pressed {
                     println!("快捷键触发: {} ({})", name, shortcut);
-                    if name == "okey_ai" {
-                        text_translation::translate_selected_text(&app);
-                    }
-                    if name == "test" {
-                        println!("测试快捷键被按下");
-                    }
                 }
+                handle_shortcut(app, &name, event.state);
             }) {
             Ok(_) => println!(
                 "成功注册快捷键: {} ({})",
@@ -94,3 +260,134 @@ pub fn init_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::my_config::Shortcut;
+
+    fn shortcuts() -> Vec<Shortcut> {
+        vec![
+            Shortcut {
+                name: "okey_ai".to_string(),
+                hot_key: "Ctrl+G".to_string(),
+            },
+            Shortcut {
+                name: "write_back".to_string(),
+                hot_key: "Ctrl+Shift+R".to_string(),
+            },
+        ]
+    }
+
+    const GESTURE_KEY: &str = "F8";
+
+    #[test]
+    fn test_conflict_with_other_action() {
+        let shortcuts = shortcuts();
+        assert_eq!(
+            find_conflict(
+                &shortcuts,
+                GESTURE_KEY,
+                action_ids::TRANSLATE_BUBBLE,
+                "Ctrl+G"
+            ),
+            Ok(Some("okey_ai"))
+        );
+        // 修饰键顺序和大小写不同也视为同一组合键
+        assert_eq!(
+            find_conflict(
+                &shortcuts,
+                GESTURE_KEY,
+                action_ids::TRANSLATE_BUBBLE,
+                "shift+control+r"
+            ),
+            Ok(Some("write_back"))
+        );
+    }
+
+    #[test]
+    fn test_conflict_with_gesture_key() {
+        let shortcuts = shortcuts();
+        assert_eq!(
+            find_conflict(&shortcuts, GESTURE_KEY, action_ids::TRANSLATE_BUBBLE, "F8"),
+            Ok(Some(GESTURE_BINDING))
+        );
+        // 带修饰键时手势识别为组合键
+        assert_eq!(
+            find_conflict(
+                &shortcuts,
+                GESTURE_KEY,
+                action_ids::TRANSLATE_BUBBLE,
+                "Shift+F8"
+            ),
+            Ok(None)
+        );
+        assert_eq!(find_gesture_conflict(&shortcuts, "f8"), None);
+        assert_eq!(find_gesture_conflict(&shortcuts, "Banana"), None);
+
+        let mut shortcuts = shortcuts;
+        shortcuts.push(Shortcut {
+            name: "translate_bubble".to_string(),
+            hot_key: "F8".to_string(),
+        });
+        assert_eq!(
+            find_gesture_conflict(&shortcuts, "f8"),
+            Some("translate_bubble")
+        );
+    }
+
+    #[test]
+    fn test_rebinding_same_action_is_not_conflict() {
+        let shortcuts = shortcuts();
+        // 旧名称 okey_ai 对应 translate_window
+        assert_eq!(
+            find_conflict(
+                &shortcuts,
+                GESTURE_KEY,
+                action_ids::TRANSLATE_WINDOW,
+                "Ctrl+G"
+            ),
+            Ok(None)
+        );
+        assert_eq!(
+            find_conflict(
+                &shortcuts,
+                GESTURE_KEY,
+                action_ids::TRANSLATE_BUBBLE,
+                "Ctrl+Alt+G"
+            ),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_current_hotkey() {
+        let shortcuts = shortcuts();
+        assert!(is_current_hotkey(
+            &shortcuts,
+            action_ids::TRANSLATE_WINDOW,
+            "control+g"
+        ));
+        assert!(!is_current_hotkey(
+            &shortcuts,
+            action_ids::TRANSLATE_WINDOW,
+            "Ctrl+Shift+R"
+        ));
+        assert!(!is_current_hotkey(
+            &shortcuts,
+            action_ids::TRANSLATE_BUBBLE,
+            "Ctrl+G"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_shortcut() {
+        assert!(find_conflict(
+            &shortcuts(),
+            GESTURE_KEY,
+            action_ids::TRANSLATE_BUBBLE,
+            "Ctrl+"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::my_config::{self, SttConfig};
+use crate::my_stt;
+use tauri::ipc::{InvokeBody, Request};
+use tauri::AppHandle;
+
+/// 识别页面录制的音频，请求体为 16kHz 单声道 wav 的原始字节。
+/// 请求头 `translate: true` 时把识别结果送入翻译流程
+#[tauri::command]
+pub async fn transcribe_audio(app: AppHandle, request: Request<'_>) -> Result<String, String> {
+    let InvokeBody::Raw(audio) = request.body() else {
+        return Err("请求体应为音频数据".to_string());
+    };
+    let translate = request
+        .headers()
+        .get("translate")
+        .is_some_and(|value| value == "true");
+
+    if translate {
+        my_stt::transcribe_and_translate(&app, audio).await
+    } else {
+        my_stt::transcribe(&app, audio).await
+    }
+}
+
+/// 录音窗口页面就绪，返回是否需要立即开始录音
+#[tauri::command]
+pub fn recording_window_ready() -> bool {
+    my_stt::set_window_ready()
+}
+
+#[tauri::command]
+pub fn get_stt_config(app: AppHandle) -> Result<SttConfig, String> {
+    my_config::get_global_config(&app)
+        .map(|config| config.stt)
+        .map_err(|e| format!("获取配置失败: {}", e))
+}
+
+#[tauri::command]
+pub fn set_stt_config(app: AppHandle, config: SttConfig) -> Result<(), String> {
+    let mut global_config =
+        my_config::get_global_config(&app).map_err(|e| format!("获取配置失败: {}", e))?;
+    global_config.stt = config;
+    my_config::set_global_config(&app, &global_config).map_err(|e| format!("保存配置失败: {}", e))
+}
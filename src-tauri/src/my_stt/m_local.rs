@@ -0,0 +1,147 @@
+use crate::my_config::LocalSttConfig;
+use crate::my_stt::traits::{SttEngine, TranscriptionRequest};
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::process::Command;
+
+/// 调用本地的 whisper.cpp 识别，音频先写入临时文件
+#[derive(Debug)]
+pub struct LocalSttEngine {
+    config: LocalSttConfig,
+}
+
+impl LocalSttEngine {
+    pub fn new(config: LocalSttConfig) -> Self {
+        Self { config }
+    }
+
+    fn program(&self) -> &str {
+        match &self.config.path {
+            Some(path) if !path.is_empty() => path,
+            _ => "whisper-cli",
+        }
+    }
+}
+
+/// 不输出时间戳和运行信息，只在标准输出打印识别结果
+fn build_args(
+    model: &str,
+    audio_path: &Path,
+    language: Option<&str>,
+    prompt: Option<&str>,
+) -> Vec<String> {
+    let mut args = vec![
+        "-m".to_string(),
+        model.to_string(),
+        "-f".to_string(),
+        audio_path.display().to_string(),
+        "-l".to_string(),
+        language.unwrap_or("auto").to_string(),
+        "-nt".to_string(),
+        "-np".to_string(),
+    ];
+    if let Some(prompt) = prompt {
+        args.push("--prompt".to_string());
+        args.push(prompt.to_string());
+    }
+    args
+}
+
+/// "[BLANK_AUDIO]"、"[Music]" 等非语音标记
+fn is_marker(line: &str) -> bool {
+    line.starts_with('[') && line.ends_with(']')
+}
+
+/// whisper.cpp 按片段分行输出，去掉空行和 "[BLANK_AUDIO]" 等标记后合并
+fn clean_transcript(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !is_marker(line))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn run_program(program: &str, args: &[String]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("无法启动 {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} 识别失败: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(clean_transcript(&String::from_utf8_lossy(&output.stdout)))
+}
+
+impl SttEngine for LocalSttEngine {
+    fn transcribe<'a>(
+        &'a self,
+        request: TranscriptionRequest<'a>,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        Box::pin(async move {
+            if self.config.model.is_empty() {
+                return Err("未配置 whisper.cpp 模型".to_string());
+            }
+            let audio_path =
+                std::env::temp_dir().join(format!("okey_ai_stt_{}.wav", uuid::Uuid::new_v4()));
+            std::fs::write(&audio_path, request.audio)
+                .map_err(|e| format!("写入临时音频失败: {}", e))?;
+
+            let program = self.program().to_string();
+            let args = build_args(
+                &self.config.model,
+                &audio_path,
+                request.language,
+                request.prompt,
+            );
+            let result = tauri::async_runtime::spawn_blocking(move || run_program(&program, &args))
+                .await
+                .map_err(|e| e.to_string());
+            let _ = std::fs::remove_file(&audio_path);
+            result?
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_args() {
+        let args = build_args("ggml-base.bin", Path::new("/tmp/a.wav"), None, Some("okey"));
+        assert_eq!(
+            args,
+            [
+                "-m",
+                "ggml-base.bin",
+                "-f",
+                "/tmp/a.wav",
+                "-l",
+                "auto",
+                "-nt",
+                "-np",
+                "--prompt",
+                "okey"
+            ]
+        );
+        assert_eq!(
+            build_args("m", Path::new("a.wav"), Some("zh"), None)[5],
+            "zh"
+        );
+    }
+
+    #[test]
+    fn test_clean_transcript() {
+        assert_eq!(
+            clean_transcript("\n Hello there.\n[BLANK_AUDIO]\n How are you?\n"),
+            "Hello there. How are you?"
+        );
+        assert_eq!(clean_transcript("[BLANK_AUDIO]\n"), "");
+    }
+}
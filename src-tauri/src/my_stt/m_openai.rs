@@ -0,0 +1,146 @@
+use crate::my_config::OpenAiSttConfig;
+use crate::my_stt::traits::{SttEngine, TranscriptionRequest};
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+use tauri_plugin_http::reqwest;
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+/// multipart/form-data 请求体的一部分
+enum FormPart<'a> {
+    Text(&'a str, &'a str),
+    /// 字段名、文件名、类型和内容
+    File(&'a str, &'a str, &'a str, &'a [u8]),
+}
+
+fn multipart_body(boundary: &str, parts: &[FormPart]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for part in parts {
+        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        match part {
+            FormPart::Text(name, value) => {
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                        name, value
+                    )
+                    .as_bytes(),
+                );
+            }
+            FormPart::File(name, file_name, content_type, content) => {
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                        name, file_name, content_type
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(content);
+                body.extend_from_slice(b"\r\n");
+            }
+        }
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+/// OpenAI 兼容的 `/audio/transcriptions` 接口
+#[derive(Debug)]
+pub struct OpenAISttEngine {
+    config: OpenAiSttConfig,
+    client: reqwest::Client,
+}
+
+impl OpenAISttEngine {
+    pub fn new(config: OpenAiSttConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl SttEngine for OpenAISttEngine {
+    fn transcribe<'a>(
+        &'a self,
+        request: TranscriptionRequest<'a>,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        Box::pin(async move {
+            let api_url = format!("{}/audio/transcriptions", self.config.base_url);
+            let boundary = format!("okey-ai-{}", uuid::Uuid::new_v4().simple());
+
+            let mut parts = vec![
+                FormPart::Text("model", &self.config.model),
+                FormPart::Text("response_format", "json"),
+                FormPart::File("file", "audio.wav", "audio/wav", request.audio),
+            ];
+            if let Some(language) = request.language {
+                parts.push(FormPart::Text("language", language));
+            }
+            if let Some(prompt) = request.prompt {
+                parts.push(FormPart::Text("prompt", prompt));
+            }
+
+            let mut http_request = self
+                .client
+                .post(&api_url)
+                .header(
+                    "Content-Type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .body(multipart_body(&boundary, &parts));
+            if !self.config.api_key.is_empty() {
+                http_request =
+                    http_request.header("Authorization", format!("Bearer {}", self.config.api_key));
+            }
+            let response = http_request
+                .send()
+                .await
+                .map_err(|e| format!("Failed to send request: {}", e))?;
+
+            if !response.status().is_success() {
+                return Err(format!(
+                    "API request failed with status: {}",
+                    response.status()
+                ));
+            }
+
+            let response_text = response
+                .text()
+                .await
+                .map_err(|e| format!("Failed to read response text: {}", e))?;
+            let transcription: TranscriptionResponse = serde_json::from_str(&response_text)
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            Ok(transcription.text)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipart_body() {
+        let body = multipart_body(
+            "b",
+            &[
+                FormPart::Text("model", "whisper-1"),
+                FormPart::File("file", "audio.wav", "audio/wav", b"RIFF"),
+            ],
+        );
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "--b\r\n\
+             Content-Disposition: form-data; name=\"model\"\r\n\r\nwhisper-1\r\n\
+             --b\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"audio.wav\"\r\n\
+             Content-Type: audio/wav\r\n\r\nRIFF\r\n\
+             --b--\r\n"
+        );
+    }
+}
@@ -0,0 +1,157 @@
+pub mod commands;
+pub mod m_local;
+pub mod m_openai;
+pub mod traits;
+
+use crate::my_config::{self, SttConfig, SttEngineKind};
+use crate::my_events::event_names;
+use crate::my_windows;
+use crate::utils::text_translation;
+use m_local::LocalSttEngine;
+use m_openai::OpenAISttEngine;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use traits::{SttEngine, TranscriptionRequest};
+
+/// 录音窗口，长按触发键时显示
+const RECORDING_WINDOW: &str = "input_method_editor";
+
+/// 录音窗口页面和长按的状态，两者同时在一把锁下修改，保证开始录音只通知一次
+struct RecordingState {
+    /// 当前加载的页面已注册录音事件的监听
+    window_ready: bool,
+    /// 正在长按录音
+    recording: bool,
+}
+
+impl RecordingState {
+    const fn new() -> Self {
+        Self {
+            window_ready: false,
+            recording: false,
+        }
+    }
+
+    /// 开始长按，返回是否可以直接通知页面开始录音
+    fn start(&mut self) -> bool {
+        self.recording = true;
+        self.window_ready
+    }
+
+    /// 结束长按，返回之前是否在录音
+    fn stop(&mut self) -> bool {
+        std::mem::take(&mut self.recording)
+    }
+
+    /// 页面注册好监听，返回是否需要立即开始录音
+    fn page_ready(&mut self) -> bool {
+        self.window_ready = true;
+        self.recording
+    }
+
+    /// 页面重新加载或窗口被销毁，之前注册的监听已失效，需等新页面再次就绪
+    fn page_unloaded(&mut self) {
+        self.window_ready = false;
+    }
+}
+
+static RECORDING_STATE: Mutex<RecordingState> = Mutex::new(RecordingState::new());
+
+fn create_engine(config: &SttConfig) -> Box<dyn SttEngine + Send + Sync> {
+    match config.engine {
+        SttEngineKind::OpenAi => Box::new(OpenAISttEngine::new(config.openai.clone())),
+        SttEngineKind::Local => Box::new(LocalSttEngine::new(config.local.clone())),
+    }
+}
+
+/// 空字符串视为未设置
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 识别 16kHz 单声道 wav 音频，语言和提示词取自配置
+pub async fn transcribe(app: &AppHandle, audio: &[u8]) -> Result<String, String> {
+    let config = my_config::get_global_config(app)
+        .map_err(|e| format!("获取配置失败: {}", e))?
+        .stt;
+    let engine = create_engine(&config);
+    let transcript = engine
+        .transcribe(TranscriptionRequest {
+            audio,
+            language: non_empty(&config.language),
+            prompt: non_empty(&config.prompt),
+        })
+        .await?;
+    Ok(transcript.trim().to_string())
+}
+
+/// 识别后把文本作为新的用户消息送入翻译流程，在气泡中显示译文
+pub async fn transcribe_and_translate(app: &AppHandle, audio: &[u8]) -> Result<String, String> {
+    let transcript = transcribe(app, audio).await?;
+    if !transcript.is_empty() {
+        text_translation::translate_text_bubble(app, transcript.clone());
+    }
+    Ok(transcript)
+}
+
+/// 是否启用了语音输入
+pub fn is_enabled(app: &AppHandle) -> bool {
+    my_config::get_global_config(app).is_ok_and(|config| config.stt.enabled)
+}
+
+/// 显示录音窗口并通知页面开始录音；页面尚未就绪时，由页面就绪后通过
+/// `recording_window_ready` 得知需要录音
+pub fn start_recording(app: &AppHandle) {
+    my_windows::window_input_method_editor_show(app);
+    let mut state = RECORDING_STATE.lock().unwrap();
+    if state.start() {
+        let _ = app.emit_to(RECORDING_WINDOW, event_names::RECORDING_START, ());
+    }
+}
+
+/// 通知页面结束录音，页面随后把音频交给 `transcribe_audio` 命令；没有在录音时只隐藏窗口
+pub fn stop_recording(app: &AppHandle) {
+    let was_recording = RECORDING_STATE.lock().unwrap().stop();
+    if was_recording {
+        let _ = app.emit_to(RECORDING_WINDOW, event_names::RECORDING_STOP, ());
+    }
+    my_windows::window_input_method_editor_hide(app);
+}
+
+/// 录音窗口页面注册好监听后调用，返回是否正在长按、需要立即开始录音
+pub fn set_window_ready() -> bool {
+    RECORDING_STATE.lock().unwrap().page_ready()
+}
+
+/// 窗口开始加载页面或被销毁时调用，录音窗口的页面需重新就绪后才能接收录音事件
+pub fn on_window_unloaded(label: &str) {
+    if label == RECORDING_WINDOW {
+        RECORDING_STATE.lock().unwrap().page_unloaded();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_state_across_sessions() {
+        let mut state = RecordingState::new();
+        // 页面就绪前开始长按，由页面就绪时得知需要录音
+        assert!(!state.start());
+        assert!(state.page_ready());
+        assert!(state.stop());
+
+        // 窗口隐藏后页面仍在，第二次长按直接通知页面
+        assert!(state.start());
+        assert!(state.stop());
+        assert!(!state.stop());
+
+        // 页面重新加载后旧的监听失效，等新页面就绪
+        state.page_unloaded();
+        assert!(!state.start());
+        assert!(state.page_ready());
+        assert!(state.stop());
+        assert!(!state.page_ready());
+    }
+}
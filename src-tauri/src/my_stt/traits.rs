@@ -0,0 +1,17 @@
+use std::future::Future;
+use std::pin::Pin;
+
+/// 一次识别请求，音频为 16kHz 单声道 wav
+pub struct TranscriptionRequest<'a> {
+    pub audio: &'a [u8],
+    /// ISO 639-1 语言代码，为空时自动识别
+    pub language: Option<&'a str>,
+    pub prompt: Option<&'a str>,
+}
+
+pub trait SttEngine {
+    fn transcribe<'a>(
+        &'a self,
+        request: TranscriptionRequest<'a>,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
+}
@@ -0,0 +1,15 @@
+use crate::my_config::{self, ToolsConfig};
+use crate::my_tools;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn get_tools_config(app: AppHandle) -> Result<ToolsConfig, String> {
+    my_config::get_global_config(&app)
+        .map(|config| config.tools)
+        .map_err(|e| format!("获取配置失败: {}", e))
+}
+
+#[tauri::command]
+pub fn set_tools_config(app: AppHandle, config: ToolsConfig) -> Result<(), String> {
+    my_tools::set_tools_config(&app, config)
+}
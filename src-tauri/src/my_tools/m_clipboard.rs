@@ -0,0 +1,32 @@
+use crate::my_api::traits::ToolDefinition;
+use crate::my_tools::traits::{truncate_result, Tool};
+use crate::utils::clipboard::{ClipboardBackend, SystemClipboard};
+use std::future::Future;
+use std::pin::Pin;
+
+/// 读取剪贴板中的文本，用户提到“剪贴板里的内容”时由模型调用
+pub struct ClipboardTool;
+
+impl Tool for ClipboardTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition::function(
+            "read_clipboard",
+            "读取用户剪贴板中的文本。用户提到剪贴板或刚复制的内容时调用。",
+            serde_json::json!({ "type": "object", "properties": {} }),
+        )
+    }
+
+    fn call<'a>(
+        &'a self,
+        _arguments: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        // 剪贴板句柄不能跨线程，在返回 future 之前读取
+        let result = SystemClipboard::new().map(|mut clipboard| {
+            match clipboard.read_text().filter(|text| !text.trim().is_empty()) {
+                Some(text) => truncate_result(&text),
+                None => "剪贴板中没有文本".to_string(),
+            }
+        });
+        Box::pin(async move { result })
+    }
+}
@@ -0,0 +1,107 @@
+use crate::my_api::traits::ToolDefinition;
+use crate::my_tools::traits::{parse_arguments, truncate_result, Tool};
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+use tauri_plugin_http::reqwest;
+
+/// 通过在线词典查询单词的释义和例句
+pub struct DictionaryTool {
+    /// 查询地址，`{word}` 替换为单词
+    url: String,
+    client: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct Arguments {
+    word: String,
+}
+
+impl DictionaryTool {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// 对单词做百分号编码后填入查询地址
+fn lookup_url(template: &str, word: &str) -> String {
+    let encoded: String = word
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    template.replace("{word}", &encoded)
+}
+
+impl Tool for DictionaryTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition::function(
+            "dictionary",
+            "在英文词典中查询单词的音标、词性、释义和例句。需要确认生僻词或多义词的含义时调用。",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "word": { "type": "string", "description": "要查询的单词原形" }
+                },
+                "required": ["word"]
+            }),
+        )
+    }
+
+    fn call<'a>(
+        &'a self,
+        arguments: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        Box::pin(async move {
+            let arguments: Arguments = parse_arguments(arguments)?;
+            let word = arguments.word.trim();
+            if word.is_empty() {
+                return Err("单词为空".to_string());
+            }
+
+            let response = self
+                .client
+                .get(lookup_url(&self.url, word))
+                .send()
+                .await
+                .map_err(|e| format!("词典查询失败: {}", e))?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(format!("词典中没有 {}", word));
+            }
+            if !response.status().is_success() {
+                return Err(format!("词典查询失败: {}", response.status()));
+            }
+            let body = response
+                .text()
+                .await
+                .map_err(|e| format!("读取词典结果失败: {}", e))?;
+            Ok(truncate_result(&body))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_url() {
+        let template = "https://example.com/en/{word}";
+        assert_eq!(lookup_url(template, "run"), "https://example.com/en/run");
+        assert_eq!(
+            lookup_url(template, "ice cream"),
+            "https://example.com/en/ice%20cream"
+        );
+        assert_eq!(
+            lookup_url(template, "café"),
+            "https://example.com/en/caf%C3%A9"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::my_api::traits::ToolDefinition;
+use crate::my_tools::traits::{parse_arguments, Tool};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+
+/// 查询用户的术语表，让译文使用指定的译法
+pub struct GlossaryTool {
+    glossary: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct Arguments {
+    text: String,
+}
+
+impl GlossaryTool {
+    pub fn new(glossary: BTreeMap<String, String>) -> Self {
+        Self { glossary }
+    }
+}
+
+/// 查找文本中出现的术语，不区分大小写，较长的术语在前
+fn lookup<'a>(glossary: &'a BTreeMap<String, String>, text: &str) -> Vec<(&'a str, &'a str)> {
+    let text = text.to_lowercase();
+    let mut entries: Vec<_> = glossary
+        .iter()
+        .filter(|(term, _)| !term.is_empty() && text.contains(&term.to_lowercase()))
+        .map(|(term, translation)| (term.as_str(), translation.as_str()))
+        .collect();
+    entries.sort_by_key(|(term, _)| std::cmp::Reverse(term.chars().count()));
+    entries
+}
+
+impl Tool for GlossaryTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition::function(
+            "glossary",
+            "查询用户术语表中的指定译法。翻译含有专有名词或术语的文本前调用，翻译时必须使用返回的译法。",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string", "description": "要查询的术语或包含术语的原文" }
+                },
+                "required": ["text"]
+            }),
+        )
+    }
+
+    fn call<'a>(
+        &'a self,
+        arguments: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        Box::pin(async move {
+            let arguments: Arguments = parse_arguments(arguments)?;
+            let entries = lookup(&self.glossary, &arguments.text);
+            if entries.is_empty() {
+                return Ok("术语表中没有相关术语".to_string());
+            }
+            Ok(entries
+                .iter()
+                .map(|(term, translation)| format!("{} => {}", term, translation))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let glossary = BTreeMap::from([
+            ("Tauri".to_string(), "Tauri".to_string()),
+            ("borrow".to_string(), "借用".to_string()),
+            ("borrow checker".to_string(), "借用检查器".to_string()),
+            ("".to_string(), "空".to_string()),
+        ]);
+        assert_eq!(
+            lookup(&glossary, "The Borrow Checker rejects this"),
+            vec![("borrow checker", "借用检查器"), ("borrow", "借用")]
+        );
+        assert!(lookup(&glossary, "hello").is_empty());
+    }
+}
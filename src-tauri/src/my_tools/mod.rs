@@ -0,0 +1,168 @@
+pub mod commands;
+pub mod m_clipboard;
+pub mod m_dictionary;
+pub mod m_glossary;
+pub mod traits;
+
+use crate::my_api::traits::ToolDefinition;
+use crate::my_config::{self, ToolsConfig};
+use crate::utils::chat_message::ToolCall;
+use crate::utils::translation_manager::TranslationManager;
+use m_clipboard::ClipboardTool;
+use m_dictionary::DictionaryTool;
+use m_glossary::GlossaryTool;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use traits::Tool;
+
+type SharedTool = Arc<dyn Tool + Send + Sync>;
+
+/// 对话中模型可以调用的工具，按名称索引
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: BTreeMap<String, SharedTool>,
+    max_rounds: usize,
+}
+
+impl ToolRegistry {
+    pub fn from_config(config: &ToolsConfig) -> Self {
+        let mut registry = ToolRegistry {
+            tools: BTreeMap::new(),
+            max_rounds: config.max_rounds,
+        };
+        if !config.enabled {
+            return registry;
+        }
+        if !config.glossary.is_empty() {
+            registry.register(Arc::new(GlossaryTool::new(config.glossary.clone())));
+        }
+        if !config.dictionary_url.is_empty() {
+            registry.register(Arc::new(DictionaryTool::new(config.dictionary_url.clone())));
+        }
+        if config.clipboard {
+            registry.register(Arc::new(ClipboardTool));
+        }
+        registry
+    }
+
+    pub fn register(&mut self, tool: SharedTool) {
+        self.tools.insert(tool.definition().function.name, tool);
+    }
+
+    /// 第 `round` 轮请求可用的工具；没有工具或已达到轮数上限时为 None，模型必须直接回答
+    pub fn definitions(&self, round: usize) -> Option<Vec<ToolDefinition>> {
+        if self.tools.is_empty() || round >= self.max_rounds {
+            return None;
+        }
+        Some(self.tools.values().map(|tool| tool.definition()).collect())
+    }
+
+    pub fn max_rounds(&self) -> usize {
+        self.max_rounds
+    }
+
+    /// 执行一次工具调用，出错时把错误作为结果交给模型
+    pub async fn execute(&self, call: &ToolCall) -> String {
+        let result = match self.tools.get(&call.function.name) {
+            Some(tool) => tool.call(&call.function.arguments).await,
+            None => Err(format!("没有名为 {} 的工具", call.function.name)),
+        };
+        result.unwrap_or_else(|e| {
+            eprintln!("工具 {} 执行失败: {}", call.function.name, e);
+            format!("错误: {}", e)
+        })
+    }
+}
+
+pub fn setup_tools(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let config = my_config::get_global_config(app)?.tools;
+    apply_tools_config(app, &config);
+    Ok(())
+}
+
+/// 保存工具配置并立即生效
+pub fn set_tools_config(app: &AppHandle, config: ToolsConfig) -> Result<(), String> {
+    let mut global_config =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    global_config.tools = config;
+    my_config::set_global_config(app, &global_config)
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    apply_tools_config(app, &global_config.tools);
+    Ok(())
+}
+
+pub fn apply_tools_config(app: &AppHandle, config: &ToolsConfig) {
+    if let Some(manager) = app.try_state::<TranslationManager>() {
+        manager.set_tools(ToolRegistry::from_config(config));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::chat_message::FunctionCall;
+
+    fn call(name: &str, arguments: &str) -> ToolCall {
+        ToolCall {
+            id: "call_1".to_string(),
+            kind: "function".to_string(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_registry_from_config() {
+        let disabled = ToolRegistry::from_config(&ToolsConfig {
+            glossary: BTreeMap::from([("Rust".to_string(), "Rust".to_string())]),
+            ..ToolsConfig::default()
+        });
+        assert!(disabled.definitions(0).is_none());
+
+        let registry = ToolRegistry::from_config(&ToolsConfig {
+            enabled: true,
+            max_rounds: 2,
+            glossary: BTreeMap::from([("Rust".to_string(), "Rust".to_string())]),
+            clipboard: true,
+            ..ToolsConfig::default()
+        });
+        let names: Vec<_> = registry
+            .definitions(0)
+            .unwrap()
+            .into_iter()
+            .map(|definition| definition.function.name)
+            .collect();
+        assert_eq!(names, ["dictionary", "glossary", "read_clipboard"]);
+        assert!(registry.definitions(1).is_some());
+        assert!(registry.definitions(2).is_none());
+    }
+
+    #[test]
+    fn test_execute() {
+        let registry = ToolRegistry::from_config(&ToolsConfig {
+            enabled: true,
+            glossary: BTreeMap::from([("borrow".to_string(), "借用".to_string())]),
+            dictionary_url: String::new(),
+            ..ToolsConfig::default()
+        });
+        tauri::async_runtime::block_on(async {
+            assert_eq!(
+                registry
+                    .execute(&call("glossary", r#"{"text":"borrow it"}"#))
+                    .await,
+                "borrow => 借用"
+            );
+            assert!(registry
+                .execute(&call("glossary", "{"))
+                .await
+                .starts_with("错误: 参数格式错误"));
+            assert_eq!(
+                registry.execute(&call("unknown", "{}")).await,
+                "错误: 没有名为 unknown 的工具"
+            );
+        });
+    }
+}
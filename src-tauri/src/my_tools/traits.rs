@@ -0,0 +1,65 @@
+use crate::my_api::traits::ToolDefinition;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::pin::Pin;
+
+/// 返回给模型的工具结果最大字符数
+pub const MAX_RESULT_CHARS: usize = 4000;
+
+pub trait Tool {
+    /// 工具名称、说明和参数，名称在注册表中唯一
+    fn definition(&self) -> ToolDefinition;
+
+    /// 执行工具，参数为模型给出的 JSON 字符串，返回交给模型的文本
+    fn call<'a>(
+        &'a self,
+        arguments: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
+}
+
+/// 解析模型给出的参数，空字符串按空对象处理
+pub fn parse_arguments<T: DeserializeOwned>(arguments: &str) -> Result<T, String> {
+    let arguments = match arguments.trim() {
+        "" => "{}",
+        arguments => arguments,
+    };
+    serde_json::from_str(arguments).map_err(|e| format!("参数格式错误: {}", e))
+}
+
+/// 截断过长的结果，避免占满上下文
+pub fn truncate_result(text: &str) -> String {
+    match text.char_indices().nth(MAX_RESULT_CHARS) {
+        Some((end, _)) => format!("{}…（已截断）", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Arguments {
+        #[serde(default)]
+        word: String,
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let arguments: Arguments = parse_arguments(r#"{"word":"run"}"#).unwrap();
+        assert_eq!(arguments.word, "run");
+        let empty: Arguments = parse_arguments(" ").unwrap();
+        assert_eq!(empty.word, "");
+        assert!(parse_arguments::<Arguments>("{\"word\":").is_err());
+    }
+
+    #[test]
+    fn test_truncate_result() {
+        assert_eq!(truncate_result("短文本"), "短文本");
+        let long = "字".repeat(MAX_RESULT_CHARS + 10);
+        let truncated = truncate_result(&long);
+        assert!(truncated.starts_with(&"字".repeat(MAX_RESULT_CHARS)));
+        assert!(truncated.ends_with("（已截断）"));
+    }
+}
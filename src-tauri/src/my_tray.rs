@@ -1,30 +1,74 @@
 use tauri::{
-    menu::{MenuBuilder, MenuItem},
+    menu::{CheckMenuItem, MenuBuilder, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Runtime,
+    AppHandle, Manager, Runtime,
 };
 
+use crate::my_actions::{self, action_ids, ActionRegistry};
 use crate::{my_config, my_windows};
 
+/// 需要在运行时同步状态的托盘菜单项
+pub struct TrayMenuItems<R: Runtime> {
+    pub clipboard_watch: CheckMenuItem<R>,
+}
+
+pub fn set_clipboard_watch_checked<R: Runtime>(app_handle: &AppHandle<R>, checked: bool) {
+    if let Some(items) = app_handle.try_state::<TrayMenuItems<R>>() {
+        let _ = items.clipboard_watch.set_checked(checked);
+    }
+}
+
 /*******  ab7e53dc-7cba-45e1-8b3a-3837c9b2580a  *******/
-pub fn create_tray<R: Runtime>(app_handle: &AppHandle<R>) -> tauri::Result<()> {
-    // 创建菜单项
-    let show_item = MenuItem::with_id(app_handle, "show", "Show", true, None::<&str>)?;
+pub fn create_tray(app_handle: &AppHandle) -> tauri::Result<()> {
+    // 创建菜单项，绑定动作的菜单项以动作 id 作为菜单 id
+    let show_item = MenuItem::with_id(
+        app_handle,
+        action_ids::SHOW_ABOUT,
+        "Show",
+        true,
+        None::<&str>,
+    )?;
+    let history_item = MenuItem::with_id(
+        app_handle,
+        action_ids::OPEN_HISTORY,
+        "History",
+        true,
+        None::<&str>,
+    )?;
+    let clipboard_watch_enabled = my_config::get_global_config(app_handle)
+        .map(|config| config.clipboard_watch.enabled)
+        .unwrap_or(false);
+    let clipboard_watch_item = CheckMenuItem::with_id(
+        app_handle,
+        action_ids::TOGGLE_CLIPBOARD_WATCH,
+        "Clipboard Watch",
+        true,
+        clipboard_watch_enabled,
+        None::<&str>,
+    )?;
     let test_item = MenuItem::with_id(app_handle, "test", "Test", true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app_handle, "quit", "Quit", true, None::<&str>)?;
 
     // 构建菜单
     let menu = MenuBuilder::new(app_handle)
         .item(&show_item)
+        .item(&history_item)
+        .item(&clipboard_watch_item)
         .item(&test_item)
         .separator()
         .item(&quit_item)
         .build()?;
 
+    app_handle.manage(TrayMenuItems {
+        clipboard_watch: clipboard_watch_item,
+    });
+
     // 菜单点击事件
     app_handle.on_menu_event(|app, event| match event.id().as_ref() {
-        "show" => {
-            my_windows::window_about_show(app);
+        id if app.state::<ActionRegistry>().contains(id) => {
+            if let Err(e) = my_actions::run_action(app, id) {
+                eprintln!("{}", e);
+            }
         }
         "test" => {
             let config = my_config::get_global_config(app);
@@ -0,0 +1,155 @@
+//! 合成音频的磁盘缓存，按引擎、声音和文本索引，超出上限时删除最久未使用的文件
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub struct TtsCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+/// 缓存文件的信息，用于选出要删除的文件
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// FNV-1a，结果在不同版本和平台间保持一致，可以作为文件名
+fn fnv1a(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // 用 0 分隔各部分，避免 ("ab", "c") 和 ("a", "bc") 相同
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+pub fn cache_key(engine_id: &str, voice: &str, text: &str) -> String {
+    format!("{:016x}", fnv1a(&[engine_id, voice, text]))
+}
+
+/// 从最久未使用的文件开始删除，直到总大小不超过上限
+fn select_evictions(mut entries: Vec<CacheEntry>, max_bytes: u64) -> Vec<PathBuf> {
+    let mut total: u64 = entries.iter().map(|entry| entry.size).sum();
+    entries.sort_by_key(|entry| entry.modified);
+    entries
+        .into_iter()
+        .take_while(|entry| {
+            let evict = total > max_bytes;
+            total = total.saturating_sub(entry.size);
+            evict
+        })
+        .map(|entry| entry.path)
+        .collect()
+}
+
+impl TtsCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    pub fn set_max_bytes(&mut self, max_bytes: u64) {
+        self.max_bytes = max_bytes;
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.audio", key))
+    }
+
+    /// 读取缓存的音频，命中时更新修改时间以记录最近使用
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path(key);
+        let bytes = fs::read(&path).ok()?;
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(bytes)
+    }
+
+    pub fn put(&self, key: &str, bytes: &[u8]) -> Result<(), String> {
+        if self.max_bytes == 0 {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+        fs::write(self.path(key), bytes).map_err(|e| format!("写入缓存失败: {}", e))?;
+        self.evict();
+        Ok(())
+    }
+
+    fn evict(&self) {
+        for path in select_evictions(list_entries(&self.dir), self.max_bytes) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        for entry in list_entries(&self.dir) {
+            fs::remove_file(entry.path).map_err(|e| format!("删除缓存失败: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+fn list_entries(dir: &Path) -> Vec<CacheEntry> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "audio"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(CacheEntry {
+                path: entry.path(),
+                size: metadata.len(),
+                modified: metadata.modified().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cache_key() {
+        let key = cache_key("openai:tts-1", "alloy", "hello");
+        assert_eq!(key.len(), 16);
+        assert_eq!(key, cache_key("openai:tts-1", "alloy", "hello"));
+        assert_ne!(key, cache_key("openai:tts-1", "nova", "hello"));
+        assert_ne!(cache_key("a", "bc", ""), cache_key("ab", "c", ""));
+    }
+
+    #[test]
+    fn test_select_evictions() {
+        let now = SystemTime::now();
+        let entry = |name: &str, size, age| CacheEntry {
+            path: PathBuf::from(name),
+            size,
+            modified: now - Duration::from_secs(age),
+        };
+        let entries = vec![
+            entry("new", 40, 1),
+            entry("old", 30, 100),
+            entry("mid", 50, 10),
+        ];
+
+        assert!(select_evictions(entries.clone(), 120).is_empty());
+        assert_eq!(
+            select_evictions(entries.clone(), 100),
+            [PathBuf::from("old")]
+        );
+        assert_eq!(
+            select_evictions(entries, 40),
+            [PathBuf::from("old"), PathBuf::from("mid")]
+        );
+    }
+}
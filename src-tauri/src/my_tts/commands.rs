@@ -0,0 +1,51 @@
+use crate::my_config::{self, TtsConfig};
+use crate::my_tts::player::PlaybackState;
+use crate::my_tts::{self, TtsManager};
+use tauri::{AppHandle, State};
+
+/// 朗读文本，返回 false 表示当前使用页面朗读，由页面自行处理
+#[tauri::command]
+pub async fn tts_speak(
+    text: String,
+    voice: Option<String>,
+    state: State<'_, TtsManager>,
+) -> Result<bool, String> {
+    state.speak(&text, voice).await
+}
+
+#[tauri::command]
+pub fn tts_stop(state: State<'_, TtsManager>) -> Result<(), String> {
+    state.stop()
+}
+
+#[tauri::command]
+pub fn tts_pause(state: State<'_, TtsManager>) -> Result<(), String> {
+    state.pause()
+}
+
+#[tauri::command]
+pub fn tts_resume(state: State<'_, TtsManager>) -> Result<(), String> {
+    state.resume()
+}
+
+#[tauri::command]
+pub fn tts_state(state: State<'_, TtsManager>) -> PlaybackState {
+    state.state()
+}
+
+#[tauri::command]
+pub fn tts_clear_cache(state: State<'_, TtsManager>) -> Result<(), String> {
+    state.clear_cache()
+}
+
+#[tauri::command]
+pub fn get_tts_config(app: AppHandle) -> Result<TtsConfig, String> {
+    my_config::get_global_config(&app)
+        .map(|config| config.tts)
+        .map_err(|e| format!("获取配置失败: {}", e))
+}
+
+#[tauri::command]
+pub fn set_tts_config(app: AppHandle, config: TtsConfig) -> Result<(), String> {
+    my_tts::set_tts_config(&app, config)
+}
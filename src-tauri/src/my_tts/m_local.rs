@@ -0,0 +1,134 @@
+use crate::my_config::{LocalTtsConfig, LocalTtsProgram};
+use crate::my_tts::traits::{resolve_voice, TtsEngine};
+use std::future::Future;
+use std::io::Write;
+use std::pin::Pin;
+use std::process::{Command, Stdio};
+
+/// 调用本地的 espeak-ng 或 piper 合成 wav，文本从标准输入传入
+#[derive(Debug)]
+pub struct LocalTtsEngine {
+    config: LocalTtsConfig,
+    speed: f32,
+}
+
+impl LocalTtsEngine {
+    pub fn new(config: LocalTtsConfig, speed: f32) -> Self {
+        Self {
+            config,
+            speed: speed.clamp(0.25, 4.0),
+        }
+    }
+
+    fn program(&self) -> &str {
+        match &self.config.path {
+            Some(path) if !path.is_empty() => path,
+            _ => match self.config.program {
+                LocalTtsProgram::EspeakNg => "espeak-ng",
+                LocalTtsProgram::Piper => "piper",
+            },
+        }
+    }
+}
+
+/// 合成到标准输出的命令行参数
+fn build_args(program: LocalTtsProgram, voice: &str, speed: f32) -> Vec<String> {
+    match program {
+        // espeak-ng 默认语速为每分钟 175 词
+        LocalTtsProgram::EspeakNg => vec![
+            "--stdout".to_string(),
+            "--stdin".to_string(),
+            "-v".to_string(),
+            voice.to_string(),
+            "-s".to_string(),
+            ((175.0 * speed).round() as u32).to_string(),
+        ],
+        // piper 用 length_scale 控制语速，数值越大越慢
+        LocalTtsProgram::Piper => vec![
+            "--model".to_string(),
+            voice.to_string(),
+            "--output_file".to_string(),
+            "-".to_string(),
+            "--length_scale".to_string(),
+            format!("{:.2}", 1.0 / speed),
+        ],
+    }
+}
+
+fn run_program(program: &str, args: &[String], text: &str) -> Result<Vec<u8>, String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("无法启动 {}: {}", program, e))?;
+
+    // 写完后关闭标准输入，程序才会开始合成
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("写入 {} 失败: {}", program, e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("{} 运行失败: {}", program, e))?;
+
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!(
+            "{} 合成失败: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+impl TtsEngine for LocalTtsEngine {
+    fn id(&self) -> String {
+        format!("local:{}:{}", self.program(), self.speed)
+    }
+
+    fn voice_for(&self, language: &str) -> Option<String> {
+        resolve_voice(&self.config.voices, &self.config.default_voice, language)
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        text: &'a str,
+        voice: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, String>> + Send + 'a>> {
+        let program = self.program().to_string();
+        let args = build_args(self.config.program, voice, self.speed);
+        let text = text.to_string();
+        Box::pin(async move {
+            tauri::async_runtime::spawn_blocking(move || run_program(&program, &args, &text))
+                .await
+                .map_err(|e| e.to_string())?
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_args() {
+        assert_eq!(
+            build_args(LocalTtsProgram::EspeakNg, "en-us", 1.2),
+            ["--stdout", "--stdin", "-v", "en-us", "-s", "210"]
+        );
+        assert_eq!(
+            build_args(LocalTtsProgram::Piper, "/voices/zh.onnx", 0.8),
+            [
+                "--model",
+                "/voices/zh.onnx",
+                "--output_file",
+                "-",
+                "--length_scale",
+                "1.25"
+            ]
+        );
+    }
+}
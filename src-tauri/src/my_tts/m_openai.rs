@@ -0,0 +1,94 @@
+use crate::my_config::OpenAiTtsConfig;
+use crate::my_tts::traits::{resolve_voice, TtsEngine};
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use tauri_plugin_http::reqwest;
+
+#[derive(Debug, Serialize)]
+struct SpeechRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+    speed: f32,
+}
+
+/// OpenAI 兼容的 `/audio/speech` 接口
+#[derive(Debug)]
+pub struct OpenAITtsEngine {
+    config: OpenAiTtsConfig,
+    speed: f32,
+    client: reqwest::Client,
+}
+
+impl OpenAITtsEngine {
+    pub fn new(config: OpenAiTtsConfig, speed: f32) -> Self {
+        Self {
+            config,
+            // 接口支持的范围为 0.25 到 4.0
+            speed: speed.clamp(0.25, 4.0),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl TtsEngine for OpenAITtsEngine {
+    fn id(&self) -> String {
+        format!(
+            "openai:{}:{}:{}",
+            self.config.base_url, self.config.model, self.speed
+        )
+    }
+
+    fn voice_for(&self, language: &str) -> Option<String> {
+        resolve_voice(&self.config.voices, &self.config.default_voice, language)
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        text: &'a str,
+        voice: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, String>> + Send + 'a>> {
+        Box::pin(async move {
+            let api_url = format!("{}/audio/speech", self.config.base_url);
+            let body = SpeechRequest {
+                model: &self.config.model,
+                input: text,
+                voice,
+                response_format: "mp3",
+                speed: self.speed,
+            };
+
+            let json_body = serde_json::to_string(&body)
+                .map_err(|e| format!("Failed to serialize request: {}", e))?;
+
+            let mut request = self
+                .client
+                .post(&api_url)
+                .header("Content-Type", "application/json")
+                .body(json_body);
+            if !self.config.api_key.is_empty() {
+                request =
+                    request.header("Authorization", format!("Bearer {}", self.config.api_key));
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| format!("Failed to send request: {}", e))?;
+
+            if !response.status().is_success() {
+                return Err(format!(
+                    "API request failed with status: {}",
+                    response.status()
+                ));
+            }
+
+            response
+                .bytes()
+                .await
+                .map(|bytes| bytes.to_vec())
+                .map_err(|e| format!("Failed to read response body: {}", e))
+        })
+    }
+}
@@ -0,0 +1,209 @@
+pub mod cache;
+pub mod commands;
+pub mod m_local;
+pub mod m_openai;
+pub mod player;
+pub mod traits;
+
+use crate::my_config::{self, TtsConfig, TtsEngineKind};
+use crate::my_dictionary;
+use crate::my_events::event_names;
+use crate::states::setting_states::{self, AutoSpeakState};
+use crate::utils::chat_message::ChatMessage;
+use crate::utils::language_detection;
+use cache::TtsCache;
+use m_local::LocalTtsEngine;
+use m_openai::OpenAITtsEngine;
+use player::AudioPlayer;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Emitter, Manager};
+use traits::TtsEngine;
+
+/// 一次朗读的最大字符数，超出部分不朗读
+const MAX_SPEECH_CHARS: usize = 4096;
+
+type SharedEngine = Arc<dyn TtsEngine + Send + Sync>;
+
+pub struct TtsManager {
+    /// 为空时由页面朗读
+    engine: RwLock<Option<SharedEngine>>,
+    cache: RwLock<TtsCache>,
+    player: AudioPlayer,
+    /// 每次朗读加一，合成完成时已有更新的朗读则不再播放
+    generation: AtomicU64,
+}
+
+fn create_engine(config: &TtsConfig) -> Option<SharedEngine> {
+    match config.engine {
+        TtsEngineKind::Webview => None,
+        TtsEngineKind::OpenAi => Some(Arc::new(OpenAITtsEngine::new(
+            config.openai.clone(),
+            config.speed,
+        ))),
+        TtsEngineKind::Local => Some(Arc::new(LocalTtsEngine::new(
+            config.local.clone(),
+            config.speed,
+        ))),
+    }
+}
+
+fn cache_max_bytes(config: &TtsConfig) -> u64 {
+    config.cache_max_mb * 1024 * 1024
+}
+
+/// 自动朗读：单词模式只读单个词，全部模式读任何非空文本
+fn should_auto_speak(state: AutoSpeakState, text: &str) -> bool {
+    let text = text.trim();
+    match state {
+        AutoSpeakState::Off => false,
+        AutoSpeakState::Single => my_dictionary::is_single_word(text),
+        AutoSpeakState::All => !text.is_empty(),
+    }
+}
+
+impl TtsManager {
+    pub fn new(config: &TtsConfig, cache: TtsCache, player: AudioPlayer) -> Self {
+        Self {
+            engine: RwLock::new(create_engine(config)),
+            cache: RwLock::new(cache),
+            player,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn apply_config(&self, config: &TtsConfig) {
+        *self.engine.write().unwrap() = create_engine(config);
+        self.cache
+            .write()
+            .unwrap()
+            .set_max_bytes(cache_max_bytes(config));
+    }
+
+    /// 朗读文本，未指定声音时按文本语言选择。返回 false 表示使用页面朗读
+    pub async fn speak(&self, text: &str, voice: Option<String>) -> Result<bool, String> {
+        let Some(engine) = self.engine.read().unwrap().clone() else {
+            return Ok(false);
+        };
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let _ = self.player.stop();
+
+        let text: String = text.trim().chars().take(MAX_SPEECH_CHARS).collect();
+        let voice = voice
+            .or_else(|| engine.voice_for(language_detection::detect_language(&text)))
+            .ok_or("未配置朗读声音")?;
+
+        let key = cache::cache_key(&engine.id(), &voice, &text);
+        let cached = self.cache.read().unwrap().get(&key);
+        let audio = match cached {
+            Some(audio) => audio,
+            None => {
+                let audio = engine.synthesize(&text, &voice).await?;
+                if let Err(e) = self.cache.read().unwrap().put(&key, &audio) {
+                    eprintln!("{}", e);
+                }
+                audio
+            }
+        };
+
+        if self.generation.load(Ordering::SeqCst) == generation {
+            self.player.play(audio)?;
+        }
+        Ok(true)
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.player.stop()
+    }
+
+    pub fn pause(&self) -> Result<(), String> {
+        self.player.pause()
+    }
+
+    pub fn resume(&self) -> Result<(), String> {
+        self.player.resume()
+    }
+
+    pub fn state(&self) -> player::PlaybackState {
+        self.player.state()
+    }
+
+    pub fn clear_cache(&self) -> Result<(), String> {
+        self.cache.read().unwrap().clear()
+    }
+}
+
+pub fn setup_tts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let config = my_config::get_global_config(app)?.tts;
+    let cache = TtsCache::new(
+        app.path().app_cache_dir()?.join("tts"),
+        cache_max_bytes(&config),
+    );
+    let app_handle = app.clone();
+    let player = AudioPlayer::new(move |state| {
+        let _ = app_handle.emit(event_names::TTS_STATE_CHANGED, state);
+    });
+    app.manage(TtsManager::new(&config, cache, player));
+    Ok(())
+}
+
+/// 保存语音合成配置并立即生效
+pub fn set_tts_config(app: &AppHandle, config: TtsConfig) -> Result<(), String> {
+    let mut global_config =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    global_config.tts = config;
+    my_config::set_global_config(app, &global_config)
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    apply_tts_config(app, &global_config.tts);
+    Ok(())
+}
+
+pub fn apply_tts_config(app: &AppHandle, config: &TtsConfig) {
+    if let Some(manager) = app.try_state::<TtsManager>() {
+        manager.apply_config(config);
+    }
+}
+
+/// 按自动朗读设置朗读会话的原文，引擎不可用时交给气泡页面朗读
+pub fn auto_speak(app: &AppHandle, chat_history: &[ChatMessage]) {
+    let Some(message) = chat_history.last() else {
+        return;
+    };
+    let text = message
+        .raw
+        .clone()
+        .unwrap_or_else(|| message.content.text().into_owned());
+    if !should_auto_speak(setting_states::get_settings(app).auto_speak, &text) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let spoken = match app.state::<TtsManager>().speak(&text, None).await {
+            Ok(spoken) => spoken,
+            Err(e) => {
+                eprintln!("朗读失败: {}", e);
+                false
+            }
+        };
+        if !spoken {
+            let _ = app.emit(event_names::BUBBLE_AUTO_SPEAK, &text);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_auto_speak() {
+        assert!(!should_auto_speak(AutoSpeakState::Off, "hello"));
+        assert!(should_auto_speak(AutoSpeakState::Single, " hello "));
+        assert!(!should_auto_speak(AutoSpeakState::Single, "hello world"));
+        assert!(!should_auto_speak(AutoSpeakState::Single, "  "));
+        assert!(should_auto_speak(AutoSpeakState::All, "hello world"));
+        assert!(!should_auto_speak(AutoSpeakState::All, ""));
+    }
+}
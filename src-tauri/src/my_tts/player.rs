@@ -0,0 +1,159 @@
+//! 音频播放。rodio 的输出流不能跨线程，因此由专门的线程持有，通过消息控制
+
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use serde::Serialize;
+use std::io::Cursor;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 播放状态，变化时通过回调通知
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaybackState {
+    #[default]
+    Stopped,
+    Playing,
+    Paused,
+}
+
+enum PlayerCommand {
+    /// 停止当前音频并播放新的音频，解码或打开音频设备失败时通过回复返回错误
+    Play(Vec<u8>, Sender<Result<(), String>>),
+    Stop,
+    Pause,
+    Resume,
+}
+
+/// 检查音频是否播放完毕的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct AudioPlayer {
+    sender: Mutex<Sender<PlayerCommand>>,
+    state: Arc<Mutex<PlaybackState>>,
+}
+
+impl AudioPlayer {
+    pub fn new<F>(on_state_changed: F) -> Self
+    where
+        F: Fn(PlaybackState) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let state = Arc::new(Mutex::new(PlaybackState::Stopped));
+        let thread_state = state.clone();
+        std::thread::spawn(move || run_player(receiver, thread_state, on_state_changed));
+        Self {
+            sender: Mutex::new(sender),
+            state,
+        }
+    }
+
+    pub fn play(&self, audio: Vec<u8>) -> Result<(), String> {
+        let (reply, result) = mpsc::channel();
+        self.send(PlayerCommand::Play(audio, reply))?;
+        result.recv().map_err(|_| "播放线程已退出".to_string())?
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        self.send(PlayerCommand::Stop)
+    }
+
+    pub fn pause(&self) -> Result<(), String> {
+        self.send(PlayerCommand::Pause)
+    }
+
+    pub fn resume(&self) -> Result<(), String> {
+        self.send(PlayerCommand::Resume)
+    }
+
+    pub fn state(&self) -> PlaybackState {
+        *self.state.lock().unwrap()
+    }
+
+    fn send(&self, command: PlayerCommand) -> Result<(), String> {
+        self.sender
+            .lock()
+            .unwrap()
+            .send(command)
+            .map_err(|_| "播放线程已退出".to_string())
+    }
+}
+
+fn run_player<F>(
+    receiver: Receiver<PlayerCommand>,
+    state: Arc<Mutex<PlaybackState>>,
+    on_state_changed: F,
+) where
+    F: Fn(PlaybackState),
+{
+    // 第一次播放时才打开音频设备
+    let mut output: Option<(OutputStream, OutputStreamHandle)> = None;
+    let mut sink: Option<Sink> = None;
+
+    let set_state = |new_state: PlaybackState| {
+        let mut current = state.lock().unwrap();
+        if *current != new_state {
+            *current = new_state;
+            on_state_changed(new_state);
+        }
+    };
+
+    loop {
+        let command = match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(command) => command,
+            Err(RecvTimeoutError::Timeout) => {
+                if sink.as_ref().is_some_and(Sink::empty) {
+                    sink = None;
+                    set_state(PlaybackState::Stopped);
+                }
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        match command {
+            PlayerCommand::Play(audio, reply) => {
+                // 丢弃旧的 Sink 即停止之前的音频
+                sink = None;
+                let result = play(&mut output, audio).map(|new_sink| sink = Some(new_sink));
+                set_state(if result.is_ok() {
+                    PlaybackState::Playing
+                } else {
+                    PlaybackState::Stopped
+                });
+                let _ = reply.send(result);
+            }
+            PlayerCommand::Stop => {
+                sink = None;
+                set_state(PlaybackState::Stopped);
+            }
+            PlayerCommand::Pause => {
+                if let Some(sink) = &sink {
+                    sink.pause();
+                    set_state(PlaybackState::Paused);
+                }
+            }
+            PlayerCommand::Resume => {
+                if let Some(sink) = &sink {
+                    sink.play();
+                    set_state(PlaybackState::Playing);
+                }
+            }
+        }
+    }
+}
+
+fn play(
+    output: &mut Option<(OutputStream, OutputStreamHandle)>,
+    audio: Vec<u8>,
+) -> Result<Sink, String> {
+    let source = Decoder::new(Cursor::new(audio)).map_err(|e| format!("无法解码音频: {}", e))?;
+    if output.is_none() {
+        *output =
+            Some(OutputStream::try_default().map_err(|e| format!("无法打开音频设备: {}", e))?);
+    }
+    let (_, handle) = output.as_ref().unwrap();
+    let sink = Sink::try_new(handle).map_err(|e| format!("无法播放音频: {}", e))?;
+    sink.append(source);
+    Ok(sink)
+}
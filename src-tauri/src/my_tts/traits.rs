@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+
+pub trait TtsEngine {
+    /// 引擎标识，作为缓存键的一部分：标识、声音和文本相同时音频相同
+    fn id(&self) -> String;
+
+    /// 该语言使用的声音，未配置时返回 None
+    fn voice_for(&self, language: &str) -> Option<String>;
+
+    /// 合成音频，返回 mp3 或 wav 等可直接解码的文件内容
+    fn synthesize<'a>(
+        &'a self,
+        text: &'a str,
+        voice: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, String>> + Send + 'a>>;
+}
+
+/// 按语言选择声音：先找完整的语言代码（"en-US"），再找主语言（"en"），最后使用默认声音
+pub fn resolve_voice(
+    voices: &BTreeMap<String, String>,
+    default_voice: &str,
+    language: &str,
+) -> Option<String> {
+    let primary = language.split('-').next().unwrap_or(language);
+    voices
+        .get(language)
+        .or_else(|| voices.get(primary))
+        .map(String::as_str)
+        .or(Some(default_voice))
+        .filter(|voice| !voice.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_voice() {
+        let voices = BTreeMap::from([
+            ("zh-CN".to_string(), "cmn".to_string()),
+            ("en".to_string(), "en-gb".to_string()),
+        ]);
+        assert_eq!(resolve_voice(&voices, "en-us", "zh-CN").unwrap(), "cmn");
+        assert_eq!(resolve_voice(&voices, "en-us", "en-US").unwrap(), "en-gb");
+        assert_eq!(resolve_voice(&voices, "en-us", "unknown").unwrap(), "en-us");
+        assert_eq!(resolve_voice(&voices, "", "unknown"), None);
+    }
+}
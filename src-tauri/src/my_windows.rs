@@ -5,13 +5,16 @@ use std::{
 };
 use tauri::Emitter;
 use tauri::{
-    window::Color, AppHandle, Listener, LogicalSize, Manager, PhysicalPosition, Runtime,
-    WebviewUrl, WebviewWindowBuilder,
+    window::Color, AppHandle, Listener, LogicalSize, Manager, PhysicalPosition, PhysicalSize,
+    Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
 };
 
+use crate::utils::bubble_placement::{self, Placement};
+use crate::utils::coordinates::{self, MonitorInfo, Rect};
+use crate::utils::selection_bounds;
+use crate::utils::window_geometry::{self, WindowGeometry};
 use crate::{my_events::event_names, states::setting_states};
-use mouse_position::mouse_position::{Mouse, Position};
-use tauri::Monitor;
+use mouse_position::mouse_position::Mouse;
 
 pub fn window_input_method_editor_show<R: Runtime>(app: &AppHandle<R>) {
     const WINDOW_WIDTH: f64 = 13.0;
@@ -24,41 +27,12 @@ pub fn window_input_method_editor_show<R: Runtime>(app: &AppHandle<R>) {
         let _ = window.set_min_size(Some(size));
         let _ = window.set_background_color(Some(Color(0, 0, 0, 0)));
 
-        let (logical_x, logical_y) =
-            calculate_window_position(app, WINDOW_WIDTH, WINDOW_HEIGHT, CURSOR_OFFSET);
-
-        let mut target_scale = 1.0;
-
-        if let Ok(monitors) = window.available_monitors() {
-            for monitor in monitors {
-                let pos = monitor.position();
-                let size_mon = monitor.size();
-                let scale = monitor.scale_factor();
-
-                let mon_x = pos.x as f64 / scale;
-                let mon_y = pos.y as f64 / scale;
-                let mon_w = size_mon.width as f64 / scale;
-                let mon_h = size_mon.height as f64 / scale;
-
-                if logical_x >= mon_x
-                    && logical_x < mon_x + mon_w
-                    && logical_y >= mon_y
-                    && logical_y < mon_y + mon_h
-                {
-                    target_scale = scale;
-                    break;
-                }
-            }
+        if let Some(position) =
+            calculate_window_position(app, WINDOW_WIDTH, WINDOW_HEIGHT, CURSOR_OFFSET)
+        {
+            let _ = window.set_position(tauri::Position::Physical(position));
         }
 
-        let physical_x = (logical_x * target_scale) as i32;
-        let physical_y = (logical_y * target_scale) as i32;
-
-        let _ = window.set_position(tauri::Position::Physical(PhysicalPosition {
-            x: physical_x,
-            y: physical_y,
-        }));
-
         let _ = window.show();
         let _ = window.set_always_on_top(true);
     }
@@ -71,12 +45,19 @@ pub fn window_input_method_editor_hide<R: Runtime>(app: &AppHandle<R>) {
 }
 
 pub const WINDOW_HEIGHT_TRANSLATE_BUBBLE: f64 = 36.0;
+/// 多行译文时气泡的最大高度
+pub const WINDOW_MAX_HEIGHT_TRANSLATE_BUBBLE: f64 = 400.0;
+/// 气泡与锚点之间的间隔
+const BUBBLE_ANCHOR_GAP: f64 = 17.0;
+
+/// 当前气泡的锚点（物理像素）和方向，气泡大小变化后据此重新定位
+static BUBBLE_ANCHOR: Mutex<Option<(Rect, Placement)>> = Mutex::new(None);
+
 pub fn window_translate_bubble_show<R: Runtime, F>(app: &AppHandle<R>, callback: Option<F>)
 where
     F: FnOnce() + Send + 'static,
 {
-    const WINDOW_WIDTH: f64 = 170.0;
-    const CURSOR_OFFSET: f64 = 17.0;
+    const WINDOW_WIDTH: f64 = 226.0;
 
     if let Some(window) = app.get_webview_window("translate_bubble") {
         let size = LogicalSize::new(WINDOW_WIDTH, WINDOW_HEIGHT_TRANSLATE_BUBBLE);
@@ -84,48 +65,18 @@ where
         let _ = window.set_min_size(Some(size));
         let _ = window.set_max_size(Some(LogicalSize::new(
             10_000.0,
-            WINDOW_HEIGHT_TRANSLATE_BUBBLE,
+            WINDOW_MAX_HEIGHT_TRANSLATE_BUBBLE,
         )));
         let _ = window.set_background_color(Some(Color(0, 0, 0, 0)));
 
-        let (logical_x, logical_y) = calculate_window_position(
-            app,
-            WINDOW_WIDTH,
-            WINDOW_HEIGHT_TRANSLATE_BUBBLE,
-            CURSOR_OFFSET,
-        );
-
-        let mut target_scale = 1.0;
-
-        if let Ok(monitors) = window.available_monitors() {
-            for monitor in monitors {
-                let pos = monitor.position();
-                let size_mon = monitor.size();
-                let scale = monitor.scale_factor();
-
-                let mon_x = pos.x as f64 / scale;
-                let mon_y = pos.y as f64 / scale;
-                let mon_w = size_mon.width as f64 / scale;
-                let mon_h = size_mon.height as f64 / scale;
-
-                if logical_x >= mon_x
-                    && logical_x < mon_x + mon_w
-                    && logical_y >= mon_y
-                    && logical_y < mon_y + mon_h
-                {
-                    target_scale = scale;
-                    break;
-                }
-            }
-        }
-
-        let physical_x = (logical_x * target_scale) as i32;
-        let physical_y = (logical_y * target_scale) as i32;
-
-        let _ = window.set_position(tauri::Position::Physical(PhysicalPosition {
-            x: physical_x,
-            y: physical_y,
-        }));
+        // 优先以选区为锚点，避免遮住选中的文本；读取不到时退回鼠标位置
+        let monitors = monitor_infos(app);
+        let anchor = selection_bounds::selection_bounds(&monitors).unwrap_or_else(|| {
+            let (x, y) = cursor_position(&monitors).unwrap_or_default();
+            Rect::point(x, y)
+        });
+        *BUBBLE_ANCHOR.lock().unwrap() = None;
+        place_translate_bubble(app, &window, anchor, size, None);
 
         let _ = window.show();
         let _ = window.set_always_on_top(true);
@@ -136,6 +87,74 @@ where
     }
 }
 
+/// 调整气泡大小，并按新的大小重新选择位置，避免超出屏幕
+pub fn resize_translate_bubble<R: Runtime>(app: &AppHandle<R>, size: LogicalSize<f64>) {
+    if let Some(window) = app.get_webview_window("translate_bubble") {
+        let _ = window.set_size(size);
+        let anchor = *BUBBLE_ANCHOR.lock().unwrap();
+        if let Some((anchor, placement)) = anchor {
+            place_translate_bubble(app, &window, anchor, size, Some(placement));
+        }
+    }
+}
+
+fn place_translate_bubble<R: Runtime>(
+    app: &AppHandle<R>,
+    window: &WebviewWindow<R>,
+    anchor: Rect,
+    size: LogicalSize<f64>,
+    preferred: Option<Placement>,
+) {
+    let monitors = monitor_infos(app);
+    if let Some(result) = bubble_placement::place_bubble(
+        &monitors,
+        anchor,
+        size.width,
+        size.height,
+        BUBBLE_ANCHOR_GAP,
+        preferred,
+    ) {
+        let _ = window.set_position(tauri::Position::Physical(PhysicalPosition {
+            x: result.x,
+            y: result.y,
+        }));
+        *BUBBLE_ANCHOR.lock().unwrap() = Some((anchor, result.placement));
+    }
+}
+
+fn monitor_infos<R: Runtime>(app: &AppHandle<R>) -> Vec<MonitorInfo> {
+    let monitors: Vec<MonitorInfo> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorInfo::from)
+        .collect();
+    if !monitors.is_empty() {
+        return monitors;
+    }
+    app.primary_monitor()
+        .ok()
+        .flatten()
+        .iter()
+        .map(MonitorInfo::from)
+        .collect()
+}
+
+/// 鼠标位置（物理像素），限制在虚拟桌面范围内
+fn cursor_position(monitors: &[MonitorInfo]) -> Option<(f64, f64)> {
+    let (x, y) = match Mouse::get_mouse_position() {
+        Mouse::Position { x, y } => (x as f64, y as f64),
+        Mouse::Error => return None,
+    };
+    match coordinates::virtual_desktop_bounds(monitors) {
+        Some(desktop) => Some((
+            x.clamp(desktop.x, desktop.right() - 1.0),
+            y.clamp(desktop.y, desktop.bottom() - 1.0),
+        )),
+        None => Some((x, y)),
+    }
+}
+
 pub fn window_translate_show<R: Runtime, F>(app: &AppHandle<R>, callback: Option<F>)
 where
     F: FnOnce() + Send + 'static,
@@ -157,8 +176,15 @@ where
         const WINDOW_HEIGHT: f64 = 600.0;
         const CURSOR_OFFSET: f64 = 10.0;
 
+        // 固定模式下恢复窗口在鼠标所在显示器上最后的位置和大小
+        let restored = if is_translate_pinned(app) {
+            monitor_at_cursor(app).and_then(|(monitor, _)| window_geometry::load(app, &monitor))
+        } else {
+            None
+        };
+
         // Use centered position if callback is None, otherwise use mouse-based positioning
-        let (adjusted_x, adjusted_y) = if callback.is_none() {
+        let position = if callback.is_none() {
             calculate_center_position(app, WINDOW_WIDTH, WINDOW_HEIGHT)
         } else {
             calculate_window_position(app, WINDOW_WIDTH, WINDOW_HEIGHT, CURSOR_OFFSET)
@@ -174,7 +200,7 @@ where
                 .min_inner_size(350.0, 600.0)
                 .background_color(Color(0, 0, 0, 1))
                 .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
-                .position(adjusted_x, adjusted_y);
+                .visible(false);
 
         #[cfg(target_os = "macos")]
         {
@@ -188,6 +214,25 @@ where
         }
 
         let _ = builder.build().and_then(|window| {
+            // 逻辑坐标会按窗口创建时所在显示器的缩放比例换算，跨显示器时需直接设置物理坐标
+            if let Some(geometry) = restored {
+                window
+                    .set_size(tauri::Size::Physical(PhysicalSize {
+                        width: geometry.width,
+                        height: geometry.height,
+                    }))
+                    .ok();
+                window
+                    .set_position(tauri::Position::Physical(PhysicalPosition {
+                        x: geometry.x,
+                        y: geometry.y,
+                    }))
+                    .ok();
+            } else if let Some(position) = position {
+                window
+                    .set_position(tauri::Position::Physical(position))
+                    .ok();
+            }
             window.show().ok();
             window.set_focus().ok();
             let callback_for_listener = Arc::new(Mutex::new(callback)).clone();
@@ -205,8 +250,26 @@ where
             let cancelled = Arc::new(Mutex::new(false));
             let win_clone = window.clone();
             let cancel_flag = cancelled.clone();
+            // 最近一次移动或缩放后的位置和大小，窗口销毁后无法再读取
+            let geometry: Arc<Mutex<Option<WindowGeometry>>> = Arc::new(Mutex::new(None));
+            let track_geometry = {
+                let window = window.clone();
+                let geometry = geometry.clone();
+                move || {
+                    if let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size())
+                    {
+                        *geometry.lock().unwrap() = Some(WindowGeometry {
+                            x: position.x,
+                            y: position.y,
+                            width: size.width,
+                            height: size.height,
+                        });
+                    }
+                }
+            };
             window.on_window_event(move |event| match event {
                 tauri::WindowEvent::Focused(false) => {
+                    save_translate_geometry(&state_handle, *geometry.lock().unwrap());
                     *cancel_flag.lock().unwrap() = false;
                     let _win = win_clone.clone();
                     let local_cancel = cancel_flag.clone();
@@ -219,7 +282,7 @@ where
                         if {
                             let state = state_handle.state::<Mutex<setting_states::AppState>>();
                             let state_guard = state.lock().unwrap();
-                            !state_guard.auto_close_translate
+                            !state_guard.pinned
                         } {
                             _win.destroy().ok();
                         }
@@ -230,6 +293,11 @@ where
                 }
                 tauri::WindowEvent::Moved(_) => {
                     *cancelled.lock().unwrap() = true;
+                    track_geometry();
+                }
+                tauri::WindowEvent::Resized(_) => track_geometry(),
+                tauri::WindowEvent::Destroyed => {
+                    save_translate_geometry(&state_handle, *geometry.lock().unwrap());
                 }
                 _ => {}
             });
@@ -238,6 +306,37 @@ where
     }
 }
 
+/// 翻译窗口是否正在显示，已创建但被隐藏或最小化时不算
+pub fn is_translate_window_visible<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.get_webview_window("translate").is_some_and(|window| {
+        window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false)
+    })
+}
+
+fn is_translate_pinned<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.state::<Mutex<setting_states::AppState>>()
+        .lock()
+        .unwrap()
+        .pinned
+}
+
+/// 固定模式下记录翻译窗口在所在显示器上的位置和大小
+fn save_translate_geometry<R: Runtime>(app: &AppHandle<R>, geometry: Option<WindowGeometry>) {
+    let Some(geometry) = geometry else {
+        return;
+    };
+    if !is_translate_pinned(app) {
+        return;
+    }
+    let monitors = monitor_infos(app);
+    let (x, y) = geometry.center();
+    if let Some(monitor) = coordinates::monitor_at(&monitors, x, y) {
+        if let Err(e) = window_geometry::save(app, monitor, geometry) {
+            eprintln!("保存翻译窗口位置失败: {}", e);
+        }
+    }
+}
+
 pub fn window_about_show<R: Runtime>(app: &AppHandle<R>) {
     if let Some(window) = app.get_webview_window("about") {
         let _ = window.show();
@@ -250,149 +349,42 @@ pub fn window_about_show<R: Runtime>(app: &AppHandle<R>) {
     }
 }
 
-fn get_monitor_at_position<R: Runtime>(app: &AppHandle<R>, x: i32, y: i32) -> Option<Monitor> {
-    if let Ok(monitors) = app.available_monitors() {
-        for monitor in monitors {
-            let size = monitor.size();
-            let position = monitor.position();
-
-            if x >= position.x
-                && x < position.x + size.width as i32
-                && y >= position.y
-                && y < position.y + size.height as i32
-            {
-                return Some(monitor);
-            }
-        }
+/// 鼠标所在的显示器，获取不到鼠标位置时使用主显示器
+fn monitor_at_cursor<R: Runtime>(app: &AppHandle<R>) -> Option<(MonitorInfo, Option<(f64, f64)>)> {
+    let monitors = monitor_infos(app);
+    match cursor_position(&monitors) {
+        Some((x, y)) => coordinates::monitor_at(&monitors, x, y).map(|m| (*m, Some((x, y)))),
+        None => app
+            .primary_monitor()
+            .ok()
+            .flatten()
+            .map(|monitor| (MonitorInfo::from(&monitor), None)),
     }
-
-    app.primary_monitor().ok().flatten()
 }
 
+/// 在鼠标所在显示器的工作区居中
 fn calculate_center_position<R: Runtime>(
     app: &AppHandle<R>,
     width: f64,
     height: f64,
-) -> (f64, f64) {
-    // Get the primary monitor
-    if let Ok(Some(primary_monitor)) = app.primary_monitor() {
-        let scale_factor = primary_monitor.scale_factor();
-
-        // Convert physical dimensions to logical dimensions
-        let monitor_position = primary_monitor.position();
-        let monitor_size = primary_monitor.size();
-
-        let monitor_x = monitor_position.x as f64 / scale_factor;
-        let monitor_y = monitor_position.y as f64 / scale_factor;
-        let monitor_width = monitor_size.width as f64 / scale_factor;
-        let monitor_height = monitor_size.height as f64 / scale_factor;
-
-        // Calculate centered position (with monitor offset)
-        let x = monitor_x + (monitor_width - width) / 2.0;
-        let y = monitor_y + (monitor_height - height) / 2.0;
-
-        (x, y)
-    } else {
-        // Fallback to (0, 0) if primary monitor cannot be determined
-        (0.0, 0.0)
-    }
+) -> Option<PhysicalPosition<i32>> {
+    let (monitor, _) = monitor_at_cursor(app)?;
+    let (x, y) = coordinates::center_in_work_area(&monitor, width, height);
+    Some(PhysicalPosition { x, y })
 }
 
+/// 在鼠标附近显示窗口，`width`、`height`、`cursor_offset` 为逻辑像素
 fn calculate_window_position<R: Runtime>(
     app: &AppHandle<R>,
     width: f64,
     height: f64,
     cursor_offset: f64,
-) -> (f64, f64) {
-    let mouse_position = match Mouse::get_mouse_position() {
-        Mouse::Position { x, y } => Position { x, y },
-        Mouse::Error => Position { x: 0, y: 0 },
-    };
-
-    match get_monitor_at_position(app, mouse_position.x, mouse_position.y) {
-        Some(monitor) => {
-            let scale_factor = monitor.scale_factor();
-
-            // 辅助函数：物理像素转逻辑像素
-            let to_logical = |value: i32| value as f64 / scale_factor;
-            let to_logical_f = |value: u32| value as f64 / scale_factor;
-
-            // 鼠标位置（逻辑像素）
-            let mouse_x = to_logical(mouse_position.x);
-            let mouse_y = to_logical(mouse_position.y);
-
-            // 显示器边界（逻辑像素）
-            let monitor_x = to_logical(monitor.position().x);
-            let monitor_y = to_logical(monitor.position().y);
-            let monitor_width = to_logical_f(monitor.size().width);
-            let monitor_height = to_logical_f(monitor.size().height);
-            let monitor_right = monitor_x + monitor_width;
-            let monitor_bottom = monitor_y + monitor_height;
-
-            // 鼠标在显示器中的相对位置 (0.0 ~ 1.0)
-            let relative_x = (mouse_x - monitor_x) / monitor_width;
-            let relative_y = (mouse_y - monitor_y) / monitor_height;
-
-            // 智能X轴定位：鼠标在左半边时窗口显示在右边，反之亦然
-            let x = if relative_x < 0.5 {
-                // 鼠标在左半边，尝试放在右边
-                let right_pos = mouse_x + cursor_offset;
-                if right_pos + width <= monitor_right {
-                    right_pos
-                } else {
-                    // 右边放不下，放在左边
-                    (mouse_x - width - cursor_offset).max(monitor_x)
-                }
-            } else {
-                // 鼠标在右半边，尝试放在左边
-                let left_pos = mouse_x - width - cursor_offset;
-                if left_pos >= monitor_x {
-                    left_pos
-                } else {
-                    // 左边放不下，放在右边
-                    (mouse_x + cursor_offset).min(monitor_right - width)
-                }
-            };
-
-            // 智能Y轴定位：鼠标在上半边时窗口显示在下边，反之亦然
-            let y = if relative_y < 0.5 {
-                // 鼠标在上半边，尝试放在下边
-                let bottom_pos = mouse_y + cursor_offset;
-                if bottom_pos + height <= monitor_bottom {
-                    bottom_pos
-                } else {
-                    // 下边放不下，放在上边
-                    (mouse_y - height - cursor_offset).max(monitor_y)
-                }
-            } else {
-                // 鼠标在下半边，尝试放在上边
-                let top_pos = mouse_y - height - cursor_offset;
-                if top_pos >= monitor_y {
-                    top_pos
-                } else {
-                    // 上边放不下，放在下边
-                    (mouse_y + cursor_offset).min(monitor_bottom - height)
-                }
-            };
-
-            // 最终边界夹紧（防御性编程）
-            let x = x.clamp(monitor_x, monitor_right - width);
-            let y = y.clamp(monitor_y, monitor_bottom - height);
-
-            (x, y)
+) -> Option<PhysicalPosition<i32>> {
+    let (x, y) = match monitor_at_cursor(app)? {
+        (monitor, Some((x, y))) => {
+            coordinates::position_near_point(&monitor, x, y, width, height, cursor_offset)
         }
-        None => {
-            // 没有找到对应的显示器，使用默认逻辑
-            // 优化：尝试获取主显示器
-            if let Ok(Some(monitor)) = app.primary_monitor() {
-                let scale = monitor.scale_factor();
-                let logical_x = mouse_position.x as f64 / scale;
-                let logical_y = mouse_position.y as f64 / scale;
-                (logical_x, logical_y)
-            } else {
-                // 最后的回退：使用物理像素（可能在高DPI屏幕上不准确）
-                (mouse_position.x as f64, mouse_position.y as f64)
-            }
-        }
-    }
+        (monitor, None) => coordinates::center_in_work_area(&monitor, width, height),
+    };
+    Some(PhysicalPosition { x, y })
 }
@@ -1,4 +1,4 @@
-use crate::utils::chat_message::{ChatMessage, ChatMessageHistory};
+use crate::utils::chat_message::{ChatMessage, ChatMessageHistory, MessageContent};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::sync::Arc;
@@ -20,7 +20,12 @@ impl ChatHistoriesState {
     }
 
     /// Add a system message to a specific chat history
-    pub async fn add_system_message(&self, key: &str, content: String, raw: Option<String>) {
+    pub async fn add_system_message(
+        &self,
+        key: &str,
+        content: impl Into<MessageContent>,
+        raw: Option<String>,
+    ) {
         let mut state = self.0.write().await;
         state
             .histories
@@ -30,7 +35,12 @@ impl ChatHistoriesState {
     }
 
     /// Add a user message to a specific chat history
-    pub async fn add_user_message(&self, key: &str, content: String, raw: Option<String>) {
+    pub async fn add_user_message(
+        &self,
+        key: &str,
+        content: impl Into<MessageContent>,
+        raw: Option<String>,
+    ) {
         let mut state = self.0.write().await;
         state
             .histories
@@ -40,7 +50,12 @@ impl ChatHistoriesState {
     }
 
     /// Add an assistant message to a specific chat history
-    pub async fn add_assistant_message(&self, key: &str, content: String, raw: Option<String>) {
+    pub async fn add_assistant_message(
+        &self,
+        key: &str,
+        content: impl Into<MessageContent>,
+        raw: Option<String>,
+    ) {
         let mut state = self.0.write().await;
         state
             .histories
@@ -49,6 +64,26 @@ impl ChatHistoriesState {
             .add_assistant_message(content, raw);
     }
 
+    /// 添加完整的消息，如带工具调用的助手消息和工具结果
+    pub async fn push_message(&self, key: &str, message: ChatMessage) {
+        let mut state = self.0.write().await;
+        state
+            .histories
+            .entry(key.to_string())
+            .or_insert_with(ChatMessageHistory::new)
+            .push(message);
+    }
+
+    /// 记录会话对应的截图
+    pub async fn set_image(&self, key: &str, image: String) {
+        let mut state = self.0.write().await;
+        state
+            .histories
+            .entry(key.to_string())
+            .or_insert_with(ChatMessageHistory::new)
+            .image = Some(image);
+    }
+
     /// Get messages from a specific chat history
     pub async fn get_messages(&self, key: &str) -> Option<Vec<ChatMessage>> {
         let state = self.0.read().await;
@@ -1,7 +1,12 @@
-use serde::Serialize;
+use crate::my_config;
+use crate::my_events::event_names;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 
 // Enum for auto speak state - three possible states
-#[derive(Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AutoSpeakState {
     Off, // Completely off
     #[default]
@@ -9,7 +14,7 @@ pub enum AutoSpeakState {
     All, // Read full sentence
 }
 
-// Implement Display and Serialize for AutoSpeakState to return string values
+// Implement Display for AutoSpeakState to return string values
 impl std::fmt::Display for AutoSpeakState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -20,20 +25,49 @@ impl std::fmt::Display for AutoSpeakState {
     }
 }
 
-impl Serialize for AutoSpeakState {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
-    }
-}
-
-// Global state struct for auto-close window setting
-#[derive(Default)]
+/// 用户设置，保存在全局配置的 `settings` 中，运行时缓存在 `Mutex<AppState>` 里
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppState {
-    pub auto_close_translate: bool,
+    /// 翻译窗口固定模式：失焦不关闭，按显示器记住位置和大小，新的结果追加显示
+    pub pinned: bool,
     #[allow(dead_code)]
     pub auto_close_bubble: bool,
     pub auto_speak: AutoSpeakState,
 }
+
+/// 启动时从配置加载设置
+pub fn load_settings<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::error::Error>> {
+    let settings = my_config::get_global_config(app)?.settings;
+    *app.state::<Mutex<AppState>>().lock().unwrap() = settings;
+    Ok(())
+}
+
+pub fn get_settings<R: Runtime>(app: &AppHandle<R>) -> AppState {
+    app.state::<Mutex<AppState>>().lock().unwrap().clone()
+}
+
+/// 修改设置，保存到配置并通知所有窗口
+pub fn update_settings<R, F>(app: &AppHandle<R>, update: F) -> Result<AppState, String>
+where
+    R: Runtime,
+    F: FnOnce(&mut AppState),
+{
+    let settings = {
+        let state = app.state::<Mutex<AppState>>();
+        let mut state_guard = state.lock().unwrap();
+        update(&mut state_guard);
+        state_guard.clone()
+    };
+
+    let mut global_config =
+        my_config::get_global_config(app).map_err(|e| format!("获取配置失败: {}", e))?;
+    if global_config.settings != settings {
+        global_config.settings = settings.clone();
+        my_config::set_global_config(app, &global_config)
+            .map_err(|e| format!("保存配置失败: {}", e))?;
+    }
+
+    let _ = app.emit(event_names::SETTINGS_CHANGED, &settings);
+    Ok(settings)
+}
@@ -0,0 +1,322 @@
+//! 气泡定位：根据锚点（选区矩形或鼠标位置）、气泡大小和显示器工作区，
+//! 选择在锚点下方、上方、右侧或左侧显示，并保证气泡不超出工作区
+
+use crate::utils::coordinates::{clamp_span, monitor_at, MonitorInfo, Rect};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    Below,
+    Above,
+    Right,
+    Left,
+}
+
+/// 没有指定偏好时的尝试顺序
+const DEFAULT_ORDER: [Placement; 4] = [
+    Placement::Below,
+    Placement::Above,
+    Placement::Right,
+    Placement::Left,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacementResult {
+    pub placement: Placement,
+    /// 窗口左上角，物理像素
+    pub x: i32,
+    pub y: i32,
+    /// 所在显示器的缩放比例
+    pub scale_factor: f64,
+}
+
+/// 在主轴方向上锚点该侧的可用空间
+fn space_for(placement: Placement, anchor: &Rect, area: &Rect, gap: f64) -> f64 {
+    match placement {
+        Placement::Below => area.bottom() - anchor.bottom() - gap,
+        Placement::Above => anchor.y - area.y - gap,
+        Placement::Right => area.right() - anchor.right() - gap,
+        Placement::Left => anchor.x - area.x - gap,
+    }
+}
+
+/// 计算某一方向上的位置（逻辑像素），交叉轴方向限制在工作区内
+fn position_for(
+    placement: Placement,
+    anchor: &Rect,
+    area: &Rect,
+    width: f64,
+    height: f64,
+    gap: f64,
+) -> (f64, f64) {
+    let (x, y) = match placement {
+        Placement::Below => (anchor.x, anchor.bottom() + gap),
+        Placement::Above => (anchor.x, anchor.y - gap - height),
+        Placement::Right => (anchor.right() + gap, anchor.y),
+        Placement::Left => (anchor.x - gap - width, anchor.y),
+    };
+    (
+        clamp_span(x, width, area.x, area.right()),
+        clamp_span(y, height, area.y, area.bottom()),
+    )
+}
+
+/// 选择气泡位置
+///
+/// `anchor` 为物理像素，`width`、`height`、`gap` 为逻辑像素。
+/// `preferred` 通常是上一次的方向：调整大小后仍放得下就保持不变，避免气泡跳动。
+pub fn place_bubble(
+    monitors: &[MonitorInfo],
+    anchor: Rect,
+    width: f64,
+    height: f64,
+    gap: f64,
+    preferred: Option<Placement>,
+) -> Option<PlacementResult> {
+    let (cx, cy) = anchor.center();
+    let monitor = monitor_at(monitors, cx, cy)?;
+    let area = monitor.logical_work_area();
+    let anchor = monitor.to_logical_rect(&anchor);
+
+    let needed = |placement: Placement| match placement {
+        Placement::Below | Placement::Above => height,
+        Placement::Right | Placement::Left => width,
+    };
+
+    let placement = preferred
+        .into_iter()
+        .chain(DEFAULT_ORDER)
+        .find(|&p| space_for(p, &anchor, &area, gap) >= needed(p))
+        .unwrap_or_else(|| {
+            // 都放不下时选剩余空间最多的一侧
+            DEFAULT_ORDER
+                .into_iter()
+                .max_by(|&a, &b| {
+                    let slack_a = space_for(a, &anchor, &area, gap) - needed(a);
+                    let slack_b = space_for(b, &anchor, &area, gap) - needed(b);
+                    slack_a.total_cmp(&slack_b)
+                })
+                .unwrap()
+        });
+
+    let (x, y) = position_for(placement, &anchor, &area, width, height, gap);
+    let (x, y) = monitor.to_physical_position(x, y);
+    Some(PlacementResult {
+        placement,
+        x,
+        y,
+        scale_factor: monitor.scale_factor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1920x1080，底部 40px 任务栏
+    fn single_monitor() -> Vec<MonitorInfo> {
+        vec![MonitorInfo {
+            bounds: Rect::new(0.0, 0.0, 1920.0, 1080.0),
+            work_area: Rect::new(0.0, 0.0, 1920.0, 1040.0),
+            scale_factor: 1.0,
+        }]
+    }
+
+    /// 左侧 1x 显示器，右侧 2x 显示器（物理 3840x2160，逻辑 1920x1080），
+    /// 以及位于主屏上方、坐标为负的 1.5x 显示器
+    fn mixed_dpi_monitors() -> Vec<MonitorInfo> {
+        vec![
+            MonitorInfo {
+                bounds: Rect::new(0.0, 0.0, 1920.0, 1080.0),
+                work_area: Rect::new(0.0, 0.0, 1920.0, 1040.0),
+                scale_factor: 1.0,
+            },
+            MonitorInfo {
+                bounds: Rect::new(1920.0, 0.0, 3840.0, 2160.0),
+                work_area: Rect::new(1920.0, 50.0, 3840.0, 2110.0),
+                scale_factor: 2.0,
+            },
+            MonitorInfo {
+                bounds: Rect::new(0.0, -1620.0, 2880.0, 1620.0),
+                work_area: Rect::new(0.0, -1620.0, 2880.0, 1620.0),
+                scale_factor: 1.5,
+            },
+        ]
+    }
+
+    fn place(
+        monitors: &[MonitorInfo],
+        anchor: Rect,
+        width: f64,
+        height: f64,
+        preferred: Option<Placement>,
+    ) -> PlacementResult {
+        place_bubble(monitors, anchor, width, height, 10.0, preferred).unwrap()
+    }
+
+    #[test]
+    fn test_below_by_default() {
+        let result = place(
+            &single_monitor(),
+            Rect::point(100.0, 100.0),
+            200.0,
+            36.0,
+            None,
+        );
+        assert_eq!(result.placement, Placement::Below);
+        assert_eq!((result.x, result.y), (100, 110));
+    }
+
+    #[test]
+    fn test_above_when_no_room_below_taskbar() {
+        // 鼠标在任务栏上方不远处，下方放不下
+        let result = place(
+            &single_monitor(),
+            Rect::point(500.0, 1020.0),
+            200.0,
+            36.0,
+            None,
+        );
+        assert_eq!(result.placement, Placement::Above);
+        assert_eq!((result.x, result.y), (500, 974));
+    }
+
+    #[test]
+    fn test_clamped_to_right_edge() {
+        let result = place(
+            &single_monitor(),
+            Rect::point(1900.0, 100.0),
+            300.0,
+            36.0,
+            None,
+        );
+        assert_eq!(result.placement, Placement::Below);
+        assert_eq!((result.x, result.y), (1620, 110));
+    }
+
+    #[test]
+    fn test_selection_rect_anchor() {
+        let selection = Rect::new(300.0, 400.0, 500.0, 40.0);
+        let result = place(&single_monitor(), selection, 200.0, 36.0, None);
+        assert_eq!((result.x, result.y), (300, 450));
+    }
+
+    #[test]
+    fn test_side_placement_when_too_tall() {
+        let result = place(
+            &single_monitor(),
+            Rect::point(100.0, 500.0),
+            300.0,
+            600.0,
+            None,
+        );
+        assert_eq!(result.placement, Placement::Right);
+        assert_eq!((result.x, result.y), (110, 440));
+    }
+
+    #[test]
+    fn test_keeps_preferred_after_resize() {
+        let anchor = Rect::point(500.0, 600.0);
+        let first = place(
+            &single_monitor(),
+            anchor,
+            200.0,
+            36.0,
+            Some(Placement::Above),
+        );
+        assert_eq!(first.placement, Placement::Above);
+
+        // 变高后上方仍放得下，保持在上方
+        let resized = place(
+            &single_monitor(),
+            anchor,
+            400.0,
+            300.0,
+            Some(first.placement),
+        );
+        assert_eq!(resized.placement, Placement::Above);
+        assert_eq!((resized.x, resized.y), (500, 290));
+    }
+
+    #[test]
+    fn test_flips_after_resize() {
+        let anchor = Rect::point(500.0, 800.0);
+        let first = place(&single_monitor(), anchor, 200.0, 36.0, None);
+        assert_eq!(first.placement, Placement::Below);
+
+        // 变高后下方放不下，改为上方
+        let resized = place(
+            &single_monitor(),
+            anchor,
+            400.0,
+            300.0,
+            Some(first.placement),
+        );
+        assert_eq!(resized.placement, Placement::Above);
+        assert_eq!((resized.x, resized.y), (500, 490));
+    }
+
+    #[test]
+    fn test_high_dpi_monitor() {
+        // 物理坐标 (2920, 2100) 在 2x 显示器上，逻辑 (2420, 1050)，下方放不下
+        let result = place(
+            &mixed_dpi_monitors(),
+            Rect::point(2920.0, 2100.0),
+            200.0,
+            36.0,
+            None,
+        );
+        assert_eq!(result.scale_factor, 2.0);
+        assert_eq!(result.placement, Placement::Above);
+        assert_eq!((result.x, result.y), (2920, 2008));
+    }
+
+    #[test]
+    fn test_high_dpi_right_edge() {
+        // 2x 显示器逻辑右边界 3840，气泡 300 宽，左上角最多在 3540（物理 5160）
+        let result = place(
+            &mixed_dpi_monitors(),
+            Rect::point(5700.0, 200.0),
+            300.0,
+            36.0,
+            None,
+        );
+        assert_eq!(result.placement, Placement::Below);
+        assert_eq!((result.x, result.y), (5160, 220));
+    }
+
+    #[test]
+    fn test_negative_coordinates_monitor() {
+        let result = place(
+            &mixed_dpi_monitors(),
+            Rect::point(300.0, -300.0),
+            200.0,
+            36.0,
+            None,
+        );
+        assert_eq!(result.scale_factor, 1.5);
+        // 下方只剩 190 逻辑像素 - 10 间隔，放得下
+        assert_eq!(result.placement, Placement::Below);
+        assert_eq!((result.x, result.y), (300, -285));
+    }
+
+    #[test]
+    fn test_anchor_outside_all_monitors_uses_nearest() {
+        let result = place(
+            &single_monitor(),
+            Rect::point(2500.0, 100.0),
+            200.0,
+            36.0,
+            None,
+        );
+        assert_eq!(result.scale_factor, 1.0);
+        assert_eq!((result.x, result.y), (1720, 110));
+    }
+
+    #[test]
+    fn test_no_monitors() {
+        assert_eq!(
+            place_bubble(&[], Rect::point(0.0, 0.0), 100.0, 36.0, 10.0, None),
+            None
+        );
+    }
+}
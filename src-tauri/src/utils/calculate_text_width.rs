@@ -1,117 +1,44 @@
-use tauri::LogicalSize;
+use tauri::{AppHandle, LogicalSize, Manager};
 
 use crate::my_windows;
+use crate::utils::text_layout;
 
-// 字体配置常量
-const FONT_SIZE: f64 = 14.0; // 实际字体大小 14px
+/// 气泡中拖动把手和按钮占用的宽度
+const HORIZONTAL_PADDING: f64 = 229.0;
+/// 气泡上下边框和内边距
+const VERTICAL_PADDING: f64 = 16.0;
+const MIN_WIDTH: f64 = 150.0;
+const MAX_WIDTH: f64 = 800.0;
 
+/// 随应用打包的气泡字体（见 tauri.conf.json 的 bundle.resources），缺失时按字符类别估算宽度
+const BUBBLE_FONT_RESOURCE: &str = "fonts/DejaVuSans.ttf";
 
-// 调整后的字符宽度系数
-const CJK_WIDTH_RATIO: f64 = 1.0;      // 中文等宽字符
-const ASCII_WIDE_RATIO: f64 = 0.7;     // 宽字母 (W, M, w, m) - 提高到0.7
-const ASCII_NORMAL_RATIO: f64 = 0.55;  // 普通ASCII字符 - 提高到0.55
-const ASCII_NARROW_RATIO: f64 = 0.35;  // 窄字符 - 提高到0.35
-const SPACE_RATIO: f64 = 0.4;          // **单独定义空格宽度**
-const TAB_WIDTH_RATIO: f64 = 2.0;
-
-pub fn calculate_text_width(content: &str) -> LogicalSize<f64> {
-    let mut total_width: f64 = 0.0;
-    
-    for c in content.chars() {
-        let char_width = match c {
-            // 中文字符（CJK统一表意文字）
-            '\u{4E00}'..='\u{9FFF}' |  // 基本汉字
-            '\u{3400}'..='\u{4DBF}' |  // 扩展A
-            '\u{20000}'..='\u{2A6DF}' | // 扩展B
-            '\u{2A700}'..='\u{2B73F}' | // 扩展C
-            '\u{2B740}'..='\u{2B81F}' | // 扩展D
-            '\u{2B820}'..='\u{2CEAF}' | // 扩展E
-            '\u{F900}'..='\u{FAFF}' |   // 兼容汉字
-            '\u{2F800}'..='\u{2FA1F}'   // 兼容补充
-            => FONT_SIZE * CJK_WIDTH_RATIO,
-            
-            // 全角字符（包括全角标点、日文假名等）
-            '\u{FF01}'..='\u{FF5E}' |  // 全角ASCII
-            '\u{3000}'..='\u{303F}' |  // CJK标点
-            '\u{3040}'..='\u{309F}' |  // 平假名
-            '\u{30A0}'..='\u{30FF}'    // 片假名
-            => FONT_SIZE * CJK_WIDTH_RATIO,
-            
-            // 韩文字符
-            '\u{AC00}'..='\u{D7AF}' |  // 韩文音节
-            '\u{1100}'..='\u{11FF}'    // 韩文字母
-            => FONT_SIZE * CJK_WIDTH_RATIO,
-            
-            // 表情符号和特殊符号
-            '\u{1F300}'..='\u{1F9FF}' | // 表情符号
-            '\u{2600}'..='\u{26FF}' |   // 杂项符号
-            '\u{2700}'..='\u{27BF}'     // 装饰符号
-            => FONT_SIZE * CJK_WIDTH_RATIO,
-            
-            // 制表符
-            '\t' => FONT_SIZE * TAB_WIDTH_RATIO,
-            
-            // 换行符（视为普通空格）
-            '\n' | '\r' => FONT_SIZE * ASCII_NORMAL_RATIO,
-            
-            // 在匹配逻辑中修改:
-            _ if c.is_ascii() => {
-                match c {
-                    // 空格单独处理
-                    ' ' => FONT_SIZE * SPACE_RATIO,
-                    // 宽字母
-                    'W' | 'M' | 'w' | 'm' | '@' | '%' | '#' | '&' | '$'
-                    => FONT_SIZE * ASCII_WIDE_RATIO,
-                    
-                    // 窄字符 (移除空格)
-                    'i' | 'l' | 'I' | 'j' | 't' | 'f' | 'r' |
-                    '.' | ',' | ':' | ';' | '\'' | '!' | '|' | '`'
-                    => FONT_SIZE * ASCII_NARROW_RATIO,
-                    
-                    // 数字和大部分字母
-                    '0'..='9' | 'a'..='z' | 'A'..='Z'
-                    => FONT_SIZE * ASCII_NORMAL_RATIO,
-                    
-                    // 其他ASCII符号
-                    _ => FONT_SIZE * ASCII_NORMAL_RATIO,
-                }
-            },
-            
-            // 其他Unicode字符，默认半角宽度
-            _ => FONT_SIZE * ASCII_NORMAL_RATIO,
-        };
-        
-        total_width += char_width;
+/// 加载气泡字体，供排版使用真实字形宽度
+pub fn init_bubble_font(app: &AppHandle) {
+    let Ok(resource_dir) = app.path().resource_dir() else {
+        return;
+    };
+    match std::fs::read(resource_dir.join(BUBBLE_FONT_RESOURCE)) {
+        Ok(bytes) => {
+            if let Err(e) = text_layout::init_font(bytes) {
+                eprintln!("{}", e);
+            }
+        }
+        Err(e) => eprintln!("未找到气泡字体，使用估算宽度: {}", e),
     }
-    
-    // 添加左右边距
-    let padding: f64 = 173.0;
-    let calculated_width = total_width + padding;
-    
-    // 限制宽度范围：最小150，最大800
-    let width = calculated_width.clamp(150.0, 800.0);
-    
-    LogicalSize::new(width, my_windows::WINDOW_HEIGHT_TRANSLATE_BUBBLE)
 }
 
-// 可选：支持多行文本计算
-#[allow(dead_code)]
-pub fn calculate_multiline_text_size(content: &str, max_width: f64) -> LogicalSize<f64> {
-    let lines: Vec<&str> = content.lines().collect();
-    let line_count = lines.len().max(1);
-    
-    // 计算最宽行的宽度
-    let max_line_width = lines.iter()
-        .map(|line| {
-            let size = calculate_text_width(line);
-            size.width
-        })
-        .max_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap_or(150.0);
-    
-    let width = max_line_width.min(max_width);
-    let height = my_windows::WINDOW_HEIGHT_TRANSLATE_BUBBLE * line_count as f64;
-    
+/// 按气泡最大宽度排版文本，返回气泡窗口大小
+pub fn calculate_text_width(content: &str) -> LogicalSize<f64> {
+    let metrics = text_layout::default_metrics();
+    let layout = text_layout::layout_text(content, &*metrics, MAX_WIDTH - HORIZONTAL_PADDING);
+
+    let width = (layout.width + HORIZONTAL_PADDING).clamp(MIN_WIDTH, MAX_WIDTH);
+    let height = (layout.height + VERTICAL_PADDING).clamp(
+        my_windows::WINDOW_HEIGHT_TRANSLATE_BUBBLE,
+        my_windows::WINDOW_MAX_HEIGHT_TRANSLATE_BUBBLE,
+    );
+
     LogicalSize::new(width, height)
 }
 
@@ -122,8 +49,9 @@ mod tests {
     #[test]
     fn test_chinese_text() {
         let size = calculate_text_width("你好世界");
-        // 4个中文字符 × 14px + 100px padding = 156px
-        assert!(size.width >= 150.0 && size.width <= 200.0);
+        // 4个中文字符 × 14px + 229px padding
+        assert_eq!(size.width, 285.0);
+        assert_eq!(size.height, my_windows::WINDOW_HEIGHT_TRANSLATE_BUBBLE);
     }
 
     #[test]
@@ -131,6 +59,7 @@ mod tests {
         let size = calculate_text_width("Hello 世界");
         // 验证宽度在合理范围内
         assert!(size.width >= 150.0 && size.width <= 800.0);
+        assert_eq!(size.height, my_windows::WINDOW_HEIGHT_TRANSLATE_BUBBLE);
     }
 
     #[test]
@@ -138,4 +67,13 @@ mod tests {
         let size = calculate_text_width("Hello World");
         assert!(size.width >= 150.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_long_text_wraps() {
+        let size =
+            calculate_text_width(&"The quick brown fox jumps over the lazy dog. ".repeat(10));
+        assert!(size.width > 700.0 && size.width <= MAX_WIDTH);
+        assert!(size.height > my_windows::WINDOW_HEIGHT_TRANSLATE_BUBBLE);
+        assert!(size.height <= my_windows::WINDOW_MAX_HEIGHT_TRANSLATE_BUBBLE);
+    }
+}
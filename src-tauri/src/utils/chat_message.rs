@@ -1,27 +1,280 @@
-use serde::{Deserialize, Serialize};
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
 use std::fmt;
+use std::path::Path;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct LLMChatMessage<'a> {
     pub role: &'a Role,
-    pub content: &'a str,
+    pub content: LLMContent<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<&'a [ToolCall]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<&'a str>,
+}
+
+/// 发送给模型的消息内容：纯文本，或文本和图片组成的列表（需要支持视觉的模型）
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum LLMContent<'a> {
+    Text(Cow<'a, str>),
+    Parts(Vec<LLMContentPart<'a>>),
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LLMContentPart<'a> {
+    Text { text: &'a str },
+    ImageUrl { image_url: LLMImageUrl },
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LLMImageUrl {
+    /// 图片地址或 `data:image/png;base64,...`
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<ImageDetail>,
+}
+
+/// 图片的识别精度，低精度消耗的 token 更少
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageDetail {
+    Auto,
+    Low,
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSource {
+    /// 网络地址或 data URL
+    Url(String),
+    /// 本地文件，发送前读取并转为 data URL
+    Path(String),
+}
+
+/// 消息内容中的一段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text {
+        text: String,
+    },
+    Image {
+        source: ImageSource,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        detail: Option<ImageDetail>,
+    },
+}
+
+/// 消息内容。纯文本序列化为字符串，与只有文本的旧会话记录兼容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+impl MessageContent {
+    /// 文本内容，多段文本用换行连接，忽略图片
+    pub fn text(&self) -> Cow<'_, str> {
+        match self {
+            MessageContent::Text(text) => Cow::Borrowed(text),
+            MessageContent::Parts(parts) => Cow::Owned(
+                parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        ContentPart::Text { text } => Some(text.as_str()),
+                        ContentPart::Image { .. } => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        }
+    }
+
+    /// 检查本地图片都在 `dir` 中。来自前端的消息必须先检查，避免读取并上传任意文件
+    pub fn check_image_paths(&self, dir: &Path) -> Result<(), String> {
+        let MessageContent::Parts(parts) = self else {
+            return Ok(());
+        };
+        let dir = dir
+            .canonicalize()
+            .map_err(|e| format!("读取图片目录失败: {}", e))?;
+        for part in parts {
+            if let ContentPart::Image {
+                source: ImageSource::Path(path),
+                ..
+            } = part
+            {
+                let inside = Path::new(path)
+                    .canonicalize()
+                    .is_ok_and(|path| path.starts_with(&dir));
+                if !inside {
+                    return Err(format!("不允许读取图片 {}", path));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 转为发送给模型的格式，本地图片读取为 data URL
+    fn as_llm(&self) -> Result<LLMContent<'_>, String> {
+        let parts = match self {
+            MessageContent::Text(text) => return Ok(LLMContent::Text(Cow::Borrowed(text))),
+            MessageContent::Parts(parts) => parts,
+        };
+        parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text { text } => Ok(LLMContentPart::Text { text }),
+                ContentPart::Image { source, detail } => Ok(LLMContentPart::ImageUrl {
+                    image_url: LLMImageUrl {
+                        url: image_url(source)?,
+                        detail: *detail,
+                    },
+                }),
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map(LLMContent::Parts)
+    }
+}
+
+impl Default for MessageContent {
+    fn default() -> Self {
+        MessageContent::Text(String::new())
+    }
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        MessageContent::Text(text)
+    }
+}
+
+impl From<&str> for MessageContent {
+    fn from(text: &str) -> Self {
+        MessageContent::Text(text.to_string())
+    }
+}
+
+impl From<Vec<ContentPart>> for MessageContent {
+    fn from(parts: Vec<ContentPart>) -> Self {
+        MessageContent::Parts(parts)
+    }
+}
+
+pub fn data_url(mime_type: &str, bytes: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        mime_type,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+/// 按扩展名判断图片类型，无法判断时按 png 处理
+fn image_mime_type(path: &str) -> &'static str {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "image/png",
+    }
+}
+
+fn image_url(source: &ImageSource) -> Result<String, String> {
+    match source {
+        ImageSource::Url(url) => Ok(url.clone()),
+        ImageSource::Path(path) => {
+            let bytes = std::fs::read(path).map_err(|e| format!("读取图片失败 {}: {}", path, e))?;
+            Ok(data_url(image_mime_type(path), &bytes))
+        }
+    }
+}
+
+/// 模型发起的工具调用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: FunctionCall,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    /// JSON 格式的参数
+    pub arguments: String,
 }
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+/// 模型调用工具时，助手消息的 content 为 null
+fn content_or_null<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MessageContent, D::Error> {
+    Ok(Option::<MessageContent>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 /// Represents a chat message with a role and content
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: Role,
-    pub content: String,
+    #[serde(default, deserialize_with = "content_or_null")]
+    pub content: MessageContent,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw: Option<String>,
+    /// 助手消息中要求执行的工具调用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// 工具消息对应的调用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// 推理模型的思考过程，只用于显示，不会发送给模型。
+    /// 非流式回复中的字段名为 reasoning_content
+    #[serde(
+        default,
+        alias = "reasoning_content",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub reasoning: Option<String>,
 }
 
 impl ChatMessage {
-    pub fn as_llm(&self) -> LLMChatMessage<'_> {
-        LLMChatMessage {
-            role: &self.role,
-            content: &self.content,
+    pub fn new(role: Role, content: impl Into<MessageContent>) -> Self {
+        ChatMessage {
+            role,
+            content: content.into(),
+            raw: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning: None,
         }
     }
+
+    /// 工具的执行结果
+    pub fn tool_result(tool_call_id: &str, content: String) -> Self {
+        ChatMessage {
+            tool_call_id: Some(tool_call_id.to_string()),
+            ..ChatMessage::new(Role::Tool, content)
+        }
+    }
+
+    pub fn as_llm(&self) -> Result<LLMChatMessage<'_>, String> {
+        Ok(LLMChatMessage {
+            role: &self.role,
+            content: self.content.as_llm()?,
+            tool_calls: self.tool_calls.as_deref(),
+            tool_call_id: self.tool_call_id.as_deref(),
+        })
+    }
 }
 
 /// Represents the role of a participant in the conversation
@@ -31,6 +284,7 @@ pub enum Role {
     System,
     User,
     Assistant,
+    Tool,
 }
 
 impl fmt::Display for Role {
@@ -39,6 +293,7 @@ impl fmt::Display for Role {
             Role::System => write!(f, "system"),
             Role::User => write!(f, "user"),
             Role::Assistant => write!(f, "assistant"),
+            Role::Tool => write!(f, "tool"),
         }
     }
 }
@@ -61,6 +316,7 @@ impl From<String> for Role {
             "system" => Role::System,
             "user" => Role::User,
             "assistant" => Role::Assistant,
+            "tool" => Role::Tool,
             _ => Role::User, // Default to user for unknown roles
         }
     }
@@ -72,6 +328,7 @@ impl<'a> From<&'a str> for Role {
             "system" => Role::System,
             "user" => Role::User,
             "assistant" => Role::Assistant,
+            "tool" => Role::Tool,
             _ => Role::User, // Default to user for unknown roles
         }
     }
@@ -81,6 +338,9 @@ impl<'a> From<&'a str> for Role {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessageHistory {
     pub messages: Vec<ChatMessage>,
+    /// 会话原文来自截图识别时，截图文件的路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
 }
 
 impl ChatMessageHistory {
@@ -88,28 +348,54 @@ impl ChatMessageHistory {
     pub fn new() -> Self {
         ChatMessageHistory {
             messages: Vec::new(),
+            image: None,
         }
     }
 
     /// Adds a new message to the history
-    pub fn add_message(&mut self, role: Role, content: String, raw: Option<String>) -> &mut Self {
-        let message = ChatMessage { role, content, raw };
+    pub fn add_message(
+        &mut self,
+        role: Role,
+        content: impl Into<MessageContent>,
+        raw: Option<String>,
+    ) -> &mut Self {
+        let message = ChatMessage {
+            raw,
+            ..ChatMessage::new(role, content)
+        };
+        self.push(message)
+    }
+
+    /// 添加完整的消息，用于带工具调用的助手消息和工具结果
+    pub fn push(&mut self, message: ChatMessage) -> &mut Self {
         self.messages.push(message);
         self
     }
 
     /// Adds a system message to the history
-    pub fn add_system_message(&mut self, content: String, raw: Option<String>) -> &mut Self {
+    pub fn add_system_message(
+        &mut self,
+        content: impl Into<MessageContent>,
+        raw: Option<String>,
+    ) -> &mut Self {
         self.add_message(Role::System, content, raw)
     }
 
     /// Adds a user message to the history
-    pub fn add_user_message(&mut self, content: String, raw: Option<String>) -> &mut Self {
+    pub fn add_user_message(
+        &mut self,
+        content: impl Into<MessageContent>,
+        raw: Option<String>,
+    ) -> &mut Self {
         self.add_message(Role::User, content, raw)
     }
 
     /// Adds an assistant message to the history
-    pub fn add_assistant_message(&mut self, content: String, raw: Option<String>) -> &mut Self {
+    pub fn add_assistant_message(
+        &mut self,
+        content: impl Into<MessageContent>,
+        raw: Option<String>,
+    ) -> &mut Self {
         self.add_message(Role::Assistant, content, raw)
     }
 
@@ -124,3 +410,103 @@ impl Default for ChatMessageHistory {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_text_history() {
+        let message: ChatMessage =
+            serde_json::from_str(r#"{"role":"user","content":"你好","raw":"hello"}"#).unwrap();
+        assert_eq!(message.content, MessageContent::Text("你好".to_string()));
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({ "role": "user", "content": "你好", "raw": "hello" })
+        );
+    }
+
+    #[test]
+    fn test_reasoning_not_sent() {
+        let message = ChatMessage {
+            reasoning: Some("用户在问候".to_string()),
+            ..ChatMessage::new(Role::Assistant, "你好")
+        };
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({ "role": "assistant", "content": "你好", "reasoning": "用户在问候" })
+        );
+        assert_eq!(
+            serde_json::to_value(message.as_llm().unwrap()).unwrap(),
+            serde_json::json!({ "role": "assistant", "content": "你好" })
+        );
+
+        let reply: ChatMessage = serde_json::from_str(
+            r#"{"role":"assistant","content":"你好","reasoning_content":"用户在问候"}"#,
+        )
+        .unwrap();
+        assert_eq!(reply.reasoning.as_deref(), Some("用户在问候"));
+    }
+
+    #[test]
+    fn test_parts_round_trip() {
+        let json = serde_json::json!([
+            { "type": "text", "text": "翻译图片中的文字" },
+            { "type": "image", "source": { "path": "/tmp/a.jpg" }, "detail": "low" },
+            { "type": "image", "source": { "url": "https://example.com/b.png" } }
+        ]);
+        let content: MessageContent = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(content.text(), "翻译图片中的文字");
+        assert_eq!(serde_json::to_value(&content).unwrap(), json);
+    }
+
+    #[test]
+    fn test_path_image_as_data_url() {
+        let path = std::env::temp_dir().join("okey_ai_chat_message_test.JPG");
+        std::fs::write(&path, b"jpg").unwrap();
+        let content = MessageContent::Parts(vec![ContentPart::Image {
+            source: ImageSource::Path(path.display().to_string()),
+            detail: None,
+        }]);
+        let llm_content = content.as_llm().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            serde_json::to_value(&llm_content).unwrap(),
+            serde_json::json!([
+                { "type": "image_url", "image_url": { "url": "data:image/jpeg;base64,anBn" } }
+            ])
+        );
+
+        let missing = MessageContent::Parts(vec![ContentPart::Image {
+            source: ImageSource::Path("/nonexistent/image.png".to_string()),
+            detail: None,
+        }]);
+        assert!(missing.as_llm().is_err());
+    }
+
+    #[test]
+    fn test_check_image_paths() {
+        let root = std::env::temp_dir().join("okey_ai_image_paths_test");
+        let dir = root.join("screenshots");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("1.png"), b"png").unwrap();
+        std::fs::write(root.join("secret.txt"), b"secret").unwrap();
+        let image = |path: std::path::PathBuf| {
+            MessageContent::Parts(vec![ContentPart::Image {
+                source: ImageSource::Path(path.display().to_string()),
+                detail: None,
+            }])
+        };
+
+        assert!(image(dir.join("1.png")).check_image_paths(&dir).is_ok());
+        assert!(image(root.join("secret.txt"))
+            .check_image_paths(&dir)
+            .is_err());
+        assert!(image(dir.join("../secret.txt"))
+            .check_image_paths(&dir)
+            .is_err());
+        assert!(image(dir.join("2.png")).check_image_paths(&dir).is_err());
+        assert!(MessageContent::from("你好").check_image_paths(&dir).is_ok());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
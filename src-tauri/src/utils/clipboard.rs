@@ -0,0 +1,258 @@
+//! 剪贴板读写抽象与按键模拟，选区捕获逻辑可在测试中替换为假剪贴板
+
+use rdev::{simulate, EventType, Key};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// 剪贴板内容快照，用于捕获选区后恢复用户原有的剪贴板
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardContent {
+    Empty,
+    Text(String),
+    Image {
+        width: usize,
+        height: usize,
+        bytes: Vec<u8>,
+    },
+}
+
+pub trait ClipboardBackend {
+    /// 读取当前剪贴板内容（文本优先，其次图片）
+    fn snapshot(&mut self) -> ClipboardContent;
+    /// 将剪贴板恢复为快照内容
+    fn restore(&mut self, content: &ClipboardContent) -> Result<(), String>;
+    fn read_text(&mut self) -> Option<String>;
+    fn write_text(&mut self, text: &str) -> Result<(), String>;
+    fn clear(&mut self) -> Result<(), String>;
+}
+
+/// 基于 arboard 的系统剪贴板
+pub struct SystemClipboard(arboard::Clipboard);
+
+impl SystemClipboard {
+    pub fn new() -> Result<Self, String> {
+        arboard::Clipboard::new()
+            .map(Self)
+            .map_err(|e| format!("Failed to open clipboard: {}", e))
+    }
+}
+
+impl ClipboardBackend for SystemClipboard {
+    fn snapshot(&mut self) -> ClipboardContent {
+        if let Ok(text) = self.0.get_text() {
+            return ClipboardContent::Text(text);
+        }
+        if let Ok(image) = self.0.get_image() {
+            return ClipboardContent::Image {
+                width: image.width,
+                height: image.height,
+                bytes: image.bytes.into_owned(),
+            };
+        }
+        ClipboardContent::Empty
+    }
+
+    fn restore(&mut self, content: &ClipboardContent) -> Result<(), String> {
+        match content {
+            ClipboardContent::Empty => self.clear(),
+            ClipboardContent::Text(text) => {
+                record_own_text(text);
+                self.0
+                    .set_text(text.clone())
+                    .map_err(|e| format!("Failed to restore clipboard text: {}", e))
+            }
+            ClipboardContent::Image {
+                width,
+                height,
+                bytes,
+            } => self
+                .0
+                .set_image(arboard::ImageData {
+                    width: *width,
+                    height: *height,
+                    bytes: Cow::Borrowed(bytes),
+                })
+                .map_err(|e| format!("Failed to restore clipboard image: {}", e)),
+        }
+    }
+
+    fn read_text(&mut self) -> Option<String> {
+        self.0.get_text().ok()
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), String> {
+        record_own_text(text);
+        self.0
+            .set_text(text)
+            .map_err(|e| format!("Failed to write clipboard text: {}", e))
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.0
+            .clear()
+            .map_err(|e| format!("Failed to clear clipboard: {}", e))
+    }
+}
+
+/// 记住的应用自身写入次数，覆盖一次写入加恢复原内容这样的连续操作
+const OWN_TEXTS_CAPACITY: usize = 8;
+/// 写入记录的有效期，剪贴板监听没来得及读到的写入（如很快被恢复）不会一直残留
+const OWN_TEXT_TTL: Duration = Duration::from_secs(5);
+
+/// 应用最近写入剪贴板的文本的哈希及写入时间，剪贴板监听读到这些内容时不触发翻译
+static OWN_TEXTS: Mutex<VecDeque<(u64, Instant)>> = Mutex::new(VecDeque::new());
+
+fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 记录应用自身写入剪贴板的文本，在写入之前调用，监听随时读到都能识别
+pub fn record_own_text(text: &str) {
+    let mut own_texts = OWN_TEXTS.lock().unwrap();
+    if own_texts.len() == OWN_TEXTS_CAPACITY {
+        own_texts.pop_front();
+    }
+    own_texts.push_back((text_hash(text), Instant::now()));
+}
+
+/// 文本是否为应用最近写入剪贴板的内容，命中后移除该记录，
+/// 之后用户再次复制同样的内容仍会被识别为新的复制
+pub fn take_own_text(text: &str) -> bool {
+    let mut own_texts = OWN_TEXTS.lock().unwrap();
+    own_texts.retain(|(_, written_at)| written_at.elapsed() < OWN_TEXT_TTL);
+    let hash = text_hash(text);
+    match own_texts.iter().position(|(h, _)| *h == hash) {
+        Some(index) => {
+            own_texts.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(target_os = "macos")]
+const SHORTCUT_MODIFIER: Key = Key::MetaLeft;
+#[cfg(not(target_os = "macos"))]
+const SHORTCUT_MODIFIER: Key = Key::ControlLeft;
+
+/// 模拟 Ctrl/Cmd + key 组合键
+fn simulate_shortcut(key: Key) -> Result<(), String> {
+    send_shortcut(key, |event| {
+        let result =
+            simulate(event).map_err(|e| format!("Failed to simulate {:?}: {:?}", event, e));
+        // 部分系统需要间隔才能正确识别按键顺序
+        sleep(Duration::from_millis(20));
+        result
+    })
+}
+
+/// 依次发送组合键的按下和松开事件；中途失败时仍会松开修饰键，
+/// 否则之后用户的输入都会带上 Ctrl/Cmd
+fn send_shortcut<F>(key: Key, mut send: F) -> Result<(), String>
+where
+    F: FnMut(&EventType) -> Result<(), String>,
+{
+    let result = send(&EventType::KeyPress(SHORTCUT_MODIFIER))
+        .and_then(|()| send(&EventType::KeyPress(key)))
+        .and_then(|()| send(&EventType::KeyRelease(key)));
+    let released = send(&EventType::KeyRelease(SHORTCUT_MODIFIER));
+    result.and(released)
+}
+
+pub fn simulate_copy() -> Result<(), String> {
+    simulate_shortcut(Key::KeyC)
+}
+
+pub fn simulate_paste() -> Result<(), String> {
+    simulate_shortcut(Key::KeyV)
+}
+
+#[cfg(test)]
+pub mod testing {
+    use super::{ClipboardBackend, ClipboardContent};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// 内存中的假剪贴板，内容可与假复制 / 粘贴触发器共享
+    pub struct FakeClipboard(pub Rc<RefCell<ClipboardContent>>);
+
+    impl ClipboardBackend for FakeClipboard {
+        fn snapshot(&mut self) -> ClipboardContent {
+            self.0.borrow().clone()
+        }
+
+        fn restore(&mut self, content: &ClipboardContent) -> Result<(), String> {
+            *self.0.borrow_mut() = content.clone();
+            Ok(())
+        }
+
+        fn read_text(&mut self) -> Option<String> {
+            match &*self.0.borrow() {
+                ClipboardContent::Text(text) => Some(text.clone()),
+                _ => None,
+            }
+        }
+
+        fn write_text(&mut self, text: &str) -> Result<(), String> {
+            *self.0.borrow_mut() = ClipboardContent::Text(text.to_string());
+            Ok(())
+        }
+
+        fn clear(&mut self) -> Result<(), String> {
+            *self.0.borrow_mut() = ClipboardContent::Empty;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_own_texts() {
+        record_own_text("译文 own_texts");
+        assert!(take_own_text("译文 own_texts"));
+        assert!(!take_own_text("译文 own_texts"));
+        assert!(!take_own_text("用户复制的内容 own_texts"));
+
+        // 只记住最近的若干次写入
+        record_own_text("译文 own_texts");
+        for i in 0..OWN_TEXTS_CAPACITY {
+            record_own_text(&format!("own_texts {}", i));
+        }
+        assert!(!take_own_text("译文 own_texts"));
+        assert!(take_own_text(&format!(
+            "own_texts {}",
+            OWN_TEXTS_CAPACITY - 1
+        )));
+    }
+
+    #[test]
+    fn test_shortcut_releases_modifier_on_failure() {
+        let mut sent = Vec::new();
+        let result = send_shortcut(Key::KeyV, |event| {
+            sent.push(*event);
+            match event {
+                EventType::KeyPress(Key::KeyV) => Err("blocked".to_string()),
+                _ => Ok(()),
+            }
+        });
+
+        assert_eq!(result, Err("blocked".to_string()));
+        assert_eq!(
+            sent,
+            [
+                EventType::KeyPress(SHORTCUT_MODIFIER),
+                EventType::KeyPress(Key::KeyV),
+                EventType::KeyRelease(SHORTCUT_MODIFIER),
+            ]
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! 全局配置的版本迁移
+//!
+//! 配置以 JSON 保存，迁移直接修改 JSON，不依赖当前的 `GlobalConfig` 结构，
+//! 因此旧版本的配置即使无法按当前结构解析也能逐步升级。
+//! 修改配置格式时：`CONFIG_VERSION` 加一，在 `MIGRATIONS` 末尾添加迁移，
+//! 并在 `fixtures/config` 中添加新版本的示例配置。
+
+use crate::my_actions::{self, action_ids};
+use crate::my_config::{GestureConfig, GlobalConfig, Shortcut};
+use crate::my_shortcut;
+use crate::states::setting_states::AppState;
+use serde_json::{json, Map, Value};
+
+/// 当前配置格式的版本
+pub const CONFIG_VERSION: u32 = 4;
+
+/// 按顺序执行的迁移，第 i 项把版本 i 的配置升级到版本 i + 1
+const MIGRATIONS: [fn(&mut Value); CONFIG_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+];
+
+/// 版本 0 的设置只保存在内存中，重启后恢复默认值
+fn migrate_v0_to_v1(config: &mut Value) {
+    if let Some(object) = config.as_object_mut() {
+        object
+            .entry("settings")
+            .or_insert_with(|| json!(AppState::default()));
+    }
+}
+
+/// 移除调试用的 `test_field` 和 `test` 快捷键
+fn migrate_v1_to_v2(config: &mut Value) {
+    let Some(object) = config.as_object_mut() else {
+        return;
+    };
+    object.remove("test_field");
+    if let Some(shortcuts) = object.get_mut("shortcuts").and_then(Value::as_array_mut) {
+        shortcuts.retain(|shortcut| shortcut.get("name").and_then(Value::as_str) != Some("test"));
+    }
+}
+
+/// 翻译窗口固定模式原先借用 `auto_close_translate` 保存，改为 `pinned`
+fn migrate_v2_to_v3(config: &mut Value) {
+    let Some(settings) = config.get_mut("settings").and_then(Value::as_object_mut) else {
+        return;
+    };
+    if let Some(pinned) = settings.remove("auto_close_translate") {
+        settings.insert("pinned".to_string(), pinned);
+    }
+}
+
+/// 回写快捷键原先只存在于默认配置中，为已有配置补上；
+/// 已绑定回写或默认组合键已被其他动作占用时保持不变
+fn migrate_v3_to_v4(config: &mut Value) {
+    let gesture_key = config
+        .pointer("/gesture/key")
+        .and_then(Value::as_str)
+        .map_or_else(|| GestureConfig::default().key, str::to_string);
+    let Some(shortcuts) = config.get_mut("shortcuts").and_then(Value::as_array_mut) else {
+        return;
+    };
+    let Ok(existing) = serde_json::from_value::<Vec<Shortcut>>(Value::Array(shortcuts.clone()))
+    else {
+        return;
+    };
+    if existing
+        .iter()
+        .any(|s| my_actions::resolve_action_id(&s.name) == action_ids::WRITE_BACK)
+    {
+        return;
+    }
+    let Some(write_back) = GlobalConfig::default()
+        .shortcuts
+        .into_iter()
+        .find(|s| s.name == action_ids::WRITE_BACK)
+    else {
+        return;
+    };
+    if my_shortcut::find_conflict(
+        &existing,
+        &gesture_key,
+        action_ids::WRITE_BACK,
+        &write_back.hot_key,
+    ) != Ok(None)
+    {
+        return;
+    }
+    shortcuts.push(json!(write_back));
+}
+
+/// 配置的版本，没有版本字段的旧配置为 0
+pub fn config_version(config: &Value) -> u32 {
+    config
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32)
+}
+
+/// 是否需要迁移，更高版本（新版应用写入）的配置保持不变
+pub fn needs_migration(config: &Value) -> bool {
+    config_version(config) < CONFIG_VERSION
+}
+
+/// 把旧版本的配置升级到当前版本
+pub fn migrate_config(mut config: Value) -> Value {
+    let version = config_version(&config) as usize;
+    if version >= MIGRATIONS.len() {
+        return config;
+    }
+    for migration in &MIGRATIONS[version..] {
+        migration(&mut config);
+    }
+    if let Some(object) = config.as_object_mut() {
+        object.insert("version".to_string(), json!(CONFIG_VERSION));
+    }
+    config
+}
+
+/// 逐个字段解析配置：无法解析的字段恢复为默认值，其余字段和不认识的字段保持不变。
+/// 返回配置和被恢复的字段及原因；配置不是 JSON 对象时返回错误
+pub fn parse_config(config: Value) -> Result<(GlobalConfig, Vec<String>), String> {
+    let Value::Object(mut object) = config else {
+        return Err("配置不是 JSON 对象".to_string());
+    };
+    let mut reset = Vec::new();
+    let keys: Vec<String> = object.keys().cloned().collect();
+    for key in keys {
+        let field = Map::from_iter([(key.clone(), object[&key].clone())]);
+        if let Err(e) = serde_json::from_value::<GlobalConfig>(Value::Object(field)) {
+            object.remove(&key);
+            reset.push(format!("{}: {}", key, e));
+        }
+    }
+    let config = serde_json::from_value(Value::Object(object)).map_err(|e| e.to_string())?;
+    Ok((config, reset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::my_config::{SelectionConfig, TtsConfig};
+    use crate::states::setting_states::AutoSpeakState;
+
+    /// 每个历史版本的示例配置
+    const FIXTURES: [(&str, &str); 6] = [
+        (
+            "v0_baseline",
+            include_str!("../../fixtures/config/v0_baseline.json"),
+        ),
+        (
+            "v0_extended",
+            include_str!("../../fixtures/config/v0_extended.json"),
+        ),
+        ("v1", include_str!("../../fixtures/config/v1.json")),
+        ("v2", include_str!("../../fixtures/config/v2.json")),
+        ("v3", include_str!("../../fixtures/config/v3.json")),
+        ("v4", include_str!("../../fixtures/config/v4.json")),
+    ];
+
+    fn load_fixture(name: &str) -> Value {
+        let (_, content) = FIXTURES
+            .iter()
+            .find(|(fixture, _)| *fixture == name)
+            .unwrap();
+        serde_json::from_str(content).unwrap()
+    }
+
+    fn load_config(name: &str) -> GlobalConfig {
+        serde_json::from_value(migrate_config(load_fixture(name))).unwrap()
+    }
+
+    #[test]
+    fn test_all_fixtures_load() {
+        for (name, _) in FIXTURES {
+            let config = load_config(name);
+            assert_eq!(config.version, CONFIG_VERSION, "{}", name);
+            assert!(
+                config.shortcuts.iter().all(|s| s.name != "test"),
+                "{}",
+                name
+            );
+            assert!(!config.extra.contains_key("test_field"), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_v0_baseline_gets_defaults() {
+        let config = load_config("v0_baseline");
+        assert_eq!(config.settings, AppState::default());
+        let names: Vec<&str> = config.shortcuts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["okey_ai", action_ids::WRITE_BACK]);
+        assert!(config.custom_actions.is_empty());
+    }
+
+    #[test]
+    fn test_v0_extended_keeps_user_values() {
+        let config = load_config("v0_extended");
+        assert!(config.clipboard_watch.enabled);
+        assert_eq!(config.gesture.tap_count, 3);
+        assert_eq!(config.custom_actions.len(), 1);
+    }
+
+    #[test]
+    fn test_v1_keeps_settings() {
+        let config = load_config("v1");
+        assert!(config.settings.pinned);
+        assert_eq!(config.settings.auto_speak, AutoSpeakState::All);
+    }
+
+    fn write_back_key(config: &GlobalConfig) -> Option<&str> {
+        config
+            .shortcuts
+            .iter()
+            .find(|s| s.name == action_ids::WRITE_BACK)
+            .map(|s| s.hot_key.as_str())
+    }
+
+    #[test]
+    fn test_v3_gets_write_back_shortcut() {
+        let default_config = GlobalConfig::default();
+        let default_key = write_back_key(&default_config).unwrap();
+        let config = migrate_config(json!({
+            "version": 3,
+            "shortcuts": [{ "name": "okey_ai", "hot_key": "Alt+T" }],
+        }));
+        let config: GlobalConfig = serde_json::from_value(config).unwrap();
+        assert_eq!(write_back_key(&config), Some(default_key));
+
+        // 用户已有的回写快捷键保持不变
+        let config = load_config("v3");
+        assert_eq!(write_back_key(&config), Some("Ctrl+Shift+R"));
+        assert_eq!(config.shortcuts.len(), 2);
+
+        // 默认组合键已被其他动作占用时不添加
+        let config = migrate_config(json!({
+            "version": 3,
+            "shortcuts": [{ "name": "translate_bubble", "hot_key": default_key }],
+        }));
+        let config: GlobalConfig = serde_json::from_value(config).unwrap();
+        assert_eq!(write_back_key(&config), None);
+
+        // 当前版本中解除的绑定不会被恢复
+        let config = load_config("v4");
+        assert_eq!(write_back_key(&config), None);
+    }
+
+    #[test]
+    fn test_unknown_fields_round_trip() {
+        let config = load_config("v2");
+        assert_eq!(config.extra.get("future_field"), Some(&json!({ "a": 1 })));
+        let saved = serde_json::to_value(&config).unwrap();
+        assert_eq!(saved.get("future_field"), Some(&json!({ "a": 1 })));
+    }
+
+    #[test]
+    fn test_invalid_sections_reset_alone() {
+        let config = serde_json::from_str(include_str!(
+            "../../fixtures/config/v2_invalid_sections.json"
+        ))
+        .unwrap();
+        let (config, reset) = parse_config(config).unwrap();
+        // 未知的枚举值和字段类型错误只影响所在的字段
+        assert_eq!(reset.len(), 2);
+        assert!(reset[0].starts_with("selection:"));
+        assert!(reset[1].starts_with("tts:"));
+        assert_eq!(
+            config.selection.strategies,
+            SelectionConfig::default().strategies
+        );
+        assert_eq!(config.tts, TtsConfig::default());
+        assert_eq!(config.shortcuts[0].hot_key, "Alt+T");
+        assert_eq!(config.gesture.key, "MetaRight");
+        assert_eq!(config.gesture.tap_count, 3);
+        assert_eq!(config.extra.get("future_field"), Some(&json!({ "a": 1 })));
+
+        assert!(parse_config(json!("broken")).is_err());
+    }
+
+    #[test]
+    fn test_newer_version_unchanged() {
+        let config = json!({ "version": CONFIG_VERSION + 1, "shortcuts": [] });
+        assert!(!needs_migration(&config));
+        assert_eq!(migrate_config(config.clone()), config);
+    }
+}
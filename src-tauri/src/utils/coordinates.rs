@@ -0,0 +1,334 @@
+//! 多显示器坐标换算
+//!
+//! 系统给出的鼠标位置、显示器区域和窗口位置都是物理像素。每块显示器的缩放比例可能不同，
+//! 因此逻辑坐标只在单块显示器内有意义：以显示器物理原点为基准，按该显示器的缩放比例换算。
+//! 窗口位置统一在逻辑坐标中计算，再换算回物理像素设置，避免跨显示器时按错误的比例换算。
+
+use tauri::Monitor;
+
+/// 矩形，坐标单位由使用处说明
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// 宽高为 0 的矩形，如鼠标位置
+    pub fn point(x: f64, y: f64) -> Self {
+        Self::new(x, y, 0.0, 0.0)
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// 包含左上边界，不含右下边界
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// 点到矩形的距离，点在矩形内为 0
+    pub fn distance_to(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.x - x).max(x - self.right()).max(0.0);
+        let dy = (self.y - y).max(y - self.bottom()).max(0.0);
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// 两个矩形的外包矩形
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect::new(
+            x,
+            y,
+            self.right().max(other.right()) - x,
+            self.bottom().max(other.bottom()) - y,
+        )
+    }
+}
+
+/// 显示器信息，`bounds` 和 `work_area` 为物理像素
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorInfo {
+    pub bounds: Rect,
+    /// 去掉任务栏、Dock 后的可用区域
+    pub work_area: Rect,
+    pub scale_factor: f64,
+}
+
+impl MonitorInfo {
+    /// 物理坐标换算为该显示器的逻辑坐标
+    pub fn to_logical(self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.bounds.x + (x - self.bounds.x) / self.scale_factor,
+            self.bounds.y + (y - self.bounds.y) / self.scale_factor,
+        )
+    }
+
+    /// 该显示器的逻辑坐标换算为物理坐标
+    pub fn to_physical(self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.bounds.x + (x - self.bounds.x) * self.scale_factor,
+            self.bounds.y + (y - self.bounds.y) * self.scale_factor,
+        )
+    }
+
+    pub fn to_logical_rect(self, rect: &Rect) -> Rect {
+        let (x, y) = self.to_logical(rect.x, rect.y);
+        Rect::new(
+            x,
+            y,
+            rect.width / self.scale_factor,
+            rect.height / self.scale_factor,
+        )
+    }
+
+    /// 逻辑坐标换算为取整后的物理坐标，用于设置窗口位置
+    pub fn to_physical_position(self, x: f64, y: f64) -> (i32, i32) {
+        let (x, y) = self.to_physical(x, y);
+        (x.round() as i32, y.round() as i32)
+    }
+
+    /// 逻辑像素表示的工作区
+    pub fn logical_work_area(self) -> Rect {
+        self.to_logical_rect(&self.work_area)
+    }
+}
+
+impl From<&Monitor> for MonitorInfo {
+    fn from(monitor: &Monitor) -> Self {
+        let work_area = monitor.work_area();
+        MonitorInfo {
+            bounds: Rect::new(
+                monitor.position().x as f64,
+                monitor.position().y as f64,
+                monitor.size().width as f64,
+                monitor.size().height as f64,
+            ),
+            work_area: Rect::new(
+                work_area.position.x as f64,
+                work_area.position.y as f64,
+                work_area.size.width as f64,
+                work_area.size.height as f64,
+            ),
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+}
+
+/// 物理坐标所在的显示器，不在任何显示器内时取最近的一个
+pub fn monitor_at(monitors: &[MonitorInfo], x: f64, y: f64) -> Option<&MonitorInfo> {
+    monitors
+        .iter()
+        .find(|m| m.bounds.contains(x, y))
+        .or_else(|| {
+            monitors.iter().min_by(|a, b| {
+                a.bounds
+                    .distance_to(x, y)
+                    .total_cmp(&b.bounds.distance_to(x, y))
+            })
+        })
+}
+
+/// 所有显示器组成的虚拟桌面范围（物理像素）
+pub fn virtual_desktop_bounds(monitors: &[MonitorInfo]) -> Option<Rect> {
+    monitors
+        .iter()
+        .map(|m| m.bounds)
+        .reduce(|acc, bounds| acc.union(&bounds))
+}
+
+/// 把 `start..start+len` 限制在 `min..max` 内，放不下时贴住起始边
+pub fn clamp_span(start: f64, len: f64, min: f64, max: f64) -> f64 {
+    start.min(max - len).max(min)
+}
+
+/// 窗口在显示器工作区居中时的物理位置，`width`、`height` 为逻辑像素
+pub fn center_in_work_area(monitor: &MonitorInfo, width: f64, height: f64) -> (i32, i32) {
+    let area = monitor.logical_work_area();
+    let x = clamp_span(
+        area.x + (area.width - width) / 2.0,
+        width,
+        area.x,
+        area.right(),
+    );
+    let y = clamp_span(
+        area.y + (area.height - height) / 2.0,
+        height,
+        area.y,
+        area.bottom(),
+    );
+    monitor.to_physical_position(x, y)
+}
+
+/// 靠近某点（物理像素）放置窗口：点在显示器左半边时放右侧，否则放左侧；
+/// 上半边时放下方，否则放上方。放不下时换到另一侧，最终限制在工作区内
+pub fn position_near_point(
+    monitor: &MonitorInfo,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    offset: f64,
+) -> (i32, i32) {
+    let area = monitor.logical_work_area();
+    let bounds = monitor.to_logical_rect(&monitor.bounds);
+    let (x, y) = monitor.to_logical(x, y);
+
+    let pick = |point: f64, len: f64, start: f64, end: f64, first_half: bool| {
+        let after = point + offset;
+        let before = point - len - offset;
+        let pos = if first_half {
+            if after + len <= end {
+                after
+            } else {
+                before
+            }
+        } else if before >= start {
+            before
+        } else {
+            after
+        };
+        clamp_span(pos, len, start, end)
+    };
+
+    let (relative_x, relative_y) = (
+        (x - bounds.x) / bounds.width,
+        (y - bounds.y) / bounds.height,
+    );
+    let x = pick(x, width, area.x, area.right(), relative_x < 0.5);
+    let y = pick(y, height, area.y, area.bottom(), relative_y < 0.5);
+    monitor.to_physical_position(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 主屏 1x 1920x1080（底部 40px 任务栏），右侧 2x 4K，上方 1.5x 且坐标为负
+    fn monitors() -> Vec<MonitorInfo> {
+        vec![
+            MonitorInfo {
+                bounds: Rect::new(0.0, 0.0, 1920.0, 1080.0),
+                work_area: Rect::new(0.0, 0.0, 1920.0, 1040.0),
+                scale_factor: 1.0,
+            },
+            MonitorInfo {
+                bounds: Rect::new(1920.0, 0.0, 3840.0, 2160.0),
+                work_area: Rect::new(1920.0, 50.0, 3840.0, 2110.0),
+                scale_factor: 2.0,
+            },
+            MonitorInfo {
+                bounds: Rect::new(0.0, -1620.0, 2880.0, 1620.0),
+                work_area: Rect::new(0.0, -1620.0, 2880.0, 1620.0),
+                scale_factor: 1.5,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for monitor in monitors() {
+            let (cx, cy) = monitor.bounds.center();
+            let (lx, ly) = monitor.to_logical(cx, cy);
+            assert_eq!(monitor.to_physical(lx, ly), (cx, cy));
+        }
+    }
+
+    #[test]
+    fn test_logical_space_starts_at_physical_origin() {
+        let monitors = monitors();
+        let hidpi = &monitors[1];
+        assert_eq!(hidpi.to_logical(1920.0, 0.0), (1920.0, 0.0));
+        assert_eq!(hidpi.to_logical(2920.0, 1000.0), (2420.0, 500.0));
+        assert_eq!(
+            hidpi.logical_work_area(),
+            Rect::new(1920.0, 25.0, 1920.0, 1055.0)
+        );
+    }
+
+    #[test]
+    fn test_monitor_at() {
+        let monitors = monitors();
+        assert_eq!(monitor_at(&monitors, 10.0, 10.0).unwrap().scale_factor, 1.0);
+        assert_eq!(
+            monitor_at(&monitors, 3000.0, 2000.0).unwrap().scale_factor,
+            2.0
+        );
+        assert_eq!(
+            monitor_at(&monitors, 100.0, -100.0).unwrap().scale_factor,
+            1.5
+        );
+        // 主屏下方的空白区域，最近的是主屏
+        assert_eq!(
+            monitor_at(&monitors, 100.0, 1500.0).unwrap().scale_factor,
+            1.0
+        );
+        assert!(monitor_at(&[], 0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_virtual_desktop_bounds() {
+        assert_eq!(
+            virtual_desktop_bounds(&monitors()),
+            Some(Rect::new(0.0, -1620.0, 5760.0, 3780.0))
+        );
+        assert_eq!(virtual_desktop_bounds(&[]), None);
+    }
+
+    #[test]
+    fn test_center_in_work_area() {
+        let monitors = monitors();
+        assert_eq!(center_in_work_area(&monitors[0], 400.0, 600.0), (760, 220));
+        // 2x 屏：逻辑工作区 1920x1055，居中后换算回物理像素
+        assert_eq!(
+            center_in_work_area(&monitors[1], 400.0, 600.0),
+            (1920 + 1520, 50 + 455)
+        );
+    }
+
+    #[test]
+    fn test_position_near_point() {
+        let monitors = monitors();
+        // 主屏左上：放在右下方
+        assert_eq!(
+            position_near_point(&monitors[0], 100.0, 100.0, 400.0, 600.0, 10.0),
+            (110, 110)
+        );
+        // 主屏右下：放在左上方
+        assert_eq!(
+            position_near_point(&monitors[0], 1800.0, 1000.0, 400.0, 600.0, 10.0),
+            (1390, 390)
+        );
+        // 2x 屏左上：逻辑偏移 10 对应物理 20
+        assert_eq!(
+            position_near_point(&monitors[1], 2020.0, 200.0, 400.0, 600.0, 10.0),
+            (2040, 220)
+        );
+        // 贴近任务栏时限制在工作区内
+        assert_eq!(
+            position_near_point(&monitors[0], 100.0, 600.0, 400.0, 700.0, 10.0),
+            (110, 340)
+        );
+    }
+}
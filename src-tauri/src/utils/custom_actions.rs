@@ -0,0 +1,226 @@
+//! 用户自定义动作：按配置的提示词、模型执行，并输出到指定位置
+
+use crate::my_config::{ActionOutput, CustomAction};
+use crate::my_events::event_names;
+use crate::utils::chat_message::ChatMessage;
+use crate::utils::clipboard::{ClipboardBackend, SystemClipboard};
+use crate::utils::translation_manager::{CompletionOptions, TranslationManager};
+use crate::utils::{selecte_text, text_translation, write_back};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// 模板中被替换为选中文本的占位符
+pub const TEXT_PLACEHOLDER: &str = "{text}";
+
+const SYSTEM_PROMPT: &str = "你是一个有用的助手，请按照用户的要求处理文本。";
+
+/// 校验自定义动作，`is_reserved` 判断 id 是否已被内置动作占用，
+/// `providers` 为已配置的服务商，为空时不校验服务商
+pub fn validate_custom_action<F>(
+    action: &CustomAction,
+    is_reserved: F,
+    providers: Option<&[String]>,
+) -> Result<(), String>
+where
+    F: Fn(&str) -> bool,
+{
+    if action.id.is_empty() || action.id.len() > 64 {
+        return Err("动作 id 长度需在 1 到 64 之间".to_string());
+    }
+    if !action
+        .id
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(format!(
+            "动作 id 只能包含小写字母、数字和下划线: {}",
+            action.id
+        ));
+    }
+    if is_reserved(&action.id) {
+        return Err(format!("动作 id 与内置动作冲突: {}", action.id));
+    }
+    if action.name.trim().is_empty() {
+        return Err("动作名称不能为空".to_string());
+    }
+    if !action.prompt_template.contains(TEXT_PLACEHOLDER) {
+        return Err(format!("提示词模板需包含 {}", TEXT_PLACEHOLDER));
+    }
+    if let Some(temperature) = action.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err(format!("温度需在 0 到 2 之间: {}", temperature));
+        }
+    }
+    if let (Some(provider), Some(providers)) = (&action.provider, providers) {
+        if !providers.contains(provider) {
+            return Err(format!("未知的服务商: {}", provider));
+        }
+    }
+    if action.model.as_deref().is_some_and(|m| m.trim().is_empty()) {
+        return Err("模型名称不能为空".to_string());
+    }
+    Ok(())
+}
+
+pub fn build_prompt(action: &CustomAction, text: &str) -> String {
+    action.prompt_template.replace(TEXT_PLACEHOLDER, text)
+}
+
+async fn request_custom_action(
+    app_handle: &AppHandle,
+    action: &CustomAction,
+    text: String,
+) -> Option<Vec<ChatMessage>> {
+    let translation_manager = app_handle.state::<TranslationManager>();
+    let _ = translation_manager
+        .create_session_with_system_prompt(SYSTEM_PROMPT)
+        .await;
+
+    let options = CompletionOptions {
+        provider: action.provider.clone(),
+        model: action.model.clone(),
+        temperature: action.temperature,
+    };
+    // 只有窗口和气泡需要提前显示原文
+    let shows_conversation = matches!(
+        action.output,
+        ActionOutput::TranslateWindow | ActionOutput::Bubble
+    );
+    translation_manager
+        .complete(
+            None,
+            &build_prompt(action, &text),
+            Some(text),
+            &options,
+            |chat_history| {
+                let app_handle = app_handle.clone();
+                async move {
+                    if shows_conversation {
+                        let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
+                    }
+                }
+            },
+        )
+        .await
+}
+
+/// 对选中文本执行自定义动作
+pub fn run_on_selection(app_handle: &AppHandle, action: CustomAction) {
+    let app_handle = app_handle.clone();
+    async_runtime::spawn(async move {
+        let selected_text = selecte_text::get_selected_text_async(&app_handle).await;
+        if selected_text.is_empty() {
+            return;
+        }
+        println!("selected_text: {}", selected_text);
+        let result = request_custom_action(&app_handle, &action, selected_text).await;
+        deliver(&app_handle, &action, result).await;
+    });
+}
+
+async fn deliver(app_handle: &AppHandle, action: &CustomAction, result: Option<Vec<ChatMessage>>) {
+    match action.output {
+        ActionOutput::TranslateWindow => {
+            text_translation::show_in_translate_window(app_handle, result)
+        }
+        ActionOutput::Bubble => text_translation::show_in_bubble(app_handle, result),
+        output => {
+            let Some(content) = result.and_then(|messages| {
+                messages
+                    .last()
+                    .map(|message| message.content.text().into_owned())
+            }) else {
+                eprintln!("自定义动作执行失败: {}", action.id);
+                let _ = app_handle
+                    .notification()
+                    .builder()
+                    .title(&action.name)
+                    .body("执行失败")
+                    .show();
+                return;
+            };
+            match output {
+                ActionOutput::Clipboard => {
+                    // 写入的内容会被记录，剪贴板监听不会把它当作用户复制的内容
+                    if let Err(e) = SystemClipboard::new().and_then(|mut c| c.write_text(&content))
+                    {
+                        eprintln!("写入剪贴板失败: {}", e);
+                    }
+                }
+                ActionOutput::WriteBack => write_back::write_back_text(app_handle, content).await,
+                _ => {
+                    let _ = app_handle
+                        .notification()
+                        .builder()
+                        .title(&action.name)
+                        .body(content)
+                        .show();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action() -> CustomAction {
+        CustomAction {
+            id: "to_emoji".to_string(),
+            name: "Emoji".to_string(),
+            prompt_template: "用表情符号改写：{text}".to_string(),
+            provider: Some("qwen".to_string()),
+            model: None,
+            temperature: Some(0.7),
+            output: ActionOutput::Bubble,
+        }
+    }
+
+    fn validate(action: &CustomAction) -> Result<(), String> {
+        validate_custom_action(
+            action,
+            |id| id == "translate_window",
+            Some(&["qwen".to_string()][..]),
+        )
+    }
+
+    #[test]
+    fn test_valid_action() {
+        assert_eq!(validate(&action()), Ok(()));
+        assert_eq!(build_prompt(&action(), "hi"), "用表情符号改写：hi");
+    }
+
+    #[test]
+    fn test_invalid_actions() {
+        let cases = [
+            CustomAction {
+                id: "Bad Id".to_string(),
+                ..action()
+            },
+            CustomAction {
+                id: "translate_window".to_string(),
+                ..action()
+            },
+            CustomAction {
+                name: " ".to_string(),
+                ..action()
+            },
+            CustomAction {
+                prompt_template: "no placeholder".to_string(),
+                ..action()
+            },
+            CustomAction {
+                temperature: Some(3.0),
+                ..action()
+            },
+            CustomAction {
+                provider: Some("unknown".to_string()),
+                ..action()
+            },
+        ];
+        for case in cases {
+            assert!(validate(&case).is_err(), "{:?}", case);
+        }
+    }
+}
@@ -0,0 +1,616 @@
+//! 全局按键手势识别：由原始按键 / 鼠标事件流推导出单击、连击、长按、组合键等高层手势
+//!
+//! 识别器不依赖窗口和系统时间，时间通过 [`Clock`] 注入，便于用合成事件测试。
+
+use crate::my_config::GestureConfig;
+use rdev::Key;
+use std::time::{Duration, Instant};
+
+/// 短于该时长的按下视为按键抖动产生的碎片，不计为单击
+const MIN_PRESS: Duration = Duration::from_millis(10);
+/// 松开后在该时间内再次按下视为抖动
+const BOUNCE: Duration = Duration::from_millis(30);
+
+/// 可作为手势触发键的按键名称
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("ControlLeft", Key::ControlLeft),
+    ("ControlRight", Key::ControlRight),
+    ("MetaLeft", Key::MetaLeft),
+    ("MetaRight", Key::MetaRight),
+    ("ShiftLeft", Key::ShiftLeft),
+    ("ShiftRight", Key::ShiftRight),
+    ("Alt", Key::Alt),
+    ("AltGr", Key::AltGr),
+    ("CapsLock", Key::CapsLock),
+    ("Escape", Key::Escape),
+    ("Function", Key::Function),
+    ("F1", Key::F1),
+    ("F2", Key::F2),
+    ("F3", Key::F3),
+    ("F4", Key::F4),
+    ("F5", Key::F5),
+    ("F6", Key::F6),
+    ("F7", Key::F7),
+    ("F8", Key::F8),
+    ("F9", Key::F9),
+    ("F10", Key::F10),
+    ("F11", Key::F11),
+    ("F12", Key::F12),
+];
+
+/// 常用别名，统一映射到 `KEY_NAMES` 中的名称
+const KEY_ALIASES: &[(&str, &str)] = &[
+    ("RightCtrl", "ControlRight"),
+    ("LeftCtrl", "ControlLeft"),
+    ("RightCmd", "MetaRight"),
+    ("LeftCmd", "MetaLeft"),
+    ("RightShift", "ShiftRight"),
+    ("LeftShift", "ShiftLeft"),
+    ("RightAlt", "AltGr"),
+    ("Option", "Alt"),
+    ("Fn", "Function"),
+    ("Esc", "Escape"),
+];
+
+/// 解析触发键名称（不区分大小写，支持 `RightCtrl`、`RightCmd` 等别名）
+pub fn parse_key(name: &str) -> Result<Key, String> {
+    find_key(name)
+        .map(|(_, key)| *key)
+        .ok_or_else(|| format!("不支持的触发键: {}", name.trim()))
+}
+
+/// 触发键在 `KEY_NAMES` 中的名称，与全局快捷键的按键名称一致（如 `F8`、`ControlRight`）
+pub fn canonical_key_name(name: &str) -> Option<&'static str> {
+    find_key(name).map(|(key_name, _)| *key_name)
+}
+
+fn find_key(name: &str) -> Option<&'static (&'static str, Key)> {
+    let name = name.trim();
+    let canonical = KEY_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map_or(name, |(_, canonical)| *canonical);
+
+    KEY_NAMES
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(canonical))
+}
+
+/// 解析并校验后的手势设置，监听线程每次事件时读取，修改后无需重启线程
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureSettings {
+    pub key: Key,
+    pub tap_count: u8,
+    pub tap_window: Duration,
+    pub long_press: Duration,
+}
+
+impl TryFrom<&GestureConfig> for GestureSettings {
+    type Error = String;
+
+    fn try_from(config: &GestureConfig) -> Result<Self, Self::Error> {
+        let key = parse_key(&config.key)?;
+        if !(1..=3).contains(&config.tap_count) {
+            return Err(format!("连按次数必须为 1-3: {}", config.tap_count));
+        }
+        if !(100..=2000).contains(&config.tap_window_ms) {
+            return Err(format!(
+                "连按间隔必须在 100-2000 毫秒之间: {}",
+                config.tap_window_ms
+            ));
+        }
+        if !(200..=5000).contains(&config.long_press_ms) {
+            return Err(format!(
+                "长按时长必须在 200-5000 毫秒之间: {}",
+                config.long_press_ms
+            ));
+        }
+        Ok(Self {
+            key,
+            tap_count: config.tap_count,
+            tap_window: Duration::from_millis(config.tap_window_ms),
+            long_press: Duration::from_millis(config.long_press_ms),
+        })
+    }
+}
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// 输入识别器的原始事件
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    KeyDown(Key),
+    KeyUp(Key),
+    PointerMove { x: f64, y: f64 },
+    PointerDown,
+}
+
+/// 识别出的高层手势
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// 触发键单击，`count` 为连击序号（2 即双击），达到配置次数后重新计数
+    Tap {
+        count: u8,
+    },
+    LongPressStart,
+    LongPressEnd,
+    /// 触发键与其他按键同时按下
+    Chord(Key),
+    Click {
+        x: f64,
+        y: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    started: Instant,
+    chord: bool,
+    long_press: bool,
+}
+
+pub struct GestureRecognizer<C: Clock> {
+    clock: C,
+    settings: GestureSettings,
+    pressed: Option<Press>,
+    /// 过短的按下，随后若在抖动时间内再次按下则视为同一次按下的延续
+    fragment: Option<Press>,
+    /// 正在忽略一次松开后的抖动按下
+    bouncing: bool,
+    last_release: Option<Instant>,
+    /// 上一次单击松开的时间和连击序号
+    last_tap: Option<(Instant, u8)>,
+    other_keys: Vec<Key>,
+    pointer: (f64, f64),
+}
+
+impl<C: Clock> GestureRecognizer<C> {
+    pub fn new(settings: GestureSettings, clock: C) -> Self {
+        Self {
+            clock,
+            settings,
+            pressed: None,
+            fragment: None,
+            bouncing: false,
+            last_release: None,
+            last_tap: None,
+            other_keys: Vec::new(),
+            pointer: (0.0, 0.0),
+        }
+    }
+
+    pub fn settings(&self) -> GestureSettings {
+        self.settings
+    }
+
+    /// 替换手势设置；触发键改变时丢弃进行中的按键状态
+    pub fn set_settings(&mut self, settings: GestureSettings) {
+        if settings.key != self.settings.key {
+            self.pressed = None;
+            self.fragment = None;
+            self.bouncing = false;
+            self.last_tap = None;
+        }
+        self.settings = settings;
+    }
+
+    pub fn handle(&mut self, event: InputEvent) -> Vec<Gesture> {
+        let now = self.clock.now();
+        let mut gestures = Vec::new();
+
+        match event {
+            InputEvent::KeyDown(key) if key == self.settings.key => {
+                self.trigger_down(now, &mut gestures);
+            }
+            InputEvent::KeyUp(key) if key == self.settings.key => {
+                self.trigger_up(now, &mut gestures);
+            }
+            InputEvent::KeyDown(key) => {
+                if !self.other_keys.contains(&key) {
+                    self.other_keys.push(key);
+                }
+                // 其他按键打断连击序列
+                self.last_tap = None;
+                if let Some(press) = self.pressed.as_mut() {
+                    if !press.chord {
+                        press.chord = true;
+                        if press.long_press {
+                            press.long_press = false;
+                            gestures.push(Gesture::LongPressEnd);
+                        }
+                        gestures.push(Gesture::Chord(key));
+                    }
+                }
+            }
+            InputEvent::KeyUp(key) => {
+                self.other_keys.retain(|k| *k != key);
+            }
+            InputEvent::PointerMove { x, y } => {
+                self.pointer = (x, y);
+            }
+            InputEvent::PointerDown => {
+                let (x, y) = self.pointer;
+                gestures.push(Gesture::Click { x, y });
+            }
+        }
+
+        gestures.extend(self.check_long_press(now));
+        gestures
+    }
+
+    /// 定时调用，检测按住不放的长按
+    pub fn tick(&mut self) -> Option<Gesture> {
+        let now = self.clock.now();
+        self.check_long_press(now)
+    }
+
+    fn trigger_down(&mut self, now: Instant, gestures: &mut Vec<Gesture>) {
+        // 按住时系统的自动重复
+        if self.pressed.is_some() {
+            return;
+        }
+
+        let bounced = self
+            .last_release
+            .is_some_and(|release| now.duration_since(release) < BOUNCE);
+        if bounced {
+            match self.fragment.take() {
+                Some(press) => self.pressed = Some(press),
+                None => self.bouncing = true,
+            }
+            return;
+        }
+
+        self.fragment = None;
+        let chord = self.other_keys.first().copied();
+        if let Some(key) = chord {
+            gestures.push(Gesture::Chord(key));
+        }
+        self.pressed = Some(Press {
+            started: now,
+            chord: chord.is_some(),
+            long_press: false,
+        });
+    }
+
+    fn trigger_up(&mut self, now: Instant, gestures: &mut Vec<Gesture>) {
+        let Some(press) = self.pressed.take() else {
+            self.bouncing = false;
+            return;
+        };
+        self.last_release = Some(now);
+
+        if press.long_press {
+            self.last_tap = None;
+            gestures.push(Gesture::LongPressEnd);
+            return;
+        }
+
+        let duration = now.duration_since(press.started);
+        if duration < MIN_PRESS {
+            self.fragment = Some(press);
+            return;
+        }
+        if press.chord || duration >= self.settings.long_press {
+            self.last_tap = None;
+            return;
+        }
+
+        let count = match self.last_tap {
+            Some((last, count)) if now.duration_since(last) < self.settings.tap_window => count + 1,
+            _ => 1,
+        };
+        gestures.push(Gesture::Tap { count });
+        self.last_tap = if count >= self.settings.tap_count {
+            None
+        } else {
+            Some((now, count))
+        };
+    }
+
+    fn check_long_press(&mut self, now: Instant) -> Option<Gesture> {
+        let long_press = self.settings.long_press;
+        let press = self.pressed.as_mut()?;
+        if press.chord || press.long_press || now.duration_since(press.started) < long_press {
+            return None;
+        }
+        press.long_press = true;
+        Some(Gesture::LongPressStart)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct ManualClock(Rc<Cell<Instant>>);
+
+    impl ManualClock {
+        fn advance(&self, ms: u64) {
+            self.0.set(self.0.get() + Duration::from_millis(ms));
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            self.0.get()
+        }
+    }
+
+    const KEY: Key = Key::ControlRight;
+
+    fn setup() -> (GestureRecognizer<ManualClock>, ManualClock) {
+        let clock = ManualClock(Rc::new(Cell::new(Instant::now())));
+        let settings = GestureSettings {
+            key: KEY,
+            tap_count: 2,
+            tap_window: Duration::from_millis(1000),
+            long_press: Duration::from_millis(800),
+        };
+        (GestureRecognizer::new(settings, clock.clone()), clock)
+    }
+
+    /// 按下触发键，`hold` 毫秒后松开，返回松开时产生的手势
+    fn tap(
+        recognizer: &mut GestureRecognizer<ManualClock>,
+        clock: &ManualClock,
+        hold: u64,
+    ) -> Vec<Gesture> {
+        assert!(recognizer.handle(InputEvent::KeyDown(KEY)).is_empty());
+        clock.advance(hold);
+        recognizer.handle(InputEvent::KeyUp(KEY))
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let (mut recognizer, clock) = setup();
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 1 }]
+        );
+        clock.advance(300);
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 2 }]
+        );
+        // 达到配置次数后重新计数
+        clock.advance(300);
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_tap_window_edges() {
+        let (mut recognizer, clock) = setup();
+        tap(&mut recognizer, &clock, 80);
+        clock.advance(919);
+        // 两次松开间隔 999ms，仍在窗口内
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 2 }]
+        );
+
+        clock.advance(500);
+        tap(&mut recognizer, &clock, 80);
+        clock.advance(921);
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_press_bounce_is_one_long_press() {
+        let (mut recognizer, clock) = setup();
+        recognizer.handle(InputEvent::KeyDown(KEY));
+        clock.advance(3);
+        assert!(recognizer.handle(InputEvent::KeyUp(KEY)).is_empty());
+        clock.advance(4);
+        assert!(recognizer.handle(InputEvent::KeyDown(KEY)).is_empty());
+        clock.advance(800);
+        assert_eq!(recognizer.tick(), Some(Gesture::LongPressStart));
+        assert_eq!(
+            recognizer.handle(InputEvent::KeyUp(KEY)),
+            vec![Gesture::LongPressEnd]
+        );
+    }
+
+    #[test]
+    fn test_release_bounce_is_not_double_tap() {
+        let (mut recognizer, clock) = setup();
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 1 }]
+        );
+        clock.advance(5);
+        assert!(tap(&mut recognizer, &clock, 3).is_empty());
+        clock.advance(300);
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 2 }]
+        );
+    }
+
+    #[test]
+    fn test_key_repeat() {
+        let (mut recognizer, clock) = setup();
+        recognizer.handle(InputEvent::KeyDown(KEY));
+        let mut gestures = Vec::new();
+        for _ in 0..30 {
+            clock.advance(33);
+            gestures.extend(recognizer.handle(InputEvent::KeyDown(KEY)));
+        }
+        assert_eq!(gestures, vec![Gesture::LongPressStart]);
+        assert_eq!(
+            recognizer.handle(InputEvent::KeyUp(KEY)),
+            vec![Gesture::LongPressEnd]
+        );
+
+        let (mut recognizer, clock) = setup();
+        recognizer.handle(InputEvent::KeyDown(KEY));
+        clock.advance(40);
+        assert!(recognizer.handle(InputEvent::KeyDown(KEY)).is_empty());
+        clock.advance(40);
+        assert_eq!(
+            recognizer.handle(InputEvent::KeyUp(KEY)),
+            vec![Gesture::Tap { count: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_long_press_via_tick() {
+        let (mut recognizer, clock) = setup();
+        recognizer.handle(InputEvent::KeyDown(KEY));
+        clock.advance(500);
+        assert_eq!(recognizer.tick(), None);
+        clock.advance(300);
+        assert_eq!(recognizer.tick(), Some(Gesture::LongPressStart));
+        assert_eq!(recognizer.tick(), None);
+        clock.advance(100);
+        assert_eq!(
+            recognizer.handle(InputEvent::KeyUp(KEY)),
+            vec![Gesture::LongPressEnd]
+        );
+    }
+
+    #[test]
+    fn test_chord_with_other_key() {
+        let (mut recognizer, clock) = setup();
+        recognizer.handle(InputEvent::KeyDown(KEY));
+        clock.advance(50);
+        assert_eq!(
+            recognizer.handle(InputEvent::KeyDown(Key::KeyC)),
+            vec![Gesture::Chord(Key::KeyC)]
+        );
+        recognizer.handle(InputEvent::KeyUp(Key::KeyC));
+        clock.advance(50);
+        assert!(recognizer.handle(InputEvent::KeyUp(KEY)).is_empty());
+        clock.advance(900);
+        assert_eq!(recognizer.tick(), None);
+    }
+
+    #[test]
+    fn test_overlapping_modifier_held() {
+        let (mut recognizer, clock) = setup();
+        recognizer.handle(InputEvent::KeyDown(Key::ShiftLeft));
+        assert_eq!(
+            recognizer.handle(InputEvent::KeyDown(KEY)),
+            vec![Gesture::Chord(Key::ShiftLeft)]
+        );
+        clock.advance(80);
+        assert!(recognizer.handle(InputEvent::KeyUp(KEY)).is_empty());
+        recognizer.handle(InputEvent::KeyUp(Key::ShiftLeft));
+
+        clock.advance(200);
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_other_key_resets_tap_sequence() {
+        let (mut recognizer, clock) = setup();
+        tap(&mut recognizer, &clock, 80);
+        clock.advance(100);
+        recognizer.handle(InputEvent::KeyDown(Key::KeyA));
+        recognizer.handle(InputEvent::KeyUp(Key::KeyA));
+        clock.advance(100);
+        assert_eq!(
+            tap(&mut recognizer, &clock, 80),
+            vec![Gesture::Tap { count: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_chord_ends_long_press() {
+        let (mut recognizer, clock) = setup();
+        recognizer.handle(InputEvent::KeyDown(KEY));
+        clock.advance(900);
+        assert_eq!(recognizer.tick(), Some(Gesture::LongPressStart));
+        assert_eq!(
+            recognizer.handle(InputEvent::KeyDown(Key::KeyV)),
+            vec![Gesture::LongPressEnd, Gesture::Chord(Key::KeyV)]
+        );
+        assert!(recognizer.handle(InputEvent::KeyUp(KEY)).is_empty());
+    }
+
+    #[test]
+    fn test_click_position() {
+        let (mut recognizer, _) = setup();
+        recognizer.handle(InputEvent::PointerMove { x: 12.0, y: 34.0 });
+        assert_eq!(
+            recognizer.handle(InputEvent::PointerDown),
+            vec![Gesture::Click { x: 12.0, y: 34.0 }]
+        );
+    }
+
+    #[test]
+    fn test_change_trigger_key() {
+        let (mut recognizer, clock) = setup();
+        recognizer.handle(InputEvent::KeyDown(KEY));
+        let settings = GestureSettings {
+            key: Key::F8,
+            ..recognizer.settings()
+        };
+        recognizer.set_settings(settings);
+        clock.advance(80);
+        assert!(recognizer.handle(InputEvent::KeyUp(KEY)).is_empty());
+
+        clock.advance(100);
+        recognizer.handle(InputEvent::KeyDown(Key::F8));
+        clock.advance(80);
+        assert_eq!(
+            recognizer.handle(InputEvent::KeyUp(Key::F8)),
+            vec![Gesture::Tap { count: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("ControlRight"), Ok(Key::ControlRight));
+        assert_eq!(parse_key("controlright"), Ok(Key::ControlRight));
+        assert_eq!(parse_key("RightCmd"), Ok(Key::MetaRight));
+        assert_eq!(parse_key(" f8 "), Ok(Key::F8));
+        assert!(parse_key("Banana").is_err());
+        assert_eq!(canonical_key_name("esc"), Some("Escape"));
+    }
+
+    #[test]
+    fn test_gesture_validation() {
+        assert!(GestureSettings::try_from(&GestureConfig::default()).is_ok());
+
+        let config = GestureConfig {
+            tap_count: 4,
+            ..GestureConfig::default()
+        };
+        assert!(GestureSettings::try_from(&config).is_err());
+
+        let config = GestureConfig {
+            long_press_ms: 50,
+            ..GestureConfig::default()
+        };
+        assert!(GestureSettings::try_from(&config).is_err());
+
+        let config = GestureConfig {
+            key: "Nope".to_string(),
+            ..GestureConfig::default()
+        };
+        assert!(GestureSettings::try_from(&config).is_err());
+    }
+}
@@ -1,7 +1,20 @@
+pub mod bubble_placement;
 pub mod calculate_text_width;
 pub mod chat_message;
+pub mod clipboard;
+pub mod config_migration;
+pub mod coordinates;
+pub mod custom_actions;
+pub mod gesture;
 pub mod language_detection;
 pub mod log_filter;
+pub mod quick_actions;
+pub mod screen_capture;
 pub mod selecte_text;
+pub mod selection_bounds;
+pub mod text_chunker;
+pub mod text_layout;
 pub mod text_translation;
 pub mod translation_manager;
+pub mod window_geometry;
+pub mod write_back;
@@ -0,0 +1,171 @@
+//! 翻译之外的内置文本动作：解释、总结、润色、改写语气、扩写、缩写
+
+use crate::my_events::event_names;
+use crate::utils::chat_message::ChatMessage;
+use crate::utils::text_translation;
+use crate::utils::translation_manager::TranslationManager;
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickAction {
+    Explain,
+    Summarize,
+    Polish,
+    RewriteFormal,
+    RewriteCasual,
+    Expand,
+    Shorten,
+}
+
+/// 返回给前端的动作信息
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickActionInfo {
+    pub id: QuickAction,
+    pub label: &'static str,
+}
+
+impl QuickAction {
+    pub const ALL: [QuickAction; 7] = [
+        QuickAction::Explain,
+        QuickAction::Summarize,
+        QuickAction::Polish,
+        QuickAction::RewriteFormal,
+        QuickAction::RewriteCasual,
+        QuickAction::Expand,
+        QuickAction::Shorten,
+    ];
+
+    /// 动作 id，与序列化名称一致，也用于快捷键绑定
+    pub fn id(self) -> &'static str {
+        match self {
+            QuickAction::Explain => "explain",
+            QuickAction::Summarize => "summarize",
+            QuickAction::Polish => "polish",
+            QuickAction::RewriteFormal => "rewrite_formal",
+            QuickAction::RewriteCasual => "rewrite_casual",
+            QuickAction::Expand => "expand",
+            QuickAction::Shorten => "shorten",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            QuickAction::Explain => "解释",
+            QuickAction::Summarize => "总结",
+            QuickAction::Polish => "润色",
+            QuickAction::RewriteFormal => "改为正式语气",
+            QuickAction::RewriteCasual => "改为口语语气",
+            QuickAction::Expand => "扩写",
+            QuickAction::Shorten => "精简",
+        }
+    }
+
+    pub fn system_prompt(self) -> &'static str {
+        match self {
+            QuickAction::Explain => "你是一个知识渊博的助手，擅长用简洁易懂的语言解释词语和概念。",
+            QuickAction::Summarize => "你是一个擅长提炼要点的助手。",
+            QuickAction::Polish
+            | QuickAction::RewriteFormal
+            | QuickAction::RewriteCasual
+            | QuickAction::Expand
+            | QuickAction::Shorten => "你是一个专业的写作助手，修改文本时保持原文的核心含义。",
+        }
+    }
+
+    /// 用户消息模板，回答语言跟随原文
+    pub fn build_prompt(self, text: &str) -> String {
+        let instruction = match self {
+            QuickAction::Explain => "请解释以下词语或内容的含义，必要时给出例子。",
+            QuickAction::Summarize => "请用简洁的要点总结以下文本。",
+            QuickAction::Polish => {
+                "请修正以下文本中的语法和拼写错误并润色表达，只输出修改后的文本。"
+            }
+            QuickAction::RewriteFormal => {
+                "请将以下文本改写为正式、礼貌的语气，只输出改写后的文本。"
+            }
+            QuickAction::RewriteCasual => {
+                "请将以下文本改写为轻松、口语化的语气，只输出改写后的文本。"
+            }
+            QuickAction::Expand => "请扩写以下文本，补充细节使其更完整，只输出扩写后的文本。",
+            QuickAction::Shorten => "请精简以下文本，保留关键信息，只输出精简后的文本。",
+        };
+        format!("{}使用与原文相同的语言回答。\n\n{}", instruction, text)
+    }
+
+    pub fn info(self) -> QuickActionInfo {
+        QuickActionInfo {
+            id: self,
+            label: self.label(),
+        }
+    }
+}
+
+/// 在新会话中对文本执行动作
+async fn request_quick_action(
+    app_handle: &AppHandle,
+    action: QuickAction,
+    text: String,
+) -> Option<Vec<ChatMessage>> {
+    let translation_manager = app_handle.state::<TranslationManager>();
+    let _ = translation_manager
+        .create_session_with_system_prompt(action.system_prompt())
+        .await;
+
+    translation_manager
+        .translate(
+            None,
+            &action.build_prompt(&text),
+            Some(text),
+            |chat_history| {
+                let app_handle = app_handle.clone();
+                async move {
+                    let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
+                }
+            },
+        )
+        .await
+}
+
+/// 对选中文本执行动作并显示在气泡中（快捷键触发）
+pub fn run_on_selection_bubble(app_handle: &AppHandle, action: QuickAction) {
+    let app_handle = app_handle.clone();
+    async_runtime::spawn(async move {
+        let selected_text = crate::utils::selecte_text::get_selected_text_async(&app_handle).await;
+        if selected_text.is_empty() {
+            return;
+        }
+        println!("selected_text: {}", selected_text);
+        let result = request_quick_action(&app_handle, action, selected_text).await;
+        text_translation::show_in_bubble(&app_handle, result);
+    });
+}
+
+/// 对已有文本执行动作并显示在气泡中（气泡中切换动作）
+pub async fn run_on_text_bubble(app_handle: &AppHandle, action: QuickAction, text: String) {
+    let result = request_quick_action(app_handle, action, text).await;
+    text_translation::show_in_bubble(app_handle, result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_matches_serde_name() {
+        for action in QuickAction::ALL {
+            assert_eq!(
+                serde_json::to_value(action).unwrap(),
+                serde_json::Value::String(action.id().to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_prompt_contains_text() {
+        for action in QuickAction::ALL {
+            assert!(action.build_prompt("hello world").ends_with("hello world"));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! 框选屏幕区域截图，调用系统自带的截图工具
+//!
+//! macOS 使用 screencapture；Linux 依次尝试 slurp + grim、gnome-screenshot 和 spectacle；
+//! Windows 打开系统截图工具，从剪贴板读取截图后保存为 png。
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+/// 运行截图命令，返回是否正常退出；命令不存在时返回 None
+fn run(program: &str, args: &[&str]) -> Result<Option<bool>, String> {
+    match Command::new(program).args(args).status() {
+        Ok(status) => Ok(Some(status.success())),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("无法启动 {}: {}", program, e)),
+    }
+}
+
+/// 让用户框选区域并把截图保存到 `path`，用户取消时返回 false
+#[cfg(target_os = "macos")]
+pub fn capture_region(path: &Path) -> Result<bool, String> {
+    let path_arg = path.display().to_string();
+    // -i 框选，-x 不播放快门声
+    run("screencapture", &["-i", "-x", &path_arg])?.ok_or("找不到 screencapture")?;
+    Ok(path.exists())
+}
+
+#[cfg(target_os = "linux")]
+pub fn capture_region(path: &Path) -> Result<bool, String> {
+    let path_arg = path.display().to_string();
+
+    // Wayland：slurp 选区，grim 截图
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        if let Ok(output) = Command::new("slurp").output() {
+            // 按 Esc 取消选区时 slurp 返回非零
+            if !output.status.success() {
+                return Ok(false);
+            }
+            let geometry = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if run("grim", &["-g", &geometry, &path_arg])?.is_some() {
+                return Ok(path.exists());
+            }
+        }
+    }
+
+    let tools: [(&str, &[&str]); 2] = [
+        ("gnome-screenshot", &["-a", "-f"]),
+        ("spectacle", &["-r", "-b", "-n", "-o"]),
+    ];
+    for (program, args) in tools {
+        let args: Vec<&str> = args.iter().copied().chain([path_arg.as_str()]).collect();
+        if run(program, &args)?.is_some() {
+            return Ok(path.exists());
+        }
+    }
+    Err("找不到截图工具，请安装 grim 和 slurp、gnome-screenshot 或 spectacle".to_string())
+}
+
+#[cfg(target_os = "windows")]
+pub fn capture_region(path: &Path) -> Result<bool, String> {
+    use std::time::{Duration, Instant};
+
+    /// 等待用户完成截图的最长时间
+    const CAPTURE_TIMEOUT: Duration = Duration::from_secs(60);
+    const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    let previous = clipboard
+        .get_image()
+        .ok()
+        .map(|image| image.bytes.into_owned());
+
+    run("explorer.exe", &["ms-screenclip:"])?.ok_or("无法打开系统截图工具")?;
+
+    let started = Instant::now();
+    while started.elapsed() < CAPTURE_TIMEOUT {
+        std::thread::sleep(POLL_INTERVAL);
+        let Ok(image) = clipboard.get_image() else {
+            continue;
+        };
+        if previous.as_deref() == Some(image.bytes.as_ref()) {
+            continue;
+        }
+        save_png(path, image.width as u32, image.height as u32, &image.bytes)?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn capture_region(_path: &Path) -> Result<bool, String> {
+    Err("当前系统不支持截图".to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn save_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), String> {
+    let file = std::fs::File::create(path).map_err(|e| format!("保存截图失败: {}", e))?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(rgba))
+        .map_err(|e| format!("保存截图失败: {}", e))
+}
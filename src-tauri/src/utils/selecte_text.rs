@@ -1,14 +1,256 @@
+use crate::my_config::{self, SelectionConfig, SelectionStrategy};
+use crate::utils::clipboard::{self, ClipboardBackend, SystemClipboard};
 use selection;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tauri::{async_runtime, AppHandle, Runtime};
 
-pub fn get_selected_text() -> String {
-    let selected_text = selection::get_text();
+/// 轮询剪贴板的间隔
+const CLIPBOARD_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 触发系统“复制”操作
+pub trait CopyTrigger {
+    fn trigger_copy(&self) -> Result<(), String>;
+}
+
+/// 通过模拟 Ctrl/Cmd+C 触发复制
+pub struct KeyboardCopyTrigger;
+
+impl CopyTrigger for KeyboardCopyTrigger {
+    fn trigger_copy(&self) -> Result<(), String> {
+        clipboard::simulate_copy()
+    }
+}
+
+/// 获取选中文本，在阻塞线程中进行，捕获过程中的等待和剪贴板轮询不占用异步运行时的线程
+pub async fn get_selected_text_async<R: Runtime>(app: &AppHandle<R>) -> String {
+    let app = app.clone();
+    async_runtime::spawn_blocking(move || get_selected_text(&app))
+        .await
+        .unwrap_or_default()
+}
+
+/// 获取选中文本，会阻塞当前线程，异步代码中使用 `get_selected_text_async`
+pub fn get_selected_text<R: Runtime>(app: &AppHandle<R>) -> String {
+    let config = my_config::get_global_config(app)
+        .map(|config| config.selection)
+        .unwrap_or_default();
+
+    let mut system_clipboard = if config.strategies.contains(&SelectionStrategy::Clipboard) {
+        SystemClipboard::new().map_err(|e| eprintln!("{}", e)).ok()
+    } else {
+        None
+    };
+
+    let text = capture_selection(
+        &config,
+        selection::get_text,
+        system_clipboard.as_mut(),
+        &KeyboardCopyTrigger,
+    );
+    // 模拟复制时目标应用写入的选中文本也是应用自身引起的变化，剪贴板监听不应再翻译
+    if system_clipboard.is_some() && !text.is_empty() {
+        clipboard::record_own_text(&text);
+    }
+    text
+}
+
+/// 按配置的策略顺序依次尝试获取选中文本，返回第一个非空结果
+pub fn capture_selection<A, C, T>(
+    config: &SelectionConfig,
+    accessibility: A,
+    mut clipboard: Option<&mut C>,
+    trigger: &T,
+) -> String
+where
+    A: Fn() -> String,
+    C: ClipboardBackend,
+    T: CopyTrigger,
+{
+    for strategy in &config.strategies {
+        let text = match strategy {
+            SelectionStrategy::Accessibility => read_accessibility(&accessibility, config),
+            SelectionStrategy::Clipboard => match clipboard.as_deref_mut() {
+                Some(clipboard) => read_via_clipboard(clipboard, trigger, config),
+                None => String::new(),
+            },
+        };
+        if !text.trim().is_empty() {
+            return text;
+        }
+    }
+    String::new()
+}
+
+fn read_accessibility<A: Fn() -> String>(accessibility: &A, config: &SelectionConfig) -> String {
+    let selected_text = accessibility();
     if !selected_text.is_empty() {
         return selected_text;
     }
 
-    sleep(Duration::from_millis(100));
+    sleep(Duration::from_millis(config.accessibility_retry_delay_ms));
+
+    accessibility()
+}
+
+/// 模拟复制读取选区，完成后恢复用户原有的剪贴板内容
+fn read_via_clipboard<C: ClipboardBackend, T: CopyTrigger>(
+    clipboard: &mut C,
+    trigger: &T,
+    config: &SelectionConfig,
+) -> String {
+    let previous = clipboard.snapshot();
+
+    // 先清空剪贴板，才能区分“复制到了新内容”与“剪贴板里的旧内容”
+    if let Err(e) = clipboard.clear() {
+        eprintln!("{}", e);
+        return String::new();
+    }
+
+    let mut text = String::new();
+    match trigger.trigger_copy() {
+        Ok(()) => {
+            let deadline = Instant::now() + Duration::from_millis(config.clipboard_timeout_ms);
+            loop {
+                if let Some(copied) = clipboard.read_text().filter(|t| !t.is_empty()) {
+                    text = copied;
+                    break;
+                }
+                if Instant::now() >= deadline {
+                    break;
+                }
+                sleep(CLIPBOARD_POLL_INTERVAL);
+            }
+        }
+        Err(e) => eprintln!("{}", e),
+    }
+
+    if config.restore_clipboard {
+        if let Err(e) = clipboard.restore(&previous) {
+            eprintln!("{}", e);
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clipboard::testing::FakeClipboard;
+    use crate::utils::clipboard::ClipboardContent;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// 模拟复制：把“选中文本”写入假剪贴板
+    struct FakeTrigger {
+        clipboard: Rc<RefCell<ClipboardContent>>,
+        selection: Option<String>,
+    }
+
+    impl CopyTrigger for FakeTrigger {
+        fn trigger_copy(&self) -> Result<(), String> {
+            if let Some(selection) = &self.selection {
+                *self.clipboard.borrow_mut() = ClipboardContent::Text(selection.clone());
+            }
+            Ok(())
+        }
+    }
+
+    fn fixture(
+        initial: ClipboardContent,
+        selection: Option<&str>,
+    ) -> (Rc<RefCell<ClipboardContent>>, FakeClipboard, FakeTrigger) {
+        let shared = Rc::new(RefCell::new(initial));
+        let clipboard = FakeClipboard(shared.clone());
+        let trigger = FakeTrigger {
+            clipboard: shared.clone(),
+            selection: selection.map(str::to_string),
+        };
+        (shared, clipboard, trigger)
+    }
+
+    fn test_config() -> SelectionConfig {
+        SelectionConfig {
+            accessibility_retry_delay_ms: 0,
+            clipboard_timeout_ms: 50,
+            ..SelectionConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_accessibility_first() {
+        let (shared, mut clipboard, trigger) =
+            fixture(ClipboardContent::Text("old".into()), Some("copied"));
+        let text = capture_selection(
+            &test_config(),
+            || "selected".to_string(),
+            Some(&mut clipboard),
+            &trigger,
+        );
+        assert_eq!(text, "selected");
+        assert_eq!(*shared.borrow(), ClipboardContent::Text("old".into()));
+    }
+
+    #[test]
+    fn test_clipboard_fallback_restores_text() {
+        let (shared, mut clipboard, trigger) =
+            fixture(ClipboardContent::Text("old".into()), Some("copied"));
+        let text = capture_selection(&test_config(), String::new, Some(&mut clipboard), &trigger);
+        assert_eq!(text, "copied");
+        assert_eq!(*shared.borrow(), ClipboardContent::Text("old".into()));
+    }
+
+    #[test]
+    fn test_clipboard_fallback_restores_image() {
+        let image = ClipboardContent::Image {
+            width: 1,
+            height: 1,
+            bytes: vec![255, 0, 0, 255],
+        };
+        let (shared, mut clipboard, trigger) = fixture(image.clone(), Some("copied"));
+        let text = capture_selection(&test_config(), String::new, Some(&mut clipboard), &trigger);
+        assert_eq!(text, "copied");
+        assert_eq!(*shared.borrow(), image);
+    }
+
+    #[test]
+    fn test_nothing_copied_does_not_return_stale_clipboard() {
+        let (shared, mut clipboard, trigger) = fixture(ClipboardContent::Text("old".into()), None);
+        let text = capture_selection(&test_config(), String::new, Some(&mut clipboard), &trigger);
+        assert_eq!(text, "");
+        assert_eq!(*shared.borrow(), ClipboardContent::Text("old".into()));
+    }
 
-    selection::get_text()
+    #[test]
+    fn test_strategy_order() {
+        let (_, mut clipboard, trigger) = fixture(ClipboardContent::Empty, Some("copied"));
+        let config = SelectionConfig {
+            strategies: vec![
+                SelectionStrategy::Clipboard,
+                SelectionStrategy::Accessibility,
+            ],
+            ..test_config()
+        };
+        let text = capture_selection(
+            &config,
+            || "selected".to_string(),
+            Some(&mut clipboard),
+            &trigger,
+        );
+        assert_eq!(text, "copied");
+    }
+
+    #[test]
+    fn test_restore_disabled() {
+        let (shared, mut clipboard, trigger) =
+            fixture(ClipboardContent::Text("old".into()), Some("copied"));
+        let config = SelectionConfig {
+            restore_clipboard: false,
+            ..test_config()
+        };
+        let text = capture_selection(&config, String::new, Some(&mut clipboard), &trigger);
+        assert_eq!(text, "copied");
+        assert_eq!(*shared.borrow(), ClipboardContent::Text("copied".into()));
+    }
 }
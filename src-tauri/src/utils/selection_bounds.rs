@@ -0,0 +1,210 @@
+//! 通过辅助功能读取选中文本在屏幕上的矩形，用作气泡的锚点
+//!
+//! 目前只支持 macOS（AXBoundsForRange），其他平台或应用不提供选区位置时返回 None，
+//! 由调用方退回到鼠标位置。
+
+use crate::utils::coordinates::{MonitorInfo, Rect};
+
+/// 选区矩形（物理像素）；读取不到、选区为空或不在任何显示器上时返回 None
+pub fn selection_bounds(monitors: &[MonitorInfo]) -> Option<Rect> {
+    points_to_physical(monitors, accessibility_selection_rect()?)
+}
+
+/// 把系统的全局点坐标换算为物理像素
+///
+/// macOS 的显示器物理位置由点坐标乘以该显示器的缩放比例得到，因此按矩形中心所在的
+/// 显示器换算。宽或高为 0 的矩形（如只有光标、没有选区）视为无效。
+fn points_to_physical(monitors: &[MonitorInfo], rect: Rect) -> Option<Rect> {
+    if rect.width <= 0.0 || rect.height <= 0.0 {
+        return None;
+    }
+    let (cx, cy) = rect.center();
+    let monitor = monitors.iter().find(|m| {
+        let scale = m.scale_factor;
+        Rect::new(
+            m.bounds.x / scale,
+            m.bounds.y / scale,
+            m.bounds.width / scale,
+            m.bounds.height / scale,
+        )
+        .contains(cx, cy)
+    })?;
+    let scale = monitor.scale_factor;
+    Some(Rect::new(
+        rect.x * scale,
+        rect.y * scale,
+        rect.width * scale,
+        rect.height * scale,
+    ))
+}
+
+#[cfg(target_os = "macos")]
+fn accessibility_selection_rect() -> Option<Rect> {
+    macos::selection_rect()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn accessibility_selection_rect() -> Option<Rect> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::Rect;
+    use std::ffi::{c_char, c_void, CString};
+    use std::ptr;
+
+    type CFTypeRef = *const c_void;
+    type AXError = i32;
+
+    const AX_ERROR_SUCCESS: AXError = 0;
+    const AX_VALUE_CG_RECT_TYPE: u32 = 3;
+    const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    /// 目标应用无响应时最多等待的秒数，避免卡住气泡的显示
+    const MESSAGING_TIMEOUT: f32 = 0.2;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> CFTypeRef;
+        fn AXUIElementSetMessagingTimeout(element: CFTypeRef, timeout: f32) -> AXError;
+        fn AXUIElementCopyAttributeValue(
+            element: CFTypeRef,
+            attribute: CFTypeRef,
+            value: *mut CFTypeRef,
+        ) -> AXError;
+        fn AXUIElementCopyParameterizedAttributeValue(
+            element: CFTypeRef,
+            attribute: CFTypeRef,
+            parameter: CFTypeRef,
+            value: *mut CFTypeRef,
+        ) -> AXError;
+        fn AXValueGetValue(value: CFTypeRef, value_type: u32, out: *mut c_void) -> bool;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            allocator: CFTypeRef,
+            c_str: *const c_char,
+            encoding: u32,
+        ) -> CFTypeRef;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    /// 持有 CoreFoundation 对象，离开作用域时释放
+    struct Owned(CFTypeRef);
+
+    impl Owned {
+        fn new(value: CFTypeRef) -> Option<Self> {
+            (!value.is_null()).then_some(Self(value))
+        }
+    }
+
+    impl Drop for Owned {
+        fn drop(&mut self) {
+            unsafe { CFRelease(self.0) }
+        }
+    }
+
+    fn cf_string(text: &str) -> Option<Owned> {
+        let text = CString::new(text).ok()?;
+        Owned::new(unsafe {
+            CFStringCreateWithCString(ptr::null(), text.as_ptr(), CF_STRING_ENCODING_UTF8)
+        })
+    }
+
+    fn copy_attribute(element: &Owned, name: &str) -> Option<Owned> {
+        let name = cf_string(name)?;
+        let mut value = ptr::null();
+        let error = unsafe { AXUIElementCopyAttributeValue(element.0, name.0, &mut value) };
+        if error != AX_ERROR_SUCCESS {
+            return None;
+        }
+        Owned::new(value)
+    }
+
+    /// 当前焦点控件中选区的矩形，全局点坐标，原点在主屏左上角
+    pub fn selection_rect() -> Option<Rect> {
+        let system = Owned::new(unsafe { AXUIElementCreateSystemWide() })?;
+        // 对系统级元素设置的超时对所有元素生效
+        unsafe { AXUIElementSetMessagingTimeout(system.0, MESSAGING_TIMEOUT) };
+        let focused = copy_attribute(&system, "AXFocusedUIElement")?;
+        let range = copy_attribute(&focused, "AXSelectedTextRange")?;
+
+        let attribute = cf_string("AXBoundsForRange")?;
+        let mut value = ptr::null();
+        let error = unsafe {
+            AXUIElementCopyParameterizedAttributeValue(focused.0, attribute.0, range.0, &mut value)
+        };
+        if error != AX_ERROR_SUCCESS {
+            return None;
+        }
+        let value = Owned::new(value)?;
+
+        let mut rect = CGRect::default();
+        let ok = unsafe {
+            AXValueGetValue(
+                value.0,
+                AX_VALUE_CG_RECT_TYPE,
+                &mut rect as *mut CGRect as *mut c_void,
+            )
+        };
+        ok.then(|| Rect::new(rect.x, rect.y, rect.width, rect.height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 主屏 1x 1920x1080，右侧 2x 屏（点坐标 1920x1080，物理位置为点坐标乘以 2）
+    fn monitors() -> Vec<MonitorInfo> {
+        vec![
+            MonitorInfo {
+                bounds: Rect::new(0.0, 0.0, 1920.0, 1080.0),
+                work_area: Rect::new(0.0, 0.0, 1920.0, 1040.0),
+                scale_factor: 1.0,
+            },
+            MonitorInfo {
+                bounds: Rect::new(3840.0, 0.0, 3840.0, 2160.0),
+                work_area: Rect::new(3840.0, 50.0, 3840.0, 2110.0),
+                scale_factor: 2.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_points_to_physical() {
+        let monitors = monitors();
+        let rect = Rect::new(100.0, 200.0, 80.0, 20.0);
+        assert_eq!(points_to_physical(&monitors, rect), Some(rect));
+        assert_eq!(
+            points_to_physical(&monitors, Rect::new(2000.0, 100.0, 80.0, 20.0)),
+            Some(Rect::new(4000.0, 200.0, 160.0, 40.0))
+        );
+    }
+
+    #[test]
+    fn test_invalid_rects() {
+        let monitors = monitors();
+        // 只有光标没有选区
+        assert_eq!(
+            points_to_physical(&monitors, Rect::new(100.0, 200.0, 0.0, 20.0)),
+            None
+        );
+        // 不在任何显示器上
+        assert_eq!(
+            points_to_physical(&monitors, Rect::new(-500.0, -500.0, 80.0, 20.0)),
+            None
+        );
+    }
+}
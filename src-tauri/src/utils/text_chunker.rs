@@ -0,0 +1,324 @@
+//! 长文本分块：按段落 / 句子边界切分到 token 预算内，供并发翻译使用
+
+/// 单个分块
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChunk {
+    /// 分块在原文中的顺序
+    pub index: usize,
+    /// 分块正文（不含末尾空白）
+    pub text: String,
+    /// 原文中紧跟该分块的空白，重新拼接译文时原样保留
+    pub separator: String,
+}
+
+/// 粗略估算 token 数：CJK 字符约 1 token/字，其余字符约 4 字符/token
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut cjk = 0;
+    let mut other: usize = 0;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    cjk + other.div_ceil(4)
+}
+
+/// 将文本切分为不超过 `max_tokens` 的分块
+///
+/// 优先按空行分段，段落超出预算时按句子切分，句子仍超出时按词（拉丁文）或按字（CJK）硬切。
+pub fn split_into_chunks(text: &str, max_tokens: usize) -> Vec<TextChunk> {
+    let max_tokens = max_tokens.max(1);
+    let text = text.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let mut units: Vec<(String, String)> = Vec::new();
+    for (paragraph, paragraph_sep) in split_paragraphs(text) {
+        if estimate_tokens(paragraph) <= max_tokens {
+            units.push((paragraph.to_string(), paragraph_sep.to_string()));
+            continue;
+        }
+        let sentences = split_sentences(paragraph);
+        let last = sentences.len() - 1;
+        for (i, (sentence, sentence_sep)) in sentences.into_iter().enumerate() {
+            let sep = if i == last {
+                paragraph_sep
+            } else {
+                sentence_sep
+            };
+            if estimate_tokens(sentence) <= max_tokens {
+                units.push((sentence.to_string(), sep.to_string()));
+            } else {
+                let pieces = hard_split(sentence, max_tokens);
+                let last_piece = pieces.len() - 1;
+                for (j, (piece, piece_sep)) in pieces.into_iter().enumerate() {
+                    let piece_sep = if j == last_piece { sep } else { piece_sep };
+                    units.push((piece.to_string(), piece_sep.to_string()));
+                }
+            }
+        }
+    }
+
+    pack_units(units, max_tokens)
+}
+
+/// 按分块顺序拼接译文，保留原文中的分隔空白
+pub fn join_chunks(chunks: &[TextChunk], translations: &[String]) -> String {
+    let mut result = String::new();
+    for (chunk, translation) in chunks.iter().zip(translations) {
+        result.push_str(translation.trim());
+        result.push_str(&chunk.separator);
+    }
+    result.trim_end().to_string()
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}' |   // 基本汉字
+        '\u{3400}'..='\u{4DBF}' |   // 扩展A
+        '\u{20000}'..='\u{2CEAF}' | // 扩展B-E
+        '\u{F900}'..='\u{FAFF}' |   // 兼容汉字
+        '\u{3000}'..='\u{303F}' |   // CJK标点
+        '\u{3040}'..='\u{30FF}' |   // 平假名、片假名
+        '\u{AC00}'..='\u{D7AF}' |   // 韩文音节
+        '\u{FF01}'..='\u{FF5E}'     // 全角ASCII
+    )
+}
+
+fn is_cjk_terminator(c: char) -> bool {
+    matches!(c, '。' | '！' | '？' | '；' | '…')
+}
+
+fn is_latin_terminator(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | ';')
+}
+
+fn is_closing_mark(c: char) -> bool {
+    matches!(
+        c,
+        '"' | '\'' | ')' | ']' | '”' | '’' | '」' | '』' | '）' | '》'
+    )
+}
+
+/// 按空行切分段落，返回 (段落, 段落后的空白)
+fn split_paragraphs(text: &str) -> Vec<(&str, &str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut search = 0;
+
+    while let Some(offset) = text[search..].find('\n') {
+        let newline = search + offset;
+        let run_end = text[newline..]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(text.len(), |o| newline + o);
+
+        if text[newline..run_end].matches('\n').count() >= 2 {
+            let content_end = start + text[start..newline].trim_end().len();
+            parts.push((&text[start..content_end], &text[content_end..run_end]));
+            start = run_end;
+        }
+
+        if run_end >= text.len() {
+            break;
+        }
+        search = run_end;
+    }
+
+    if start < text.len() {
+        let content_end = start + text[start..].trim_end().len();
+        parts.push((&text[start..content_end], &text[content_end..]));
+    }
+
+    parts
+}
+
+/// 按句末标点切分句子，返回 (句子, 句子后的空白)
+fn split_sentences(text: &str) -> Vec<(&str, &str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let cjk = is_cjk_terminator(c);
+        if !cjk && !is_latin_terminator(c) {
+            continue;
+        }
+
+        // 句末标点后可能跟随引号、括号等闭合符号
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if is_closing_mark(next) || is_cjk_terminator(next) || is_latin_terminator(next) {
+                end = j + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let mut sep_end = end;
+        while let Some(&(j, next)) = chars.peek() {
+            if next.is_whitespace() {
+                sep_end = j + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        // 拉丁句号后必须跟空白才算句子边界，避免切开 3.14 或 example.com
+        if cjk || sep_end > end || end == text.len() {
+            parts.push((&text[start..end], &text[end..sep_end]));
+            start = sep_end;
+        }
+    }
+
+    if start < text.len() {
+        parts.push((&text[start..], ""));
+    }
+
+    parts
+}
+
+/// 超出预算的单句：拉丁文按词切分，CJK 或超长单词按字切分
+fn hard_split(text: &str, max_tokens: usize) -> Vec<(&str, &str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut last_break: Option<(usize, usize)> = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c.is_whitespace() {
+            let mut sep_end = i + c.len_utf8();
+            while let Some(&(j, next)) = chars.peek() {
+                if next.is_whitespace() {
+                    sep_end = j + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            last_break = Some((i, sep_end));
+            continue;
+        }
+
+        let end = i + c.len_utf8();
+        if estimate_tokens(&text[start..end]) <= max_tokens {
+            continue;
+        }
+
+        match last_break {
+            Some((break_at, sep_end)) if break_at > start => {
+                parts.push((&text[start..break_at], &text[break_at..sep_end]));
+                start = sep_end;
+            }
+            _ => {
+                parts.push((&text[start..i], ""));
+                start = i;
+            }
+        }
+        last_break = None;
+    }
+
+    if start < text.len() {
+        parts.push((&text[start..], ""));
+    }
+
+    parts
+}
+
+/// 贪心合并相邻单元，直到接近预算
+fn pack_units(units: Vec<(String, String)>, max_tokens: usize) -> Vec<TextChunk> {
+    let mut chunks: Vec<TextChunk> = Vec::new();
+    let mut current = String::new();
+    let mut current_sep = String::new();
+
+    for (unit, sep) in units {
+        if !current.is_empty() {
+            let candidate = format!("{}{}{}", current, current_sep, unit);
+            if estimate_tokens(&candidate) <= max_tokens {
+                current = candidate;
+                current_sep = sep;
+                continue;
+            }
+            chunks.push(TextChunk {
+                index: chunks.len(),
+                text: std::mem::take(&mut current),
+                separator: std::mem::take(&mut current_sep),
+            });
+        }
+        current = unit;
+        current_sep = sep;
+    }
+
+    if !current.is_empty() {
+        chunks.push(TextChunk {
+            index: chunks.len(),
+            text: current,
+            separator: current_sep,
+        });
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reassemble(chunks: &[TextChunk]) -> String {
+        let texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+        join_chunks(chunks, &texts)
+    }
+
+    #[test]
+    fn test_short_text_single_chunk() {
+        let chunks = split_into_chunks("Hello world.", 100);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, "Hello world.");
+    }
+
+    #[test]
+    fn test_paragraph_boundaries() {
+        let text = "First paragraph here.\n\nSecond paragraph here.\n\n\nThird one.";
+        let chunks = split_into_chunks(text, 6);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1].text, "Second paragraph here.");
+        assert_eq!(reassemble(&chunks), text);
+    }
+
+    #[test]
+    fn test_cjk_sentences() {
+        let text = "今天天气很好。我们去公园散步吧！你觉得怎么样？";
+        let chunks = split_into_chunks(text, 10);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| estimate_tokens(&c.text) <= 10));
+        assert!(chunks[0].text.ends_with('。'));
+        assert_eq!(reassemble(&chunks), text);
+    }
+
+    #[test]
+    fn test_latin_decimal_not_split() {
+        let parts = split_sentences("Pi is 3.14 roughly. Next sentence.");
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0], ("Pi is 3.14 roughly.", " "));
+    }
+
+    #[test]
+    fn test_oversized_sentence_hard_split() {
+        let text = "word ".repeat(100);
+        let chunks = split_into_chunks(&text, 10);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| estimate_tokens(&c.text) <= 10));
+        assert_eq!(reassemble(&chunks), text.trim());
+    }
+
+    #[test]
+    fn test_join_keeps_order() {
+        let chunks = split_into_chunks("A one.\n\nB two.", 3);
+        let translated = vec!["甲一。".to_string(), "乙二。".to_string()];
+        assert_eq!(join_chunks(&chunks, &translated), "甲一。\n\n乙二。");
+    }
+}
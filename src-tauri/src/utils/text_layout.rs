@@ -0,0 +1,360 @@
+//! 气泡文本排版：按字形宽度换行，拉丁文按单词、CJK 按字符断行
+
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use std::sync::OnceLock;
+
+/// 前端气泡使用的字号
+pub const FONT_SIZE: f64 = 14.0;
+/// 前端气泡的行高
+pub const LINE_HEIGHT: f64 = 20.0;
+
+static FONT: OnceLock<FontArc> = OnceLock::new();
+
+/// 字符宽度来源
+pub trait GlyphMetrics {
+    fn advance(&self, c: char) -> f64;
+}
+
+/// 加载前端使用的字体，之后的排版使用真实字形宽度
+pub fn init_font(bytes: Vec<u8>) -> Result<(), String> {
+    let font = FontArc::try_from_vec(bytes).map_err(|e| format!("字体加载失败: {}", e))?;
+    let _ = FONT.set(font);
+    Ok(())
+}
+
+/// 当前可用的字符宽度：已加载字体时使用字形宽度，否则按字符类别估算
+pub fn default_metrics() -> Box<dyn GlyphMetrics> {
+    match FONT.get() {
+        Some(font) => Box::new(FontMetrics::new(font.clone(), FONT_SIZE)),
+        None => Box::new(RatioMetrics),
+    }
+}
+
+/// 基于字体文件的字形宽度，字体中缺失的字符（如 CJK）按字符类别估算
+pub struct FontMetrics {
+    font: FontArc,
+    scale: PxScale,
+}
+
+impl FontMetrics {
+    pub fn new(font: FontArc, font_size: f64) -> Self {
+        // PxScale 是字体高度（ascent - descent），需由 em 大小换算
+        let units_per_em = font.units_per_em().unwrap_or(1000.0);
+        let scale = PxScale::from(font_size as f32 * font.height_unscaled() / units_per_em);
+        Self { font, scale }
+    }
+}
+
+impl GlyphMetrics for FontMetrics {
+    fn advance(&self, c: char) -> f64 {
+        let glyph_id = self.font.glyph_id(c);
+        if glyph_id.0 == 0 {
+            return RatioMetrics.advance(c);
+        }
+        self.font.as_scaled(self.scale).h_advance(glyph_id) as f64
+    }
+}
+
+/// 没有字体文件时按字符类别估算宽度
+pub struct RatioMetrics;
+
+const CJK_WIDTH_RATIO: f64 = 1.0;
+const ASCII_WIDE_RATIO: f64 = 0.7;
+const ASCII_NORMAL_RATIO: f64 = 0.55;
+const ASCII_NARROW_RATIO: f64 = 0.35;
+const SPACE_RATIO: f64 = 0.4;
+const TAB_WIDTH_RATIO: f64 = 2.0;
+
+impl GlyphMetrics for RatioMetrics {
+    fn advance(&self, c: char) -> f64 {
+        let ratio = match c {
+            _ if is_wide(c) => CJK_WIDTH_RATIO,
+            '\t' => TAB_WIDTH_RATIO,
+            ' ' => SPACE_RATIO,
+            'W' | 'M' | 'w' | 'm' | '@' | '%' | '#' | '&' | '$' => ASCII_WIDE_RATIO,
+            'i' | 'l' | 'I' | 'j' | 't' | 'f' | 'r' | '.' | ',' | ':' | ';' | '\'' | '!' | '|'
+            | '`' => ASCII_NARROW_RATIO,
+            _ => ASCII_NORMAL_RATIO,
+        };
+        FONT_SIZE * ratio
+    }
+}
+
+/// 全角字符：CJK 表意文字、全角标点、假名、韩文和常见表情符号
+fn is_wide(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{20000}'..='\u{2CEAF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{2F800}'..='\u{2FA1F}'
+        | '\u{FF01}'..='\u{FF5E}'
+        | '\u{3000}'..='\u{303F}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{1100}'..='\u{11FF}'
+        | '\u{1F300}'..='\u{1F9FF}'
+        | '\u{2600}'..='\u{27BF}')
+}
+
+/// 每个全角字符后都可以断行
+fn breaks_per_char(c: char) -> bool {
+    is_wide(c)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    pub lines: Vec<String>,
+    /// 最宽一行的宽度，不含行尾空白
+    pub width: f64,
+    pub height: f64,
+}
+
+/// 最小断行单位
+enum Unit<'a> {
+    Word(&'a str),
+    Space(&'a str),
+}
+
+fn split_units(paragraph: &str) -> Vec<Unit<'_>> {
+    let mut units = Vec::new();
+    let mut start = 0;
+    let mut in_space = false;
+
+    for (i, c) in paragraph.char_indices() {
+        let is_space = c.is_whitespace();
+        if breaks_per_char(c) {
+            if i > start {
+                units.push(if in_space {
+                    Unit::Space(&paragraph[start..i])
+                } else {
+                    Unit::Word(&paragraph[start..i])
+                });
+            }
+            let end = i + c.len_utf8();
+            units.push(Unit::Word(&paragraph[i..end]));
+            start = end;
+            in_space = false;
+            continue;
+        }
+        if i > start && is_space != in_space {
+            units.push(if in_space {
+                Unit::Space(&paragraph[start..i])
+            } else {
+                Unit::Word(&paragraph[start..i])
+            });
+            start = i;
+        }
+        in_space = is_space;
+    }
+    if start < paragraph.len() {
+        units.push(if in_space {
+            Unit::Space(&paragraph[start..])
+        } else {
+            Unit::Word(&paragraph[start..])
+        });
+    }
+    units
+}
+
+fn measure<M: GlyphMetrics + ?Sized>(text: &str, metrics: &M) -> f64 {
+    text.chars().map(|c| metrics.advance(c)).sum()
+}
+
+/// 逐行累加，`width` 为去掉行尾空白后的宽度
+struct LineBuilder {
+    lines: Vec<(String, f64)>,
+    text: String,
+    width: f64,
+    trailing_space: f64,
+}
+
+impl LineBuilder {
+    fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            text: String::new(),
+            width: 0.0,
+            trailing_space: 0.0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn push_word(&mut self, word: &str, width: f64) {
+        self.text.push_str(word);
+        self.width += self.trailing_space + width;
+        self.trailing_space = 0.0;
+    }
+
+    fn push_space(&mut self, space: &str, width: f64) {
+        // 行首空白丢弃
+        if self.is_empty() {
+            return;
+        }
+        self.text.push_str(space);
+        self.trailing_space += width;
+    }
+
+    fn finish_line(&mut self) {
+        let text = std::mem::take(&mut self.text);
+        self.lines.push((text.trim_end().to_string(), self.width));
+        self.width = 0.0;
+        self.trailing_space = 0.0;
+    }
+}
+
+/// 在 `max_width` 内排版文本，超长单词按字符断开
+pub fn layout_text<M: GlyphMetrics + ?Sized>(
+    text: &str,
+    metrics: &M,
+    max_width: f64,
+) -> TextLayout {
+    let mut builder = LineBuilder::new();
+
+    for paragraph in text.split('\n') {
+        let paragraph = paragraph.strip_suffix('\r').unwrap_or(paragraph);
+        for unit in split_units(paragraph) {
+            match unit {
+                Unit::Space(space) => builder.push_space(space, measure(space, metrics)),
+                Unit::Word(word) => {
+                    let width = measure(word, metrics);
+                    if builder.width + builder.trailing_space + width <= max_width {
+                        builder.push_word(word, width);
+                        continue;
+                    }
+                    if !builder.is_empty() {
+                        builder.finish_line();
+                    }
+                    if width <= max_width {
+                        builder.push_word(word, width);
+                        continue;
+                    }
+                    for c in word.chars() {
+                        let advance = metrics.advance(c);
+                        if !builder.is_empty() && builder.width + advance > max_width {
+                            builder.finish_line();
+                        }
+                        builder.push_word(c.encode_utf8(&mut [0; 4]), advance);
+                    }
+                }
+            }
+        }
+        builder.finish_line();
+    }
+
+    let width = builder
+        .lines
+        .iter()
+        .map(|(_, width)| *width)
+        .fold(0.0, f64::max);
+    let height = builder.lines.len() as f64 * LINE_HEIGHT;
+    TextLayout {
+        lines: builder.lines.into_iter().map(|(line, _)| line).collect(),
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 等宽字体：拉丁字符 10px，全角字符 20px
+    struct Monospace;
+
+    impl GlyphMetrics for Monospace {
+        fn advance(&self, c: char) -> f64 {
+            if is_wide(c) {
+                20.0
+            } else {
+                10.0
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_line() {
+        let layout = layout_text("hello world", &Monospace, 200.0);
+        assert_eq!(layout.lines, vec!["hello world"]);
+        assert_eq!(layout.width, 110.0);
+        assert_eq!(layout.height, LINE_HEIGHT);
+    }
+
+    #[test]
+    fn test_wraps_latin_by_word() {
+        let layout = layout_text("the quick brown fox", &Monospace, 100.0);
+        assert_eq!(layout.lines, vec!["the quick", "brown fox"]);
+        assert_eq!(layout.width, 90.0);
+        assert_eq!(layout.height, 2.0 * LINE_HEIGHT);
+    }
+
+    #[test]
+    fn test_wraps_cjk_by_char() {
+        let layout = layout_text("你好世界欢迎你", &Monospace, 80.0);
+        assert_eq!(layout.lines, vec!["你好世界", "欢迎你"]);
+        assert_eq!(layout.width, 80.0);
+    }
+
+    #[test]
+    fn test_mixed_text() {
+        let layout = layout_text("用 Rust 编写", &Monospace, 100.0);
+        assert_eq!(layout.lines, vec!["用 Rust 编", "写"]);
+        assert_eq!(layout.width, 100.0);
+    }
+
+    #[test]
+    fn test_long_word_breaks_per_char() {
+        let layout = layout_text("abcdefghij", &Monospace, 40.0);
+        assert_eq!(layout.lines, vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn test_explicit_newlines() {
+        let layout = layout_text("one\n\ntwo", &Monospace, 200.0);
+        assert_eq!(layout.lines, vec!["one", "", "two"]);
+        assert_eq!(layout.height, 3.0 * LINE_HEIGHT);
+    }
+
+    /// 随应用打包的气泡字体，每 em 2048 个单位
+    fn bubble_font() -> FontMetrics {
+        let font = FontArc::try_from_slice(include_bytes!("../../fonts/DejaVuSans.ttf")).unwrap();
+        FontMetrics::new(font, FONT_SIZE)
+    }
+
+    fn em_units(units: f64) -> f64 {
+        units * FONT_SIZE / 2048.0
+    }
+
+    #[test]
+    fn test_font_metrics() {
+        let metrics = bubble_font();
+        assert!((metrics.advance('a') - em_units(1255.0)).abs() < 0.01);
+        assert!((metrics.advance('i') - em_units(569.0)).abs() < 0.01);
+        assert!((metrics.advance('W') - em_units(2025.0)).abs() < 0.01);
+        // 字体中没有 CJK 字形，按字符类别估算
+        assert_eq!(metrics.advance('你'), RatioMetrics.advance('你'));
+    }
+
+    #[test]
+    fn test_layout_with_font() {
+        let metrics = bubble_font();
+        // hello 4949、空格 651、world 5639 个单位
+        let layout = layout_text("hello world", &metrics, 200.0);
+        assert_eq!(layout.lines, vec!["hello world"]);
+        assert!((layout.width - em_units(11239.0)).abs() < 0.01);
+
+        let layout = layout_text("hello world", &metrics, 60.0);
+        assert_eq!(layout.lines, vec!["hello", "world"]);
+        assert!((layout.width - em_units(5639.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_ratio_metrics() {
+        assert_eq!(RatioMetrics.advance('你'), FONT_SIZE);
+        assert!(RatioMetrics.advance('W') > RatioMetrics.advance('a'));
+        assert!(RatioMetrics.advance('a') > RatioMetrics.advance('i'));
+    }
+}
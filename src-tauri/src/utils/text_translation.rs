@@ -1,127 +1,353 @@
+use crate::my_dictionary::{self, DictionaryResult};
 use crate::my_events::event_names;
+use crate::my_tts;
 use crate::my_windows;
-use crate::utils::{self, translation_manager};
-use tauri::AppHandle;
+use crate::utils::chat_message::ChatMessage;
+use crate::utils::translation_manager::StreamDelta;
+use crate::utils::{self, text_chunker, translation_manager};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{async_runtime, Emitter, Manager};
+use tauri::{AppHandle, LogicalSize};
 
 use crate::utils::language_detection;
 
-pub fn translate_selected_text(app_handle: &AppHandle) {
-    let app_handle = app_handle.clone();
-    async_runtime::spawn(async move {
-        let selected_text = crate::utils::selecte_text::get_selected_text();
-        if selected_text.is_empty() {
-            return;
+/// 根据检测到的语言生成翻译提示词。分块翻译时语言由全文判断，各分块使用同一种提示词
+fn build_translation_prompt(language: &str, text: &str) -> String {
+    match language {
+        "zh-CN" => format!("请将以下中文文本翻译成英文：\n\n{}", text),
+        "en-US" => format!(
+            "Please translate the following English text into Chinese: \n\n{}",
+            text
+        ),
+        _ => format!("请分析以下文本并给出总结：\n\n{}", text),
+    }
+}
+
+/// 流式输出时调整气泡大小的最小间隔
+const BUBBLE_RESIZE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 流式输出时的气泡尺寸节流：间隔内最多调整一次，且只增不减，避免抖动
+struct ResizeThrottle {
+    interval: Duration,
+    last_resize: Option<Instant>,
+    size: LogicalSize<f64>,
+}
+
+impl ResizeThrottle {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_resize: None,
+            size: LogicalSize::new(0.0, 0.0),
         }
-        println!("selected_text: {}", selected_text);
-        let detected_lang = language_detection::detect_language(&selected_text);
-
-        let translation_prompt = match detected_lang {
-            "zh-CN" => format!("请将以下中文文本翻译成英文：\n\n{}", selected_text),
-            "en-US" => format!(
-                "Please translate the following English text into Chinese: \n\n{}",
-                selected_text
-            ),
-            _ => format!("请分析以下文本并给出总结：\n\n{}", selected_text),
-        };
-        let translation_manager = app_handle.state::<translation_manager::TranslationManager>();
-
-        let _ = translation_manager.create_session().await;
-        match translation_manager
+    }
+
+    /// 返回是否需要按新尺寸调整窗口
+    fn update(&mut self, size: LogicalSize<f64>, now: Instant) -> bool {
+        if size.width <= self.size.width && size.height <= self.size.height {
+            return false;
+        }
+        if let Some(last_resize) = self.last_resize {
+            if now.duration_since(last_resize) < self.interval {
+                return false;
+            }
+        }
+        self.size = LogicalSize::new(
+            size.width.max(self.size.width),
+            size.height.max(self.size.height),
+        );
+        self.last_resize = Some(now);
+        true
+    }
+}
+
+/// 会话创建后立即把原文推送给前端，并按设置自动朗读
+async fn emit_initial_history(app_handle: AppHandle, chat_history: Vec<ChatMessage>) {
+    my_tts::auto_speak(&app_handle, &chat_history);
+    let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
+}
+
+/// 超过单次请求长度、需要分块翻译的文本
+fn is_chunked(text: &str) -> bool {
+    text_chunker::estimate_tokens(text) > translation_manager::CHUNK_MAX_TOKENS
+}
+
+/// 在新会话中翻译选中文本，超长文本自动分块并按顺序推送分块译文，
+/// 每收到一块以已完成的译文调用 `on_progress`。
+/// 原文来自截图识别时，`image` 为截图路径，记录在会话中
+async fn request_translation<C>(
+    app_handle: &AppHandle,
+    selected_text: String,
+    image: Option<String>,
+    on_progress: C,
+) -> Option<Vec<ChatMessage>>
+where
+    C: Fn(&str),
+{
+    let language = language_detection::detect_language(&selected_text);
+    let translation_prompt = build_translation_prompt(language, &selected_text);
+    let translation_manager = app_handle.state::<translation_manager::TranslationManager>();
+    let _ = translation_manager.create_session().await;
+    if let Some(image) = image {
+        translation_manager.attach_image(image).await;
+    }
+
+    let initial_callback =
+        |chat_history: Vec<ChatMessage>| emit_initial_history(app_handle.clone(), chat_history);
+
+    if is_chunked(&selected_text) {
+        let translated = Mutex::new(String::new());
+        translation_manager
+            .translate_chunked(
+                None,
+                &translation_prompt,
+                selected_text,
+                |chunk| build_translation_prompt(language, chunk),
+                initial_callback,
+                |chunk| {
+                    let _ = app_handle.emit(event_names::AI_RESPONSE_CHUNK, &chunk);
+                    let mut translated = translated.lock().unwrap();
+                    translated.push_str(&chunk.content);
+                    on_progress(&translated);
+                },
+            )
+            .await
+    } else {
+        translation_manager
             .translate(
                 None,
                 &translation_prompt,
                 Some(selected_text),
-                |chat_history| {
-                    let app_handle = app_handle.clone();
-                    async move {
-                        let _ = app_handle.emit(event_names::BUBBLE_AUTO_SPEAK, &chat_history);
-                        let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
-                    }
-                },
+                initial_callback,
             )
             .await
-        {
-            Some(chat_history) => {
-                let app_handle_clone = app_handle.clone();
-                let chat_history_clone = chat_history.clone();
-                my_windows::window_translate_show(
-                    &app_handle,
-                    Some(move || {
-                        let app_handle_for_thread = app_handle_clone.clone();
-                        std::thread::spawn(move || {
-                            std::thread::sleep(std::time::Duration::from_millis(100));
-                            let _ = app_handle_for_thread
-                                .emit(event_names::AI_RESPONSE, &chat_history_clone);
-                        });
-                    }),
-                );
-            }
-            None => {
-                let error_msg = "翻译失败".to_string();
-                let app_handle_clone = app_handle.clone();
-                my_windows::window_translate_show(
-                    &app_handle,
-                    Some(move || {
-                        let _ = app_handle_clone.emit(event_names::AI_ERROR, error_msg);
-                    }),
-                );
+    }
+}
+
+pub fn translate_selected_text(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    async_runtime::spawn(async move {
+        let selected_text = crate::utils::selecte_text::get_selected_text_async(&app_handle).await;
+        if selected_text.is_empty() {
+            return;
+        }
+        println!("selected_text: {}", selected_text);
+        if my_dictionary::should_lookup(&app_handle, &selected_text) {
+            let initial_callback = |chat_history: Vec<ChatMessage>| {
+                emit_initial_history(app_handle.clone(), chat_history)
+            };
+            if let Some((dictionary, chat_history)) =
+                my_dictionary::lookup_word(&app_handle, &selected_text, initial_callback).await
+            {
+                deliver_to_translate_window(&app_handle, Some(chat_history), Some(dictionary));
+                return;
             }
         }
+        open_translate_window_for_chunks(&app_handle, &selected_text);
+        let result = request_translation(&app_handle, selected_text, None, |_| {}).await;
+        show_in_translate_window(&app_handle, result);
     });
 }
 
+/// 翻译截图中识别出的文字并显示在翻译窗口中
+pub async fn translate_image_text(app_handle: &AppHandle, text: String, image: String) {
+    open_translate_window_for_chunks(app_handle, &text);
+    let result = request_translation(app_handle, text, Some(image), |_| {}).await;
+    show_in_translate_window(app_handle, result);
+}
+
+/// 分块翻译耗时较长，先打开翻译窗口，分块译文到达时即可显示
+fn open_translate_window_for_chunks(app_handle: &AppHandle, text: &str) {
+    if is_chunked(text) {
+        my_windows::window_translate_show(app_handle, None::<fn()>);
+    }
+}
+
+/// 打开翻译窗口并在页面加载后推送会话结果
+pub fn show_in_translate_window(app_handle: &AppHandle, result: Option<Vec<ChatMessage>>) {
+    deliver_to_translate_window(app_handle, result, None);
+}
+
+/// 同 `show_in_translate_window`，单词查询的结构化结果紧随会话推送
+fn deliver_to_translate_window(
+    app_handle: &AppHandle,
+    result: Option<Vec<ChatMessage>>,
+    dictionary: Option<DictionaryResult>,
+) {
+    match result {
+        Some(chat_history) => {
+            let app_handle_clone = app_handle.clone();
+            my_windows::window_translate_show(
+                app_handle,
+                Some(move || {
+                    let app_handle_for_thread = app_handle_clone.clone();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(std::time::Duration::from_millis(100));
+                        let _ = app_handle_for_thread.emit(event_names::AI_RESPONSE, &chat_history);
+                        if let Some(dictionary) = dictionary {
+                            let _ = app_handle_for_thread
+                                .emit(event_names::DICTIONARY_RESULT, &dictionary);
+                        }
+                    });
+                }),
+            );
+        }
+        None => {
+            let error_msg = "翻译失败".to_string();
+            let app_handle_clone = app_handle.clone();
+            my_windows::window_translate_show(
+                app_handle,
+                Some(move || {
+                    let _ = app_handle_clone.emit(event_names::AI_ERROR, error_msg);
+                }),
+            );
+        }
+    }
+}
+
 pub fn translate_selected_text_bubble(app_handle: &AppHandle) {
     let app_handle = app_handle.clone();
     async_runtime::spawn(async move {
-        let selected_text = crate::utils::selecte_text::get_selected_text();
+        let selected_text = crate::utils::selecte_text::get_selected_text_async(&app_handle).await;
         if selected_text.is_empty() {
             return;
         }
         println!("selected_text: {}", selected_text);
-        let detected_lang = language_detection::detect_language(&selected_text);
-
-        let translation_prompt = match detected_lang {
-            "zh-CN" => format!("请将以下中文文本翻译成英文：\n\n{}", selected_text),
-            "en-US" => format!(
-                "Please translate the following English text into Chinese: \n\n{}",
-                selected_text
-            ),
-            _ => format!("请分析以下文本并给出总结：\n\n{}", selected_text),
-        };
-        let translation_manager = app_handle.state::<translation_manager::TranslationManager>();
-        let _ = translation_manager.create_session().await;
-
-        match translation_manager
-            .translate(
-                None,
-                &translation_prompt,
-                Some(selected_text),
-                |chat_history| {
-                    let app_handle = app_handle.clone();
-                    async move {
-                        let _ = app_handle.emit(event_names::BUBBLE_AUTO_SPEAK, &chat_history);
-                        let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
-                    }
-                },
-            )
-            .await
+        translate_in_bubble(&app_handle, selected_text).await;
+    });
+}
+
+/// 翻译已获取到的文本并显示在气泡中（例如剪贴板监听捕获的内容）
+pub fn translate_text_bubble(app_handle: &AppHandle, text: String) {
+    let app_handle = app_handle.clone();
+    async_runtime::spawn(async move {
+        translate_in_bubble(&app_handle, text).await;
+    });
+}
+
+async fn translate_in_bubble(app_handle: &AppHandle, text: String) {
+    if my_dictionary::should_lookup(app_handle, &text) {
+        let initial_callback =
+            |chat_history: Vec<ChatMessage>| emit_initial_history(app_handle.clone(), chat_history);
+        if let Some((dictionary, chat_history)) =
+            my_dictionary::lookup_word(app_handle, &text, initial_callback).await
         {
-            Some(chat_history) => {
-                let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
-
-                let window = app_handle.get_webview_window("translate_bubble");
-                if let Some(window) = window {
-                    let size = utils::calculate_text_width::calculate_text_width(
-                        &chat_history.last().unwrap().content,
-                    );
-                    let _ = window.set_size(size);
-                }
-            }
-            None => {
-                let error_msg = "翻译失败".to_string();
-                let _ = app_handle.emit(event_names::AI_ERROR, error_msg);
+            show_dictionary_in_bubble(app_handle, dictionary, chat_history);
+            return;
+        }
+    }
+    // 超长文本走分块翻译，本身已按分块推送
+    let result = if is_chunked(&text) {
+        // 按已完成的译文放大气泡
+        let throttle = Mutex::new(ResizeThrottle::new(BUBBLE_RESIZE_INTERVAL));
+        request_translation(app_handle, text, None, |translated| {
+            let size = utils::calculate_text_width::calculate_text_width(translated);
+            if throttle.lock().unwrap().update(size, Instant::now()) {
+                my_windows::resize_translate_bubble(app_handle, size);
             }
+        })
+        .await
+    } else {
+        request_translation_stream(app_handle, text).await
+    };
+    // 流结束后按完整译文再排版一次
+    show_in_bubble(app_handle, result);
+}
+
+/// 流式翻译，逐段推送到气泡并按节流调整气泡大小
+async fn request_translation_stream(
+    app_handle: &AppHandle,
+    selected_text: String,
+) -> Option<Vec<ChatMessage>> {
+    let language = language_detection::detect_language(&selected_text);
+    let translation_prompt = build_translation_prompt(language, &selected_text);
+    let translation_manager = app_handle.state::<translation_manager::TranslationManager>();
+    let _ = translation_manager.create_session().await;
+
+    let app_handle_for_stream = app_handle.clone();
+    let streamed = Arc::new(Mutex::new((
+        String::new(),
+        ResizeThrottle::new(BUBBLE_RESIZE_INTERVAL),
+    )));
+
+    translation_manager
+        .translate_stream(
+            None,
+            translation_prompt,
+            Some(selected_text),
+            |chat_history| emit_initial_history(app_handle.clone(), chat_history),
+            move |delta| {
+                // 气泡只显示译文，不显示思考过程
+                let StreamDelta::Content(chunk) = delta else {
+                    return;
+                };
+                let mut streamed = streamed.lock().unwrap();
+                let (content, throttle) = &mut *streamed;
+                content.push_str(&chunk);
+                let _ = app_handle_for_stream.emit_to(
+                    "translate_bubble",
+                    event_names::BUBBLE_STREAM,
+                    &*content,
+                );
+
+                let size = utils::calculate_text_width::calculate_text_width(content);
+                if throttle.update(size, Instant::now()) {
+                    my_windows::resize_translate_bubble(&app_handle_for_stream, size);
+                }
+            },
+        )
+        .await
+}
+
+/// 在气泡中显示单词查询结果，按单行摘要调整气泡大小
+fn show_dictionary_in_bubble(
+    app_handle: &AppHandle,
+    dictionary: DictionaryResult,
+    chat_history: Vec<ChatMessage>,
+) {
+    let _ = app_handle.emit(event_names::DICTIONARY_RESULT, &dictionary);
+    let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
+    let size = utils::calculate_text_width::calculate_text_width(&dictionary.summary);
+    my_windows::resize_translate_bubble(app_handle, size);
+}
+
+/// 在气泡中显示会话结果并按内容调整气泡大小
+pub fn show_in_bubble(app_handle: &AppHandle, result: Option<Vec<ChatMessage>>) {
+    match result {
+        Some(chat_history) => {
+            let _ = app_handle.emit(event_names::AI_RESPONSE, &chat_history);
+
+            let size = utils::calculate_text_width::calculate_text_width(
+                &chat_history.last().unwrap().content.text(),
+            );
+            my_windows::resize_translate_bubble(app_handle, size);
         }
-    });
+        None => {
+            let error_msg = "翻译失败".to_string();
+            let _ = app_handle.emit(event_names::AI_ERROR, error_msg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_throttle() {
+        let start = Instant::now();
+        let size = |width, height| LogicalSize::new(width, height);
+        let mut throttle = ResizeThrottle::new(Duration::from_millis(100));
+
+        assert!(throttle.update(size(200.0, 36.0), start));
+        // 间隔内不调整
+        assert!(!throttle.update(size(300.0, 36.0), start + Duration::from_millis(50)));
+        assert!(throttle.update(size(300.0, 36.0), start + Duration::from_millis(150)));
+        // 尺寸不变或变小不调整
+        assert!(!throttle.update(size(300.0, 36.0), start + Duration::from_millis(400)));
+        assert!(!throttle.update(size(250.0, 36.0), start + Duration::from_millis(500)));
+        // 换行后变高
+        assert!(throttle.update(size(300.0, 56.0), start + Duration::from_millis(600)));
+    }
 }
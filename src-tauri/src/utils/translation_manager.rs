@@ -1,17 +1,101 @@
 use crate::my_api::manager::APIManager;
-use crate::my_api::traits::ChatCompletionRequest;
+use crate::my_api::traits::{ChatCompletionRequest, ResponseFormat};
+use crate::my_tools::ToolRegistry;
 use crate::states::chat_histories::ChatHistoriesState;
-use crate::utils::chat_message::{ChatMessage, ChatMessageHistory};
+use crate::utils::chat_message::{
+    ChatMessage, ChatMessageHistory, LLMChatMessage, MessageContent, Role,
+};
+use crate::utils::text_chunker::{self, TextChunk};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::collections::BTreeMap;
 use std::future::Future;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tauri::async_runtime::RwLock;
+
+/// 单个分块的 token 预算，超过该长度的选中文本走分块翻译
+pub const CHUNK_MAX_TOKENS: usize = 800;
+/// 同时进行的分块请求数
+const CHUNK_CONCURRENCY: usize = 3;
+/// 单个分块失败后的重试次数
+const CHUNK_MAX_RETRIES: usize = 2;
+/// 分块重试前的等待时间，第 n 次重试等待 n 倍
+const CHUNK_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+/// 结构化回复格式错误时的重试次数
+const STRUCTURED_MAX_RETRIES: usize = 2;
+
+/// 按原文顺序推送给前端的分块译文
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslatedChunk {
+    pub index: usize,
+    pub total: usize,
+    /// 译文，已附带原文中的分隔空白，前端可直接拼接
+    pub content: String,
+}
+
+/// 分块重试后仍失败时的译文，保留原文
+fn chunk_failed_placeholder(chunk: &TextChunk) -> String {
+    format!("[该段翻译失败，原文如下]\n{}", chunk.text.trim())
+}
+
+/// 流式回复的片段：推理模型先返回思考过程，再返回正文
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamDelta {
+    Reasoning(String),
+    Content(String),
+}
+
+/// 单次请求的服务商、模型和采样参数，未指定的项使用默认值
+#[derive(Debug, Clone, Default)]
+pub struct CompletionOptions {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+}
+
+/// JSON 模式下的结构化回复
+pub trait StructuredReply: DeserializeOwned {
+    /// 检查内容是否完整，不完整时要求模型重新输出
+    fn validate(&self) -> Result<(), String>;
+    /// 记录在会话中的文本形式，后续追问时作为上下文
+    fn to_text(&self) -> String;
+}
+
+/// 解析并校验结构化回复，容忍模型在 JSON 外包裹的代码块标记
+pub fn parse_structured<T: StructuredReply>(reply: &str) -> Result<T, String> {
+    let mut json = reply.trim();
+    if let Some(fenced) = json.strip_prefix("```") {
+        // 跳过 ```json 所在的第一行
+        let body = fenced.split_once('\n').map_or("", |(_, body)| body);
+        json = body.trim_end().strip_suffix("```").unwrap_or(body).trim();
+    }
+    let value: T = serde_json::from_str(json).map_err(|e| format!("JSON 格式错误: {}", e))?;
+    value.validate()?;
+    Ok(value)
+}
+
+/// 转为请求格式，本地图片读取失败时放弃本次请求
+fn llm_messages<'a>(
+    messages: impl IntoIterator<Item = &'a ChatMessage>,
+) -> Option<Vec<LLMChatMessage<'a>>> {
+    messages
+        .into_iter()
+        .map(ChatMessage::as_llm)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| eprintln!("构造请求消息失败: {}", e))
+        .ok()
+}
+
 #[derive(Clone)]
 pub struct TranslationManager {
     chat_histories: ChatHistoriesState,
     active_session_id: Arc<RwLock<Option<String>>>,
     api_manager: Arc<RwLock<APIManager>>,
+    /// 对话时模型可以调用的工具，为空时请求不带工具
+    tools: Arc<std::sync::RwLock<ToolRegistry>>,
 }
 
 impl TranslationManager {
@@ -20,10 +104,39 @@ impl TranslationManager {
             chat_histories: chat_histories.clone(),
             active_session_id: Arc::new(RwLock::new(None)),
             api_manager,
+            tools: Arc::default(),
+        }
+    }
+
+    pub fn set_tools(&self, tools: ToolRegistry) {
+        *self.tools.write().unwrap() = tools;
+    }
+
+    fn tools(&self) -> ToolRegistry {
+        self.tools.read().unwrap().clone()
+    }
+
+    /// 记录助手的工具调用，依次执行并把结果追加到会话中
+    async fn run_tool_calls(&self, session_id: &str, tools: &ToolRegistry, message: ChatMessage) {
+        let tool_calls = message.tool_calls.clone().unwrap_or_default();
+        self.chat_histories.push_message(session_id, message).await;
+        for call in &tool_calls {
+            let result = tools.execute(call).await;
+            self.chat_histories
+                .push_message(session_id, ChatMessage::tool_result(&call.id, result))
+                .await;
         }
     }
 
     pub async fn create_session(&self) -> String {
+        self.create_session_with_system_prompt(
+            "你是一个专业的翻译助手。请准确地进行语言翻译，保持原文的含义和语气。",
+        )
+        .await
+    }
+
+    /// 以指定的系统提示词创建新会话并设为当前会话
+    pub async fn create_session_with_system_prompt(&self, system_prompt: &str) -> String {
         let session_id = format!(
             "translate_{}",
             SystemTime::now()
@@ -33,11 +146,7 @@ impl TranslationManager {
         );
 
         self.chat_histories
-            .add_system_message(
-                &session_id,
-                "你是一个专业的翻译助手。请准确地进行语言翻译，保持原文的含义和语气。".to_string(),
-                None,
-            )
+            .add_system_message(&session_id, system_prompt.to_string(), None)
             .await;
 
         let mut active_id = self.active_session_id.write().await;
@@ -53,6 +162,29 @@ impl TranslationManager {
         raw: Option<String>,
         callback: F,
     ) -> Option<Vec<ChatMessage>>
+    where
+        F: FnOnce(Vec<ChatMessage>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.complete(
+            session_id,
+            content,
+            raw,
+            &CompletionOptions::default(),
+            callback,
+        )
+        .await
+    }
+
+    /// 与 `translate` 相同，但可指定服务商、模型和温度（自定义动作）
+    pub async fn complete<F, Fut>(
+        &self,
+        session_id: Option<&str>,
+        content: &str,
+        raw: Option<String>,
+        options: &CompletionOptions,
+        callback: F,
+    ) -> Option<Vec<ChatMessage>>
     where
         F: FnOnce(Vec<ChatMessage>) -> Fut,
         Fut: Future<Output = ()> + Send + 'static,
@@ -71,33 +203,170 @@ impl TranslationManager {
 
         let messages = self.chat_histories.get_messages(&session_id).await?;
 
-        callback(messages.clone()).await;
+        callback(messages).await;
 
-        let request = ChatCompletionRequest {
-            model: "qwen-plus".to_string(),
-            messages: messages.iter().map(ChatMessage::as_llm).collect::<Vec<_>>(),
-            temperature: Some(0.1),
-            max_tokens: Some(500),
-            top_p: Some(1.0),
-            stream: Some(false),
+        let manager = self.api_manager.read().await;
+        let provider = options.provider.as_deref();
+        let model = match &options.model {
+            Some(model) => model.clone(),
+            None if provider.is_some() => manager.default_model(provider).await?,
+            None => "qwen-plus".to_string(),
+        };
+
+        // 模型要求调用工具时执行后再次请求，直到给出回答
+        let tools = self.tools();
+        for round in 0..=tools.max_rounds() {
+            let messages = self.chat_histories.get_messages(&session_id).await?;
+            let request = ChatCompletionRequest {
+                model: model.clone(),
+                messages: llm_messages(&messages)?,
+                temperature: Some(options.temperature.unwrap_or(0.1)),
+                max_tokens: Some(2000),
+                top_p: Some(1.0),
+                stream: Some(false),
+                tools: tools.definitions(round),
+                response_format: None,
+            };
+
+            let response = manager
+                .chat_completion_with(provider, &request)
+                .await
+                .ok()?;
+            let message = response.choices.into_iter().next()?.message;
+
+            if message
+                .tool_calls
+                .as_ref()
+                .is_some_and(|calls| !calls.is_empty())
+            {
+                self.run_tool_calls(&session_id, &tools, message).await;
+                continue;
+            }
+            let reply = ChatMessage {
+                reasoning: message.reasoning,
+                ..ChatMessage::new(Role::Assistant, message.content)
+            };
+            self.chat_histories.push_message(&session_id, reply).await;
+            break;
+        }
+
+        self.chat_histories.get_messages(&session_id).await
+    }
+
+    /// 以 JSON 模式请求结构化回复，格式错误时把错误告诉模型并重试。
+    /// 会话中记录回复的文本形式
+    pub async fn complete_structured<T, F, Fut>(
+        &self,
+        session_id: Option<&str>,
+        content: &str,
+        raw: Option<String>,
+        callback: F,
+    ) -> Option<(T, Vec<ChatMessage>)>
+    where
+        T: StructuredReply,
+        F: FnOnce(Vec<ChatMessage>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let session_id = match session_id {
+            Some(id) => id.to_string(),
+            None => {
+                let active_id = self.active_session_id.read().await;
+                active_id.as_ref()?.clone()
+            }
         };
 
+        self.chat_histories
+            .add_user_message(&session_id, content, raw)
+            .await;
+
+        let messages = self.chat_histories.get_messages(&session_id).await?;
+
+        callback(messages.clone()).await;
+
         let manager = self.api_manager.read().await;
-        let response = manager.chat_completion(&request).await.ok()?;
+        // 无效的回复和纠正提示只用于重试，不写入会话
+        let mut corrections = Vec::new();
+        for attempt in 0..=STRUCTURED_MAX_RETRIES {
+            let request = ChatCompletionRequest {
+                model: "qwen-plus".to_string(),
+                messages: llm_messages(messages.iter().chain(&corrections))?,
+                temperature: Some(0.1),
+                max_tokens: Some(2000),
+                top_p: Some(1.0),
+                stream: Some(false),
+                tools: None,
+                response_format: Some(ResponseFormat::json_object()),
+            };
+
+            let reply = match manager.chat_completion(&request).await {
+                Ok(response) => response.choices.into_iter().next()?.message,
+                Err(e) => {
+                    eprintln!("结构化请求失败 (第{}次): {}", attempt + 1, e);
+                    continue;
+                }
+            };
+            match parse_structured::<T>(&reply.content.text()) {
+                Ok(value) => {
+                    let message = ChatMessage {
+                        reasoning: reply.reasoning,
+                        ..ChatMessage::new(Role::Assistant, value.to_text())
+                    };
+                    self.chat_histories.push_message(&session_id, message).await;
+                    let messages = self.chat_histories.get_messages(&session_id).await?;
+                    return Some((value, messages));
+                }
+                Err(e) => {
+                    eprintln!("结构化回复无效 (第{}次): {}", attempt + 1, e);
+                    corrections.push(ChatMessage::new(Role::Assistant, reply.content));
+                    corrections.push(ChatMessage::new(
+                        Role::User,
+                        format!("上面的回复无效：{}。请只输出符合要求的 JSON。", e),
+                    ));
+                }
+            }
+        }
+        None
+    }
 
-        let content = response.choices.first()?.message.content.clone();
+    /// 记录本地得到的结构化结果，不请求模型，如离线词典的释义
+    pub async fn record_structured<T, F, Fut>(
+        &self,
+        session_id: Option<&str>,
+        content: &str,
+        raw: Option<String>,
+        value: &T,
+        callback: F,
+    ) -> Option<Vec<ChatMessage>>
+    where
+        T: StructuredReply,
+        F: FnOnce(Vec<ChatMessage>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let session_id = match session_id {
+            Some(id) => id.to_string(),
+            None => {
+                let active_id = self.active_session_id.read().await;
+                active_id.as_ref()?.clone()
+            }
+        };
 
         self.chat_histories
-            .add_assistant_message(&session_id, content.clone(), None)
+            .add_user_message(&session_id, content, raw)
             .await;
+        let messages = self.chat_histories.get_messages(&session_id).await?;
+        callback(messages).await;
 
+        self.chat_histories
+            .add_assistant_message(&session_id, value.to_text(), None)
+            .await;
         self.chat_histories.get_messages(&session_id).await
     }
 
+    /// 流式对话，内容可包含图片。思考过程随回复记录在会话中，之后的请求不会发送
     pub async fn translate_stream<F, Fut, StreamCallback>(
         &self,
         session_id: Option<&str>,
-        content: &str,
+        content: impl Into<MessageContent>,
         raw: Option<String>,
         initial_callback: F,
         stream_callback: StreamCallback,
@@ -105,7 +374,7 @@ impl TranslationManager {
     where
         F: FnOnce(Vec<ChatMessage>) -> Fut,
         Fut: Future<Output = ()> + Send + 'static,
-        StreamCallback: Fn(String) + Send + 'static,
+        StreamCallback: Fn(StreamDelta) + Send + 'static,
     {
         let session_id = match session_id {
             Some(id) => id.to_string(),
@@ -116,48 +385,196 @@ impl TranslationManager {
         };
 
         self.chat_histories
-            .add_user_message(&session_id, content.to_string(), raw)
+            .add_user_message(&session_id, content, raw)
             .await;
 
         let messages = self.chat_histories.get_messages(&session_id).await?;
 
-        initial_callback(messages.clone()).await;
-
-        let request = ChatCompletionRequest {
-            model: "qwen-plus".to_string(),
-            messages: messages.iter().map(ChatMessage::as_llm).collect::<Vec<_>>(),
-            temperature: Some(0.1),
-            max_tokens: Some(5000),
-            top_p: Some(1.0),
-            stream: Some(true),
-        };
+        initial_callback(messages).await;
 
         let manager = self.api_manager.read().await;
-        let content_chunks = Arc::new(std::sync::Mutex::new(String::new()));
-        let content_chunks_clone = content_chunks.clone();
-        let result = manager
-            .chat_completion_stream(&request, move |chunk| {
-                for choice in &chunk.choices {
-                    if let Some(ref content) = choice.delta.content {
-                        stream_callback(content.clone());
-                        // Collect the content chunks
-                        let mut chunks = content_chunks_clone.lock().unwrap();
-                        *chunks += content;
+        // 每轮请求的内容都推送给同一个回调
+        let stream_callback = std::sync::Mutex::new(stream_callback);
+        let tools = self.tools();
+        let mut final_content = String::new();
+        let mut reasoning = String::new();
+        for round in 0..=tools.max_rounds() {
+            let messages = self.chat_histories.get_messages(&session_id).await?;
+            let request = ChatCompletionRequest {
+                model: "qwen-plus".to_string(),
+                messages: llm_messages(&messages)?,
+                temperature: Some(0.1),
+                max_tokens: Some(5000),
+                top_p: Some(1.0),
+                stream: Some(true),
+                tools: tools.definitions(round),
+                response_format: None,
+            };
+
+            let mut content = String::new();
+            let mut tool_calls = None;
+            let result = manager
+                .chat_completion_stream(&request, |chunk| {
+                    for choice in chunk.choices {
+                        if let Some(delta) = choice.delta.reasoning_content {
+                            reasoning += &delta;
+                            (*stream_callback.lock().unwrap())(StreamDelta::Reasoning(delta));
+                        }
+                        if let Some(delta) = choice.delta.content {
+                            (*stream_callback.lock().unwrap())(StreamDelta::Content(delta.clone()));
+                            content += &delta;
+                        }
+                        if choice.tool_calls.is_some() {
+                            tool_calls = choice.tool_calls;
+                        }
                     }
+                })
+                .await;
+
+            if result.is_err() {
+                return None;
+            }
+            match tool_calls {
+                Some(tool_calls) => {
+                    let message = ChatMessage {
+                        tool_calls: Some(tool_calls),
+                        ..ChatMessage::new(Role::Assistant, content)
+                    };
+                    self.run_tool_calls(&session_id, &tools, message).await;
+                }
+                None => {
+                    final_content = content;
+                    break;
                 }
-            })
+            }
+        }
+
+        let message = ChatMessage {
+            reasoning: (!reasoning.is_empty()).then_some(reasoning),
+            ..ChatMessage::new(Role::Assistant, final_content)
+        };
+        self.chat_histories.push_message(&session_id, message).await;
+        self.chat_histories.get_messages(&session_id).await
+    }
+
+    /// 长文本分块翻译：按段落 / 句子切分后并发请求，按原文顺序回调并拼接结果
+    pub async fn translate_chunked<P, F, Fut, ChunkCallback>(
+        &self,
+        session_id: Option<&str>,
+        content: &str,
+        raw: String,
+        build_prompt: P,
+        initial_callback: F,
+        chunk_callback: ChunkCallback,
+    ) -> Option<Vec<ChatMessage>>
+    where
+        P: Fn(&str) -> String,
+        F: FnOnce(Vec<ChatMessage>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+        ChunkCallback: Fn(TranslatedChunk),
+    {
+        let session_id = match session_id {
+            Some(id) => id.to_string(),
+            None => {
+                let active_id = self.active_session_id.read().await;
+                active_id.as_ref()?.clone()
+            }
+        };
+
+        let chunks = text_chunker::split_into_chunks(&raw, CHUNK_MAX_TOKENS);
+
+        self.chat_histories
+            .add_user_message(&session_id, content.to_string(), Some(raw))
             .await;
 
-        if result.is_err() {
-            return None;
+        let messages = self.chat_histories.get_messages(&session_id).await?;
+        let system_message = messages.iter().find(|m| m.role == Role::System).cloned();
+
+        initial_callback(messages).await;
+
+        let manager = self.api_manager.read().await;
+        let total = chunks.len();
+        let mut results = futures::stream::iter(chunks.iter().map(|chunk| {
+            let prompt = build_prompt(&chunk.text);
+            let manager = &*manager;
+            let system_message = system_message.as_ref();
+            async move { Self::translate_chunk(manager, system_message, prompt).await }
+        }))
+        .buffered(CHUNK_CONCURRENCY);
+
+        // 失败的分块用占位文本代替，其余分块照常输出
+        let mut translations = Vec::with_capacity(total);
+        while let Some(result) = results.next().await {
+            let index = translations.len();
+            let translation = result.unwrap_or_else(|| chunk_failed_placeholder(&chunks[index]));
+            chunk_callback(TranslatedChunk {
+                index,
+                total,
+                content: format!("{}{}", translation.trim(), chunks[index].separator),
+            });
+            translations.push(translation);
         }
-        let final_content = content_chunks.lock().unwrap().clone();
+
+        let final_content = text_chunker::join_chunks(&chunks, &translations);
         self.chat_histories
             .add_assistant_message(&session_id, final_content, None)
             .await;
         self.chat_histories.get_messages(&session_id).await
     }
 
+    /// 翻译单个分块，失败时重试
+    async fn translate_chunk(
+        manager: &APIManager,
+        system_message: Option<&ChatMessage>,
+        prompt: String,
+    ) -> Option<String> {
+        let user_message = ChatMessage::new(Role::User, prompt);
+        let request = ChatCompletionRequest {
+            model: "qwen-plus".to_string(),
+            messages: llm_messages(system_message.into_iter().chain([&user_message]))?,
+            temperature: Some(0.1),
+            max_tokens: Some(2000),
+            top_p: Some(1.0),
+            stream: Some(false),
+            tools: None,
+            response_format: None,
+        };
+
+        for attempt in 0..=CHUNK_MAX_RETRIES {
+            if attempt > 0 {
+                tokio::time::sleep(CHUNK_RETRY_BACKOFF * attempt as u32).await;
+            }
+            match manager.chat_completion(&request).await {
+                Ok(response) => {
+                    if let Some(choice) = response.choices.first() {
+                        return Some(choice.message.content.text().into_owned());
+                    }
+                    eprintln!("分块翻译返回为空 (第{}次)", attempt + 1);
+                }
+                Err(e) => eprintln!("分块翻译失败 (第{}次): {}", attempt + 1, e),
+            }
+        }
+        None
+    }
+
+    /// 记录当前会话的原文来自哪张截图
+    pub async fn attach_image(&self, image: String) {
+        if let Some(session_id) = self.active_session_id.read().await.clone() {
+            self.chat_histories.set_image(&session_id, image).await;
+        }
+    }
+
+    /// 当前会话中最后一条助手回复
+    pub async fn last_assistant_message(&self) -> Option<String> {
+        let session_id = self.active_session_id.read().await.clone()?;
+        let messages = self.chat_histories.get_messages(&session_id).await?;
+        messages
+            .into_iter()
+            .rev()
+            .find(|message| message.role == Role::Assistant)
+            .map(|message| message.content.text().into_owned())
+    }
+
     pub async fn get_histories(&self) -> BTreeMap<String, ChatMessageHistory> {
         self.chat_histories.clone().get_all_histories().await
     }
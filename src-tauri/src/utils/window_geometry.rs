@@ -0,0 +1,147 @@
+//! 固定模式下翻译窗口的位置和大小，按显示器分别记录在 store.json 中
+
+use crate::utils::coordinates::{clamp_span, MonitorInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+const STORE_KEY: &str = "translate_window_geometry";
+
+/// 窗口外框左上角和内容区大小，物理像素
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowGeometry {
+    pub fn center(&self) -> (f64, f64) {
+        (
+            self.x as f64 + self.width as f64 / 2.0,
+            self.y as f64 + self.height as f64 / 2.0,
+        )
+    }
+}
+
+/// 显示器的标识：同一位置、同一分辨率视为同一块显示器
+pub fn monitor_key(monitor: &MonitorInfo) -> String {
+    let bounds = monitor.bounds;
+    format!(
+        "{},{},{}x{}",
+        bounds.x, bounds.y, bounds.width, bounds.height
+    )
+}
+
+/// 让记录的窗口完整显示在工作区内，工作区变小后同时缩小窗口
+pub fn fit_to_monitor(geometry: WindowGeometry, monitor: &MonitorInfo) -> WindowGeometry {
+    let area = monitor.work_area;
+    let width = (geometry.width as f64).min(area.width);
+    let height = (geometry.height as f64).min(area.height);
+    WindowGeometry {
+        x: clamp_span(geometry.x as f64, width, area.x, area.right()).round() as i32,
+        y: clamp_span(geometry.y as f64, height, area.y, area.bottom()).round() as i32,
+        width: width as u32,
+        height: height as u32,
+    }
+}
+
+fn load_all<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, WindowGeometry> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// 读取窗口在该显示器上最后的位置和大小
+pub fn load<R: Runtime>(app: &AppHandle<R>, monitor: &MonitorInfo) -> Option<WindowGeometry> {
+    load_all(app)
+        .get(&monitor_key(monitor))
+        .map(|geometry| fit_to_monitor(*geometry, monitor))
+}
+
+pub fn save<R: Runtime>(
+    app: &AppHandle<R>,
+    monitor: &MonitorInfo,
+    geometry: WindowGeometry,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut all = load_all(app);
+    if all.get(&monitor_key(monitor)) == Some(&geometry) {
+        return Ok(());
+    }
+    all.insert(monitor_key(monitor), geometry);
+    let store = app.store("store.json")?;
+    store.set(STORE_KEY, json!(all));
+    store.save().map_err(|e| e.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::coordinates::Rect;
+
+    fn monitor() -> MonitorInfo {
+        MonitorInfo {
+            bounds: Rect::new(1920.0, 0.0, 3840.0, 2160.0),
+            work_area: Rect::new(1920.0, 50.0, 3840.0, 2110.0),
+            scale_factor: 2.0,
+        }
+    }
+
+    #[test]
+    fn test_monitor_key() {
+        assert_eq!(monitor_key(&monitor()), "1920,0,3840x2160");
+    }
+
+    #[test]
+    fn test_fit_keeps_visible_geometry() {
+        let geometry = WindowGeometry {
+            x: 2000,
+            y: 100,
+            width: 800,
+            height: 1200,
+        };
+        assert_eq!(fit_to_monitor(geometry, &monitor()), geometry);
+    }
+
+    #[test]
+    fn test_fit_moves_and_shrinks() {
+        // 超出右下角的窗口移回工作区
+        let geometry = WindowGeometry {
+            x: 5500,
+            y: 2000,
+            width: 800,
+            height: 1200,
+        };
+        assert_eq!(
+            fit_to_monitor(geometry, &monitor()),
+            WindowGeometry {
+                x: 4960,
+                y: 960,
+                width: 800,
+                height: 1200,
+            }
+        );
+
+        // 比工作区还大的窗口缩小到工作区大小
+        let geometry = WindowGeometry {
+            x: 0,
+            y: 0,
+            width: 5000,
+            height: 3000,
+        };
+        assert_eq!(
+            fit_to_monitor(geometry, &monitor()),
+            WindowGeometry {
+                x: 1920,
+                y: 50,
+                width: 3840,
+                height: 2110,
+            }
+        );
+    }
+}
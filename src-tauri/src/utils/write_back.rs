@@ -0,0 +1,187 @@
+//! 回写模式：用当前会话最后一条译文替换原选区
+
+use crate::my_events::event_names;
+use crate::utils::clipboard::{self, ClipboardBackend, SystemClipboard};
+use crate::utils::translation_manager::TranslationManager;
+use std::thread::sleep;
+use std::time::Duration;
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+
+/// 等待用户松开快捷键的修饰键，避免与模拟的 Ctrl/Cmd+V 叠加
+const MODIFIER_RELEASE_DELAY: Duration = Duration::from_millis(100);
+/// 粘贴后等待目标应用读取剪贴板，再恢复原内容
+const PASTE_SETTLE_DELAY: Duration = Duration::from_millis(200);
+
+/// 触发系统“粘贴”操作
+pub trait PasteTrigger {
+    fn trigger_paste(&self) -> Result<(), String>;
+}
+
+/// 通过模拟 Ctrl/Cmd+V 触发粘贴
+pub struct KeyboardPasteTrigger;
+
+impl PasteTrigger for KeyboardPasteTrigger {
+    fn trigger_paste(&self) -> Result<(), String> {
+        clipboard::simulate_paste()
+    }
+}
+
+/// 借助剪贴板粘贴文本，完成后恢复用户原有的剪贴板内容；
+/// 粘贴已完成时恢复失败只记录日志，不算作回写失败
+pub fn paste_text<C: ClipboardBackend, T: PasteTrigger>(
+    clipboard: &mut C,
+    trigger: &T,
+    text: &str,
+    settle_delay: Duration,
+) -> Result<(), String> {
+    let previous = clipboard.snapshot();
+    clipboard.write_text(text)?;
+
+    let result = trigger.trigger_paste();
+    if result.is_ok() {
+        sleep(settle_delay);
+    }
+
+    if let Err(e) = clipboard.restore(&previous) {
+        eprintln!("恢复剪贴板失败: {}", e);
+    }
+    result
+}
+
+/// 回写最后一条译文，失败时广播 AI_ERROR（快捷键触发）
+pub fn write_back_last_translation(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    async_runtime::spawn(async move {
+        if let Err(e) = write_back_last(&app_handle).await {
+            let _ = app_handle.emit(event_names::AI_ERROR, e);
+        }
+    });
+}
+
+/// 回写当前会话最后一条译文
+pub async fn write_back_last(app_handle: &AppHandle) -> Result<(), String> {
+    let translation_manager = app_handle.state::<TranslationManager>();
+    let text = translation_manager
+        .last_assistant_message()
+        .await
+        .ok_or("没有可回写的译文")?;
+    paste_into_selection(app_handle, text).await
+}
+
+/// 隐藏气泡后把文本粘贴到原应用的选区，失败时广播 AI_ERROR
+pub async fn write_back_text(app_handle: &AppHandle, text: String) {
+    if let Err(e) = paste_into_selection(app_handle, text).await {
+        let _ = app_handle.emit(event_names::AI_ERROR, e);
+    }
+}
+
+async fn paste_into_selection(app_handle: &AppHandle, text: String) -> Result<(), String> {
+    // 气泡不抢焦点，隐藏后原应用仍保持选区
+    if let Some(window) = app_handle.get_webview_window("translate_bubble") {
+        let _ = window.hide();
+        let _ = app_handle.emit(event_names::BUBBLE_CLEAN, {});
+    }
+
+    let task = async_runtime::spawn_blocking(move || {
+        sleep(MODIFIER_RELEASE_DELAY);
+        let mut clipboard = SystemClipboard::new()?;
+        paste_text(
+            &mut clipboard,
+            &KeyboardPasteTrigger,
+            &text,
+            PASTE_SETTLE_DELAY,
+        )
+    });
+    let result = match task.await {
+        Ok(result) => result,
+        Err(e) => Err(format!("回写任务失败: {}", e)),
+    };
+    if let Err(e) = &result {
+        eprintln!("回写失败: {}", e);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clipboard::testing::FakeClipboard;
+    use crate::utils::clipboard::ClipboardContent;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// 记录粘贴时剪贴板里的内容
+    struct FakePaste {
+        clipboard: Rc<RefCell<ClipboardContent>>,
+        pasted: RefCell<Option<ClipboardContent>>,
+    }
+
+    impl PasteTrigger for FakePaste {
+        fn trigger_paste(&self) -> Result<(), String> {
+            *self.pasted.borrow_mut() = Some(self.clipboard.borrow().clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_paste_text_restores_clipboard() {
+        let shared = Rc::new(RefCell::new(ClipboardContent::Text("old".into())));
+        let mut clipboard = FakeClipboard(shared.clone());
+        let trigger = FakePaste {
+            clipboard: shared.clone(),
+            pasted: RefCell::new(None),
+        };
+
+        paste_text(&mut clipboard, &trigger, "译文", Duration::ZERO).unwrap();
+
+        assert_eq!(
+            *trigger.pasted.borrow(),
+            Some(ClipboardContent::Text("译文".into()))
+        );
+        assert_eq!(*shared.borrow(), ClipboardContent::Text("old".into()));
+    }
+
+    /// 恢复原内容总是失败的剪贴板
+    struct RestoreFails(FakeClipboard);
+
+    impl ClipboardBackend for RestoreFails {
+        fn snapshot(&mut self) -> ClipboardContent {
+            self.0.snapshot()
+        }
+
+        fn restore(&mut self, _content: &ClipboardContent) -> Result<(), String> {
+            Err("restore failed".into())
+        }
+
+        fn read_text(&mut self) -> Option<String> {
+            self.0.read_text()
+        }
+
+        fn write_text(&mut self, text: &str) -> Result<(), String> {
+            self.0.write_text(text)
+        }
+
+        fn clear(&mut self) -> Result<(), String> {
+            self.0.clear()
+        }
+    }
+
+    #[test]
+    fn test_restore_failure_after_paste_is_not_error() {
+        let shared = Rc::new(RefCell::new(ClipboardContent::Text("old".into())));
+        let mut clipboard = RestoreFails(FakeClipboard(shared.clone()));
+        let trigger = FakePaste {
+            clipboard: shared.clone(),
+            pasted: RefCell::new(None),
+        };
+
+        assert_eq!(
+            paste_text(&mut clipboard, &trigger, "译文", Duration::ZERO),
+            Ok(())
+        );
+        assert_eq!(
+            *trigger.pasted.borrow(),
+            Some(ClipboardContent::Text("译文".into()))
+        );
+    }
+}
@@ -4,6 +4,6 @@ export interface Shortcut {
 }
 
 export interface GlobalConfig {
+  version: number;
   shortcuts: Shortcut[];
-  test_field: string;
 }
@@ -1,7 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useEffect, useState } from "react";
 import { EVENT_NAMES } from "@/lib/events";
-import { AutoSpeakState } from "@/lib/types";
+import { type AppSettings, AutoSpeakState } from "@/lib/types";
 import { IIVolumeLow, IIVolumeHigh, IIVolumeOff } from "@/components/icons";
 
 const AutoSpeakVolume = ({className}:{className?:string}) => {
@@ -12,6 +13,12 @@ const AutoSpeakVolume = ({className}:{className?:string}) => {
 		invoke<AutoSpeakState>(EVENT_NAMES.GET_AUTO_SPEAK_STATE).then((res) =>
 			setAutoSpeak(res),
 		);
+		const unlisten = listen<AppSettings>(EVENT_NAMES.SETTINGS_CHANGED, ({ payload }) =>
+			setAutoSpeak(payload.auto_speak),
+		);
+		return () => {
+			unlisten.then((fn) => fn());
+		};
 	}, []);
 	return (
 		<div
@@ -0,0 +1,41 @@
+import type { DictionaryEntry } from "@/lib/types";
+import { cn } from "@/lib/utils";
+
+/** 单词查询结果：音标、按词性分组的释义和例句、词形变化 */
+const DictionaryCard = ({ entry, className }: { entry: DictionaryEntry; className?: string }) => {
+	return (
+		<div className={cn("flex flex-col gap-1.5", className)}>
+			<div className="flex flex-wrap items-baseline gap-x-3">
+				<span className="text-lg font-semibold">{entry.word}</span>
+				{entry.phonetics.map((phonetic) => (
+					<span key={`${phonetic.region}-${phonetic.ipa}`} className="text-muted-foreground text-sm">
+						{phonetic.region && <span className="mr-1 text-xs">{phonetic.region}</span>}/{phonetic.ipa}/
+					</span>
+				))}
+			</div>
+			<ol className="flex flex-col gap-1">
+				{entry.senses.map((sense, index) => (
+					<li key={`${sense.partOfSpeech}-${index}`}>
+						{sense.partOfSpeech && (
+							<span className="text-muted-foreground mr-1 italic">{sense.partOfSpeech}</span>
+						)}
+						<span>{sense.definition}</span>
+						{sense.examples.map((example) => (
+							<div key={example.text} className="text-muted-foreground pl-3 text-sm">
+								{example.text}
+								{example.translation && <span className="ml-1">{example.translation}</span>}
+							</div>
+						))}
+					</li>
+				))}
+			</ol>
+			{entry.forms.length > 0 && (
+				<div className="text-muted-foreground text-sm">
+					{entry.forms.map((form) => `${form.kind} ${form.word}`).join(" · ")}
+				</div>
+			)}
+		</div>
+	);
+};
+
+export default DictionaryCard;
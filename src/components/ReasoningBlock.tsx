@@ -0,0 +1,24 @@
+import { cn } from "@/lib/utils";
+
+/** 推理模型的思考过程：正文出现前展开显示，之后折叠，可点击展开 */
+const ReasoningBlock = ({
+	reasoning,
+	thinking,
+	className,
+}: {
+	reasoning: string;
+	/** 仍在思考，正文尚未开始 */
+	thinking: boolean;
+	className?: string;
+}) => {
+	return (
+		<details open={thinking} className={cn("text-muted-foreground text-sm", className)}>
+			<summary className="cursor-pointer select-none">
+				{thinking ? "Thinking..." : "Thought process"}
+			</summary>
+			<div className="mt-1 border-l-2 pl-3 whitespace-pre-wrap">{reasoning}</div>
+		</details>
+	);
+};
+
+export default ReasoningBlock;
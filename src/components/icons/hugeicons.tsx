@@ -12,6 +12,7 @@ import {
   VolumeOffIcon,
   VolumeHighIcon,
   Menu02Icon,
+  ArrowTurnBackwardIcon,
 } from "@hugeicons/core-free-icons";
 
 const strokeWidth = 2
@@ -28,3 +29,4 @@ export const IIVolumeLow = (props: any) => <HugeiconsIcon icon={VolumeLowIcon} {
 export const IIVolumeOff = (props: any) => <HugeiconsIcon icon={VolumeOffIcon} {...props} strokeWidth={strokeWidth}/>;
 export const IIVolumeHigh = (props: any) => <HugeiconsIcon icon={VolumeHighIcon} {...props} strokeWidth={strokeWidth}/>;
 export const IIList = (props: any) => <HugeiconsIcon icon={Menu02Icon} {...props} strokeWidth={strokeWidth}/>;
+export const IIWriteBack = (props: any) => <HugeiconsIcon icon={ArrowTurnBackwardIcon} {...props} strokeWidth={strokeWidth}/>;
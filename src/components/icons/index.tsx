@@ -10,4 +10,6 @@ export {
     IIVolumeOff,
     IIVolumeLow,
     IIVolumeHigh,
+    IIWriteBack,
+    IIList,
 } from "./hugeicons";
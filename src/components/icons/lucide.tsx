@@ -11,6 +11,7 @@ import {
   Volume1,
   Volume2,
   ListMinus,
+  CornerDownLeft,
 } from 'lucide-react';
 
 const strokeWidth = 2
@@ -26,4 +27,5 @@ export const IICopy = (props: any) => <Copy {...props} strokeWidth={strokeWidth}
 export const IIVolumeLow = (props: any) => <VolumeX {...props} strokeWidth={strokeWidth}/>;
 export const IIVolumeOff = (props: any) => <Volume1 {...props} strokeWidth={strokeWidth}/>;
 export const IIVolumeHigh = (props: any) => <Volume2 {...props} strokeWidth={strokeWidth}/>;
-export const IIList = (props: any) => <ListMinus {...props} strokeWidth={strokeWidth}/>;
\ No newline at end of file
+export const IIList = (props: any) => <ListMinus {...props} strokeWidth={strokeWidth}/>;
+export const IIWriteBack = (props: any) => <CornerDownLeft {...props} strokeWidth={strokeWidth}/>;
\ No newline at end of file
@@ -0,0 +1,74 @@
+/** 语音识别使用的采样率，whisper 系列模型要求 16kHz */
+const SAMPLE_RATE = 16000;
+
+/** 录制麦克风音频，结束时返回 16kHz 单声道 wav */
+export class AudioRecorder {
+	private recorder?: MediaRecorder;
+	private chunks: Blob[] = [];
+
+	async start() {
+		if (this.recorder?.state === "recording") return;
+		const stream = await navigator.mediaDevices.getUserMedia({ audio: true });
+		this.chunks = [];
+		this.recorder = new MediaRecorder(stream);
+		this.recorder.ondataavailable = (e) => this.chunks.push(e.data);
+		this.recorder.start();
+	}
+
+	async stop(): Promise<Uint8Array | undefined> {
+		const recorder = this.recorder;
+		if (!recorder || recorder.state === "inactive") return;
+		const stopped = new Promise((resolve) => {
+			recorder.onstop = resolve;
+		});
+		recorder.stop();
+		await stopped;
+		for (const track of recorder.stream.getTracks()) track.stop();
+		this.recorder = undefined;
+
+		const blob = new Blob(this.chunks, { type: recorder.mimeType });
+		if (blob.size === 0) return;
+		return toWav(await blob.arrayBuffer());
+	}
+}
+
+/** 解码录音并重采样为 16kHz 单声道 16 位 wav */
+async function toWav(data: ArrayBuffer): Promise<Uint8Array> {
+	const decoded = await new AudioContext().decodeAudioData(data);
+	const offline = new OfflineAudioContext(
+		1,
+		Math.ceil(decoded.duration * SAMPLE_RATE),
+		SAMPLE_RATE,
+	);
+	const source = offline.createBufferSource();
+	source.buffer = decoded;
+	source.connect(offline.destination);
+	source.start();
+	const samples = (await offline.startRendering()).getChannelData(0);
+
+	const buffer = new ArrayBuffer(44 + samples.length * 2);
+	const view = new DataView(buffer);
+	const writeString = (offset: number, value: string) => {
+		for (let i = 0; i < value.length; i++) {
+			view.setUint8(offset + i, value.charCodeAt(i));
+		}
+	};
+	writeString(0, "RIFF");
+	view.setUint32(4, 36 + samples.length * 2, true);
+	writeString(8, "WAVE");
+	writeString(12, "fmt ");
+	view.setUint32(16, 16, true);
+	view.setUint16(20, 1, true); // PCM
+	view.setUint16(22, 1, true); // 单声道
+	view.setUint32(24, SAMPLE_RATE, true);
+	view.setUint32(28, SAMPLE_RATE * 2, true);
+	view.setUint16(32, 2, true);
+	view.setUint16(34, 16, true);
+	writeString(36, "data");
+	view.setUint32(40, samples.length * 2, true);
+	samples.forEach((sample, i) => {
+		const clamped = Math.max(-1, Math.min(1, sample));
+		view.setInt16(44 + i * 2, clamped * 0x7fff, true);
+	});
+	return new Uint8Array(buffer);
+}
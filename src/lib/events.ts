@@ -6,20 +6,71 @@ export const EVENT_NAMES = {
   BUBBLE_AUTO_SPEAK: "BUBBLE_AUTO_SPEAK",
   BUBBLE_CLEAN: "BUBBLE_CLEAN",
   AI_RESPONSE: "AI_RESPONSE",
+  AI_RESPONSE_CHUNK: "AI_RESPONSE_CHUNK",
+  BUBBLE_STREAM: "BUBBLE_STREAM",
   AI_ERROR: "AI_ERROR",
+  DICTIONARY_RESULT: "DICTIONARY_RESULT",
+  CLIPBOARD_WATCH_CHANGED: "CLIPBOARD_WATCH_CHANGED",
+  OPEN_HISTORY: "OPEN_HISTORY",
+  SETTINGS_CHANGED: "SETTINGS_CHANGED",
+  PROFILE_CHANGED: "PROFILE_CHANGED",
+  TTS_STATE_CHANGED: "TTS_STATE_CHANGED",
+  RECORDING_START: "RECORDING_START",
+  RECORDING_STOP: "RECORDING_STOP",
   // to Tauri backend
   PAGE_LOADED: "PAGE_LOADED",
   // CMD events
   REGISTER_HOTKEY: "register_hotkey_okey_ai",
-  TOGGLE_AUTO_CLOSE_WINDOW: "toggle_auto_close_translate",
-  GET_AUTO_CLOSE_WINDOW_STATE: "get_auto_close_translate_state",
+  REGISTER_HOTKEY_WRITE_BACK: "register_hotkey_write_back",
+  REGISTER_ACTION_HOTKEY: "register_hotkey",
+  UNREGISTER_ACTION_HOTKEY: "unregister_hotkey",
+  LIST_ACTIONS: "list_actions",
+  LIST_QUICK_ACTIONS: "list_quick_actions",
+  RUN_QUICK_ACTION: "run_quick_action",
+  RESIZE_TRANSLATE_BUBBLE: "resize_translate_bubble",
+  LIST_CUSTOM_ACTIONS: "list_custom_actions",
+  SAVE_CUSTOM_ACTION: "save_custom_action",
+  DELETE_CUSTOM_ACTION: "delete_custom_action",
+  TOGGLE_TRANSLATE_PINNED: "toggle_translate_pinned",
+  GET_TRANSLATE_PINNED_STATE: "get_translate_pinned_state",
   DETECT_LANGUAGE: "detect_language",
   GET_AUTO_SPEAK_STATE: "get_auto_speak_state",
   COMMAND_WINDOW_TRANSLATE_SHOW: "command_window_translate_show",
   TOGGLE_AUTO_SPEAK: "toggle_auto_speak",
+  GET_SETTINGS: "get_settings",
+  SET_SETTINGS: "set_settings",
+  LIST_PROFILES: "list_profiles",
+  CREATE_PROFILE: "create_profile",
+  DELETE_PROFILE: "delete_profile",
+  SWITCH_PROFILE: "switch_profile",
+  EXPORT_PROFILE: "export_profile",
+  IMPORT_PROFILE: "import_profile",
+  LIST_PROVIDERS_MISSING_KEY: "list_providers_missing_key",
+  TTS_SPEAK: "tts_speak",
+  TTS_STOP: "tts_stop",
+  TTS_PAUSE: "tts_pause",
+  TTS_RESUME: "tts_resume",
+  TTS_STATE: "tts_state",
+  TTS_CLEAR_CACHE: "tts_clear_cache",
+  GET_TTS_CONFIG: "get_tts_config",
+  SET_TTS_CONFIG: "set_tts_config",
+  TRANSCRIBE_AUDIO: "transcribe_audio",
+  RECORDING_WINDOW_READY: "recording_window_ready",
+  GET_STT_CONFIG: "get_stt_config",
+  SET_STT_CONFIG: "set_stt_config",
+  GET_TOOLS_CONFIG: "get_tools_config",
+  SET_TOOLS_CONFIG: "set_tools_config",
+  GET_DICTIONARY_CONFIG: "get_dictionary_config",
+  SET_DICTIONARY_CONFIG: "set_dictionary_config",
+  LIST_OFFLINE_DICTIONARIES: "list_offline_dictionaries",
+  TOGGLE_CLIPBOARD_WATCH: "toggle_clipboard_watch",
+  GET_CLIPBOARD_WATCH_STATE: "get_clipboard_watch_state",
   CLOSE_MAIN_WINDOW: "close_main_window",
   GET_HISTORIES: "get_histories",
   CHAT_STREAM: "chat_stream",
+  WRITE_BACK: "write_back",
+  GET_GESTURE_CONFIG: "get_gesture_config",
+  SET_GESTURE_CONFIG: "set_gesture_config",
 } as const;
 
 // Type for event names to provide type safety
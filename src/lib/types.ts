@@ -2,14 +2,84 @@
  * TypeScript interface definition for ChatMessage
  * Corresponds to the Rust struct in src-tauri/src/types.rs
  */
+export type ImageSource = { url: string } | { path: string };
+
+/** 消息内容中的一段，图片可以是网络地址、data URL 或本地文件 */
+export type ContentPart =
+	| { type: "text"; text: string }
+	| { type: "image"; source: ImageSource; detail?: "auto" | "low" | "high" };
+
+/** 纯文本消息为字符串，图文消息为分段列表 */
+export type MessageContent = string | ContentPart[];
+
+export interface ToolCall {
+	id: string;
+	type: "function";
+	function: { name: string; arguments: string };
+}
+
 export interface ChatMessage {
-	role: "system" | "user" | "assistant";
-	content: string;
+	role: "system" | "user" | "assistant" | "tool";
+	content: MessageContent;
 	raw?: string;
+	/** 助手要求执行的工具调用 */
+	tool_calls?: ToolCall[];
+	/** 工具结果对应的调用 */
+	tool_call_id?: string;
+	/** 推理模型的思考过程，只用于显示 */
+	reasoning?: string;
+}
+
+/** 内置快捷动作，对应 src-tauri/src/utils/quick_actions.rs */
+export interface QuickActionInfo {
+	id: string;
+	label: string;
+}
+
+/** 分块翻译时按原文顺序推送的译文，对应 src-tauri/src/utils/translation_manager.rs */
+export interface TranslatedChunk {
+	index: number;
+	total: number;
+	/** 译文，已附带原文中的分隔空白；翻译失败的分块为提示和原文 */
+	content: string;
 }
 
 export interface ChatMessageHistory {
   messages: ChatMessage[];
+  /** 原文来自截图识别时，截图文件的路径 */
+  image?: string;
+}
+
+/** 单词查询结果，对应 src-tauri/src/my_dictionary/entry.rs */
+export interface DictionaryEntry {
+	word: string;
+	phonetics: { region?: string; ipa: string }[];
+	senses: {
+		partOfSpeech: string;
+		definition: string;
+		examples: { text: string; translation?: string }[];
+	}[];
+	forms: { kind: string; word: string }[];
+}
+
+export interface DictionaryResult {
+	/** 会话中对应助手消息的文本 */
+	content: string;
+	/** 单行摘要，气泡中显示 */
+	summary: string;
+	entry: DictionaryEntry;
+}
+
+/** 单词查询配置，offline_dir 为离线词典目录 */
+export interface DictionaryConfig {
+	enabled: boolean;
+	offline_dir?: string | null;
+}
+
+/** 已加载的离线词典 */
+export interface DictionaryInfo {
+	name: string;
+	wordCount: number;
 }
 
 export const AutoSpeakState = {
@@ -19,3 +89,10 @@ export const AutoSpeakState = {
 } as const;
 
 export type AutoSpeakState = typeof AutoSpeakState[keyof typeof AutoSpeakState];
+
+/** 持久化的用户设置，变化时后端广播 SETTINGS_CHANGED */
+export type AppSettings = {
+	pinned: boolean;
+	auto_close_bubble: boolean;
+	auto_speak: AutoSpeakState;
+};
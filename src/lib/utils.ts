@@ -4,12 +4,47 @@ import { type ClassValue, clsx } from "clsx";
 import { twMerge } from "tailwind-merge";
 import type { GlobalConfig } from "@/@types";
 import { EVENT_NAMES } from "./events";
+import type { MessageContent } from "./types";
 export function cn(...inputs: ClassValue[]) {
 	return twMerge(clsx(inputs));
 }
 
+/** 消息的文本内容，多段文本用换行连接，忽略图片 */
+export function messageText(content: MessageContent | undefined): string {
+	if (content === undefined || typeof content === "string") return content ?? "";
+	return content
+		.flatMap((part) => (part.type === "text" ? [part.text] : []))
+		.join("\n");
+}
+
+/** 消息中可直接显示的图片地址 */
+export function messageImages(content: MessageContent | undefined): string[] {
+	if (content === undefined || typeof content === "string") return [];
+	return content.flatMap((part) =>
+		part.type === "image" && "url" in part.source ? [part.source.url] : [],
+	);
+}
+
+/** 按序号拼接已收到的分块译文，尚未收到的分块为空 */
+export function joinChunks(chunks: string[], total: number): string {
+	return Array.from({ length: total }, (_, index) => chunks[index] ?? "").join("");
+}
+
+/** 优先使用后端配置的语音合成，未配置或失败时由页面朗读 */
 export async function speak(text: string) {
 	console.log("speak", text);
+	const spoken = await invoke<boolean>(EVENT_NAMES.TTS_SPEAK, { text }).catch(
+		(e) => {
+			console.error("朗读失败", e);
+			return false;
+		},
+	);
+	if (!spoken) {
+		await speakInWebview(text);
+	}
+}
+
+export async function speakInWebview(text: string) {
 	if ("speechSynthesis" in window) {
 		const utterance = new SpeechSynthesisUtterance(text);
 		utterance.rate = 1.0; // 语速
@@ -1,12 +1,43 @@
 import { createFileRoute } from "@tanstack/react-router";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect } from "react";
 import AudioRecording from "@/components/AudioRecording";
+import { AudioRecorder } from "@/lib/audio";
+import { EVENT_NAMES } from "@/lib/events";
 import { cn } from "@/lib/utils";
 
 export const Route = createFileRoute("/input_method_editor/")({
 	component: RouteComponent,
 });
 
+const recorder = new AudioRecorder();
+
 function RouteComponent() {
+	useEffect(() => {
+		const startRecording = () => {
+			recorder.start().catch((e) => console.error("无法录音", e));
+		};
+		// 长按触发键时录音，松开后识别并翻译
+		const unlistenStart = listen(EVENT_NAMES.RECORDING_START, startRecording);
+		const unlistenStop = listen(EVENT_NAMES.RECORDING_STOP, async () => {
+			const audio = await recorder.stop();
+			if (!audio) return;
+			invoke<string>(EVENT_NAMES.TRANSCRIBE_AUDIO, audio, {
+				headers: { translate: "true" },
+			}).catch((e) => console.error("语音识别失败", e));
+		});
+		// 监听注册好后通知后端，页面就绪前已经开始长按时立即录音
+		Promise.all([unlistenStart, unlistenStop])
+			.then(() => invoke<boolean>(EVENT_NAMES.RECORDING_WINDOW_READY))
+			.then((recording) => recording && startRecording())
+			.catch((e) => console.error("录音窗口初始化失败", e));
+		return () => {
+			unlistenStart.then((fn) => fn());
+			unlistenStop.then((fn) => fn());
+		};
+	}, []);
+
 	return (
 		<div className={cn("h-full", "flex items-center ")}>
 			<div
@@ -8,7 +8,9 @@ import type React from "react";
 import { useEffect, useRef, useState } from "react";
 import AutoSpeakVolume from "@/components/AutoSpeakVolume";
 import Copyed from "@/components/Copyed";
+import DictionaryCard from "@/components/DictionaryCard";
 import HotKey from "@/components/HotKey";
+import ReasoningBlock from "@/components/ReasoningBlock";
 import { Button } from "@/components/ui/button";
 import {
 	DropdownMenu,
@@ -29,20 +31,41 @@ import {
 	TooltipTrigger,
 } from "@/components/ui/tooltip";
 import { EVENT_NAMES } from "@/lib/events";
-import { AutoSpeakState, type ChatMessage } from "@/lib/types";
-import { cn, get_global_config, speak } from "@/lib/utils";
+import {
+	type AppSettings,
+	AutoSpeakState,
+	type ChatMessage,
+	type DictionaryEntry,
+	type DictionaryResult,
+	type TranslatedChunk,
+} from "@/lib/types";
+import { cn, get_global_config, joinChunks, messageImages, messageText, speak } from "@/lib/utils";
 import { s_Selected } from "@/store";
 import { IIArrowUp, IIPin, IIAdd, IIVolumeHigh, IICancel } from "@/components/icons";
 import { Histories } from "@/components/Histories";
 import { Store } from "@tanstack/react-store";
 
 export const s_ChatList = new Store<ChatMessage[]>([]);
+// 固定模式下已结束的会话，新的翻译追加在后面
+export const s_Feed = new Store<ChatMessage[][]>([]);
+export const s_Pinned = new Store(false);
+// 单词查询结果，按会话中助手消息的文本索引
+export const s_Dictionary = new Store<Record<string, DictionaryEntry>>({});
+
+/** 收到的消息是否属于新的会话：比当前会话短，或第一条用户消息不同 */
+function isNewSession(live: ChatMessage[], next: ChatMessage[]) {
+	if (!live.some((chat) => chat.role === "assistant")) return false;
+	const firstUser = (list: ChatMessage[]) =>
+		messageText(list.find((chat) => chat.role === "user")?.content);
+	return next.length < live.length || firstUser(next) !== firstUser(live);
+}
 export const Route = createFileRoute("/translate/")({
 	component: RouteComponent,
 });
 
 type StreamEvent =
 	| { event: "chunk"; data: { content: string } }
+	| { event: "reasoning"; data: { content: string } }
 	| { event: "done"; data?: unknown }
 	| { event: "error"; data: { message: string } };
 
@@ -155,22 +178,29 @@ function Inputer({ className }: {
 
 	const handleStream = async (chatMessage: ChatMessage) => {
 		let accumulated = "";
+		let reasoning = "";
+		// 更新正在生成的助手消息，还没有时追加一条
+		const updateReply = (reply: Partial<ChatMessage>) => {
+			s_ChatList.setState((list) => {
+				if (list.at(-1)?.role !== "assistant") {
+					return [...list, { role: "assistant", content: "", ...reply }];
+				}
+				const next = [...list];
+				next[next.length - 1] = { ...next[next.length - 1]!, ...reply };
+				return next;
+			});
+		};
 		const channel = new Channel<StreamEvent>();
 		channel.onmessage = (message) => {
 			switch (message.event) {
 				case "chunk": {
 					accumulated += message.data?.content ?? "";
-					s_ChatList.setState((list) => {
-						if (list.at(-1)?.role !== "assistant") {
-							return [...list, { role: "assistant", content: accumulated }];
-						}
-						const next = [...list];
-						next[next.length - 1] = {
-							...next[next.length - 1]!,
-							content: accumulated,
-						};
-						return next;
-					});
+					updateReply({ content: accumulated });
+					break;
+				}
+				case "reasoning": {
+					reasoning += message.data?.content ?? "";
+					updateReply({ reasoning });
 					break;
 				}
 				case "error": {
@@ -275,8 +305,11 @@ function Inputer({ className }: {
 
 function ChatList({ className }: { className?: string; }) {
 	const chatList = useStore(s_ChatList, (state) => state);
+	const feed = useStore(s_Feed, (state) => state);
 	const lastItem = chatList.at(-1)
 	const rest = chatList.slice(0, -1);
+	// 分块翻译已收到的译文，按序号存放
+	const chunks = useRef<string[]>([]);
 	useEffect(() => {
 		const unlistenResponse = listen<ChatMessage[]>(
 			EVENT_NAMES.AI_RESPONSE,
@@ -285,13 +318,39 @@ function ChatList({ className }: { className?: string; }) {
 				if (chat?.raw && chat.role === "user") {
 					s_Selected.setState({
 						text: chat.raw,
-						raw: chat.content,
+						raw: messageText(chat.content),
 					});
 				}
+				const live = s_ChatList.state;
+				if (s_Pinned.state && isNewSession(live, payload)) {
+					s_Feed.setState((feed) => [...feed, live]);
+				}
 				s_ChatList.setState(payload);
+				chunks.current = [];
 				console.log(payload)
 			},
 		);
+		// 分块译文按原文顺序拼接，作为尚未完成的译文显示在会话末尾
+		const unlistenChunk = listen<TranslatedChunk>(
+			EVENT_NAMES.AI_RESPONSE_CHUNK,
+			({ payload }) => {
+				chunks.current[payload.index] = payload.content;
+				const partial: ChatMessage = {
+					role: "assistant",
+					content: joinChunks(chunks.current, payload.total),
+				};
+				s_ChatList.setState((list) => [
+					...(list.at(-1)?.role === "assistant" ? list.slice(0, -1) : list),
+					partial,
+				]);
+			},
+		);
+		const unlistenDictionary = listen<DictionaryResult>(
+			EVENT_NAMES.DICTIONARY_RESULT,
+			({ payload }) => {
+				s_Dictionary.setState((entries) => ({ ...entries, [payload.content]: payload.entry }));
+			},
+		);
 		const unlistenError = listen<string>(EVENT_NAMES.AI_ERROR, (event) => {
 			const errorPayload: ChatMessage = {
 				role: "assistant",
@@ -302,14 +361,23 @@ function ChatList({ className }: { className?: string; }) {
 		emit(EVENT_NAMES.PAGE_LOADED, { ok: true });
 		return () => {
 			unlistenResponse.then((fn) => fn());
+			unlistenChunk.then((fn) => fn());
+			unlistenDictionary.then((fn) => fn());
 			unlistenError.then((fn) => fn());
 		};
 	}, []);
 	return (
 		<div role="none" className={cn(className, "max-w-screen flex-coh")}>
+			{feed.map((session, sessionIndex) => (
+				<div key={`feed-${sessionIndex}`} className="border-b mb-2">
+					{session.map((chat, index) => (
+						<MessageItem className="px-2.5 mb-2" key={`feed-${sessionIndex}-${messageText(chat.content)}-${index}`} chat={chat} />
+					))}
+				</div>
+			))}
 			{rest.map((chat, index) => {
 				return (
-					<MessageItem className="px-2.5 mb-2" key={`chat-${chat.content}-${index}`} chat={chat} />
+					<MessageItem className="px-2.5 mb-2" key={`chat-${messageText(chat.content)}-${index}`} chat={chat} />
 				);
 			})}
 			{lastItem && lastItem.role === "assistant" && <MessageItem className="px-2 mb-2" chat={lastItem} />}
@@ -321,6 +389,9 @@ function ChatList({ className }: { className?: string; }) {
 function MessageItem({ chat, className }: { chat: ChatMessage, className?: string }) {
 	const containerRef = useRef<HTMLDivElement>(null);
 	const isMouseInsideRef = useRef<boolean>(false);
+	const dictionary = useStore(s_Dictionary, (entries) =>
+		chat.role === "assistant" ? entries[messageText(chat.content)] : undefined,
+	);
 
 	function extractSelectedText() {
 		// 只在鼠标在当前组件内部时才处理
@@ -336,7 +407,7 @@ function MessageItem({ chat, className }: { chat: ChatMessage, className?: strin
 				if (containerRef.current.contains(range.commonAncestorContainer)) {
 					s_Selected.setState({
 						text: selectedText,
-						raw: chat.content,
+						raw: messageText(chat.content),
 					});
 				}
 			}
@@ -352,6 +423,9 @@ function MessageItem({ chat, className }: { chat: ChatMessage, className?: strin
 		// 鼠标移出时什么也不做，保留已选中的文本
 	}
 
+	// 工具调用的过程不显示
+	if (chat.role === "tool" || chat.tool_calls?.length) return null;
+
 	return (
 		<div
 			ref={containerRef}
@@ -362,12 +436,26 @@ function MessageItem({ chat, className }: { chat: ChatMessage, className?: strin
 			onMouseLeave={handleMouseLeave}
 		>
 			<div className="wrap-break-word ">
-				<Markdown className="mb-2">{chat.raw ?? chat.content}</Markdown>
+				{messageImages(chat.content).map((url) => (
+					<img key={url} src={url} alt="" className="mb-2 max-h-48 rounded-md" />
+				))}
+				{chat.reasoning && (
+					<ReasoningBlock
+						className="mb-2"
+						reasoning={chat.reasoning}
+						thinking={!messageText(chat.content)}
+					/>
+				)}
+				{dictionary ? (
+					<DictionaryCard className="mb-2" entry={dictionary} />
+				) : (
+					<Markdown className="mb-2">{chat.raw ?? messageText(chat.content)}</Markdown>
+				)}
 				<div className="flex items-center">
 					<Button size={"icon-sm"} variant={"ghost"}>
-						<Copyed text={chat.content} />
+						<Copyed text={messageText(chat.content)} />
 					</Button>
-					<Button size={"icon-sm"} variant={"ghost"} onClick={() => speak(chat.raw ?? chat.content)} >
+					<Button size={"icon-sm"} variant={"ghost"} onClick={() => speak(chat.raw ?? messageText(chat.content))} >
 						<IIVolumeHigh />
 					</Button>
 				</div>
@@ -439,11 +527,18 @@ function SelectedText({ onStream }: { onStream: (chatMessage: ChatMessage) => Pr
 }
 
 function PinWindow({ className }: { className?: string }) {
-	const [pin, setPin] = useState(false);
+	const pin = useStore(s_Pinned, (state) => state);
+	const setPin = (value: boolean) => s_Pinned.setState(value);
 	useEffect(() => {
-		invoke<boolean>(EVENT_NAMES.GET_AUTO_CLOSE_WINDOW_STATE).then((res) =>
+		invoke<boolean>(EVENT_NAMES.GET_TRANSLATE_PINNED_STATE).then((res) =>
 			setPin(res),
 		);
+		const unlisten = listen<AppSettings>(EVENT_NAMES.SETTINGS_CHANGED, ({ payload }) =>
+			setPin(payload.pinned),
+		);
+		return () => {
+			unlisten.then((fn) => fn());
+		};
 	}, []);
 	return (
 		<Button
@@ -451,7 +546,7 @@ function PinWindow({ className }: { className?: string }) {
 			variant="ghost"
 			className={cn(className)}
 			onClick={async () =>
-				setPin(await invoke<boolean>(EVENT_NAMES.TOGGLE_AUTO_CLOSE_WINDOW))
+				setPin(await invoke<boolean>(EVENT_NAMES.TOGGLE_TRANSLATE_PINNED))
 			}
 		>
 			<IIPin
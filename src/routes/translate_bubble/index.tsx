@@ -1,13 +1,13 @@
 import { createFileRoute } from "@tanstack/react-router";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { useEffect, useState } from "react";
+import { useEffect, useLayoutEffect, useRef, useState } from "react";
 import Copyed from "@/components/Copyed";
 import { Button } from "@/components/ui/button";
 import { EVENT_NAMES } from "@/lib/events";
-import { AutoSpeakState, type ChatMessage } from "@/lib/types";
-import { cn, speak } from "@/lib/utils";
-import { IIGripVertical, IIArrowExpand, IIVolumeHigh } from "@/components/icons";
+import type { ChatMessage, DictionaryResult, QuickActionInfo, TranslatedChunk } from "@/lib/types";
+import { cn, joinChunks, messageText, speak, speakInWebview } from "@/lib/utils";
+import { IIGripVertical, IIArrowExpand, IIVolumeHigh, IIWriteBack, IIList } from "@/components/icons";
 
 export const Route = createFileRoute("/translate_bubble/")({
 	component: RouteComponent,
@@ -15,37 +15,74 @@ export const Route = createFileRoute("/translate_bubble/")({
 
 function RouteComponent() {
 	const [chatHistory, setChatHistory] = useState<ChatMessage[]>();
+	const [streaming, setStreaming] = useState<string>();
+	const [dictionary, setDictionary] = useState<DictionaryResult>();
+	const [error, setError] = useState<string>();
+	const [quickActions, setQuickActions] = useState<QuickActionInfo[]>([]);
+	const [actionsOpen, setActionsOpen] = useState(false);
+	const contentRef = useRef<HTMLDivElement>(null);
+	// 展开动作列表前的气泡大小，收起时恢复
+	const sizeBeforeActions = useRef<{ width: number; height: number } | null>(null);
+	// 分块翻译已收到的译文，按序号存放
+	const chunks = useRef<string[]>([]);
 	useEffect(() => {
-		const unlistenClean = listen<ChatMessage[]>(EVENT_NAMES.BUBBLE_CLEAN, () =>
-			setChatHistory(undefined),
+		invoke<QuickActionInfo[]>(EVENT_NAMES.LIST_QUICK_ACTIONS)
+			.then(setQuickActions)
+			.catch((e) => console.error("获取快捷动作失败", e));
+		const unlistenClean = listen<ChatMessage[]>(EVENT_NAMES.BUBBLE_CLEAN, () => {
+			setChatHistory(undefined);
+			setStreaming(undefined);
+			setDictionary(undefined);
+			setError(undefined);
+			setActionsOpen(false);
+			chunks.current = [];
+		});
+		const unlistenDictionary = listen<DictionaryResult>(
+			EVENT_NAMES.DICTIONARY_RESULT,
+			({ payload }) => {
+				setDictionary(payload);
+			},
 		);
-		const unlistenSpeak = listen<ChatMessage[]>(
+		// 是否朗读由后端按自动朗读设置决定，这里只负责页面朗读
+		const unlistenSpeak = listen<string>(
 			EVENT_NAMES.BUBBLE_AUTO_SPEAK,
 			({ payload }) => {
-				const chat = payload.at(-1);
-				const content = chat?.raw ?? chat?.content ?? "";
-				invoke<AutoSpeakState>(EVENT_NAMES.GET_AUTO_SPEAK_STATE).then((res) => {
-					const isSingleWord = content.trim().split(/\s+/).length === 1;
-					if (
-						(res === AutoSpeakState.Single && isSingleWord) ||
-						(res === AutoSpeakState.All && content.trim().length > 0)
-					) {
-						speak(content);
-					}
-				});
+				speakInWebview(payload);
 			},
 		);
 		const unlistenResponse = listen<ChatMessage[]>(
 			EVENT_NAMES.AI_RESPONSE,
 			({ payload }) => {
 				setChatHistory(payload);
+				setStreaming(undefined);
+				setError(undefined);
+				chunks.current = [];
+			},
+		);
+		// 分块译文按原文顺序拼接后显示，气泡大小由后端调整
+		const unlistenChunk = listen<TranslatedChunk>(
+			EVENT_NAMES.AI_RESPONSE_CHUNK,
+			({ payload }) => {
+				chunks.current[payload.index] = payload.content;
+				setStreaming(joinChunks(chunks.current, payload.total));
+			},
+		);
+		const unlistenStream = listen<string>(
+			EVENT_NAMES.BUBBLE_STREAM,
+			({ payload }) => {
+				setStreaming(payload);
 			},
 		);
-		const unlistenError = listen<string>(EVENT_NAMES.AI_ERROR, () => { });
+		const unlistenError = listen<string>(EVENT_NAMES.AI_ERROR, ({ payload }) => {
+			setError(payload);
+		});
 		return () => {
 			unlistenClean.then((fn) => fn());
+			unlistenDictionary.then((fn) => fn());
 			unlistenSpeak.then((fn) => fn());
 			unlistenResponse.then((fn) => fn());
+			unlistenStream.then((fn) => fn());
+			unlistenChunk.then((fn) => fn());
 			unlistenError.then((fn) => fn());
 		};
 	}, []);
@@ -53,8 +90,45 @@ function RouteComponent() {
 		let item = chatHistory?.at(-1);
 		return item?.role === "assistant" ? item : undefined
 	})()
+	// 最后一条还不是译文时，翻译仍在进行或正在流式输出
+	const responding = !chat;
+	// 展开动作列表后按内容高度放大气泡
+	useLayoutEffect(() => {
+		if (!actionsOpen || !contentRef.current) return;
+		invoke(EVENT_NAMES.RESIZE_TRANSLATE_BUBBLE, {
+			width: window.innerWidth,
+			height: contentRef.current.scrollHeight,
+		});
+	}, [actionsOpen]);
+	const closeActions = () => {
+		setActionsOpen(false);
+		if (sizeBeforeActions.current) {
+			invoke(EVENT_NAMES.RESIZE_TRANSLATE_BUBBLE, sizeBeforeActions.current);
+		}
+	};
+	const toggleActions = () => {
+		if (actionsOpen) {
+			closeActions();
+			return;
+		}
+		sizeBeforeActions.current = { width: window.innerWidth, height: window.innerHeight };
+		setActionsOpen(true);
+	};
+	// 对当前原文执行动作，结果由后端推送到气泡
+	const runQuickAction = (action: QuickActionInfo) => {
+		const source = chatHistory?.at(-2);
+		const text = source?.raw ?? messageText(source?.content);
+		if (!text) return;
+		closeActions();
+		invoke(EVENT_NAMES.RUN_QUICK_ACTION, { action: action.id, text }).catch((e) =>
+			setError(String(e)),
+		);
+	};
+	// 单词查询结果显示为单行摘要
+	const summary =
+		chat && dictionary?.content === messageText(chat.content) ? dictionary.summary : undefined;
 	return (
-		<div className=" h-full p-px ">
+		<div className=" h-full p-px " ref={contentRef}>
 			<div
 				data-tauri-drag-region
 				className={cn(
@@ -89,8 +163,8 @@ function RouteComponent() {
 							/>
 						</Button>
 					</div>
-					<div className="flex overflow-hidden text-nowrap flex-1">
-						<span>{chat ? (chat?.raw ?? chat?.content) : "..."} </span>
+					<div className="flex overflow-hidden whitespace-pre-wrap break-words flex-1 font-bubble">
+						<span>{error ?? (chat ? (summary ?? chat?.raw ?? messageText(chat?.content)) : (streaming || "..."))} </span>
 						{chat?.content ? (
 							<span
 								className="truncate text-transparent selection:bg-transparent cursor-grab hover:cursor-grabbing"
@@ -109,7 +183,7 @@ function RouteComponent() {
 						size={"icon-sm"}
 						variant={"ghost"}
 					>
-						<Copyed text={chat?.content} />
+						<Copyed text={messageText(chat?.content)} />
 					</Button>
 					<Button
 						className={cn("")}
@@ -117,11 +191,32 @@ function RouteComponent() {
 						variant={"ghost"}
 						onClick={() => {
 							let chat_user = chatHistory?.at(-2);
-							speak(chat_user?.raw ?? chat_user?.content ?? "")
+							speak(chat_user?.raw ?? messageText(chat_user?.content))
 						}}
 					>
 						<IIVolumeHigh/>
 					</Button>
+					<Button
+						className={cn("")}
+						size={"icon-sm"}
+						variant={"ghost"}
+						disabled={responding}
+						onClick={() => {
+							// 成功时后端会隐藏气泡，失败时在气泡中显示原因
+							invoke(EVENT_NAMES.WRITE_BACK).catch((e) => setError(String(e)));
+						}}
+					>
+						<IIWriteBack/>
+					</Button>
+					<Button
+						className={cn("")}
+						size={"icon-sm"}
+						variant={"ghost"}
+						disabled={responding || quickActions.length === 0}
+						onClick={toggleActions}
+					>
+						<IIList/>
+					</Button>
 					<Button
 						className={cn("")}
 						size={"icon-sm"}
@@ -137,6 +232,27 @@ function RouteComponent() {
 					</Button>
 				</div>
 			</div>
+			{actionsOpen && (
+				<div
+					className={cn(
+						"mt-px p-0.5",
+						"bg-background",
+						"border rounded-md",
+						"flex flex-wrap gap-0.5",
+					)}
+				>
+					{quickActions.map((action) => (
+						<Button
+							key={action.id}
+							size={"sm"}
+							variant={"ghost"}
+							onClick={() => runQuickAction(action)}
+						>
+							{action.label}
+						</Button>
+					))}
+				</div>
+			)}
 		</div>
 	);
 }